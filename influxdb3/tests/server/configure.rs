@@ -1487,11 +1487,25 @@ async fn api_v3_configure_token_delete() {
         .unwrap();
     info!(?delete_result, "test: result running the token delete");
     assert_eq!(delete_result.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(
+        delete_result.json::<Value>().await.unwrap(),
+        json!({
+            "code": "catalog/cannot_delete_operator_token",
+            "error": "cannot delete operator token",
+        })
+    );
 
     // create admin token again - this will fail as operator token already exists
     let result = client.post(&create_url).send().await.unwrap();
     info!(?result, "test: result running the create token");
     assert_eq!(result.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        result.json::<Value>().await.unwrap(),
+        json!({
+            "code": "catalog/token_name_already_exists",
+            "error": "token name already exists, _admin",
+        })
+    );
 }
 
 #[test_log::test(tokio::test)]
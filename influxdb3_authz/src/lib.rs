@@ -240,6 +240,8 @@ pub struct TokenInfo {
     pub expiry_millis: i64,
     // should be used only in enterprise
     pub permissions: Vec<Permission>,
+    /// Whether the token has been disabled, in which case it cannot be used to authenticate
+    pub disabled: bool,
 }
 
 impl TokenInfo {
@@ -261,6 +263,7 @@ impl TokenInfo {
             updated_at: None,
             updated_by: None,
             permissions: Default::default(),
+            disabled: false,
         }
     }
 
@@ -279,6 +282,13 @@ impl TokenInfo {
     pub fn set_permissions(&mut self, all_permissions: Vec<Permission>) {
         self.permissions = all_permissions;
     }
+
+    /// Check if any of this token's permissions grant the given access request
+    pub fn can(&self, access_request: &AccessRequest) -> bool {
+        self.permissions
+            .iter()
+            .any(|permission| permission.allows(access_request))
+    }
 }
 
 // common types
@@ -324,6 +334,117 @@ pub enum Actions {
     Wildcard,
 }
 
+impl Permission {
+    /// Check if this permission grants the given access request
+    pub fn allows(&self, access_request: &AccessRequest) -> bool {
+        match access_request {
+            AccessRequest::Admin => {
+                self.resource_type == ResourceType::Wildcard
+                    && self.resource_identifier == ResourceIdentifier::Wildcard
+                    && self.actions == Actions::Wildcard
+            }
+            AccessRequest::Database(db_id, DatabaseActions(requested)) => {
+                let resource_matches = match &self.resource_identifier {
+                    ResourceIdentifier::Database(db_ids) => db_ids.contains(db_id),
+                    ResourceIdentifier::Wildcard => true,
+                    ResourceIdentifier::Token(_) => false,
+                };
+                let actions_match = match self.actions {
+                    Actions::Database(DatabaseActions(granted)) => {
+                        granted & requested == *requested
+                    }
+                    Actions::Wildcard => true,
+                    Actions::Token(_) => false,
+                };
+                matches!(
+                    self.resource_type,
+                    ResourceType::Database | ResourceType::Wildcard
+                ) && resource_matches
+                    && actions_match
+            }
+            AccessRequest::Token(token_id, CrudActions(requested)) => {
+                let resource_matches = match &self.resource_identifier {
+                    ResourceIdentifier::Token(token_ids) => token_ids.contains(token_id),
+                    ResourceIdentifier::Wildcard => true,
+                    ResourceIdentifier::Database(_) => false,
+                };
+                let actions_match = match self.actions {
+                    Actions::Token(CrudActions(granted)) => granted & requested == *requested,
+                    Actions::Wildcard => true,
+                    Actions::Database(_) => false,
+                };
+                matches!(
+                    self.resource_type,
+                    ResourceType::Token | ResourceType::Wildcard
+                ) && resource_matches
+                    && actions_match
+            }
+        }
+    }
+
+    /// Check if this permission grants any actions on the given resource
+    ///
+    /// Wildcard permissions apply to every resource. When `resource` is a list of ids, the
+    /// permission applies if it covers any of them, and a `resource` of
+    /// [`ResourceIdentifier::Wildcard`] matches any permission for the `resource_type`.
+    pub fn applies_to(&self, resource_type: ResourceType, resource: &ResourceIdentifier) -> bool {
+        let type_matches = self.resource_type == ResourceType::Wildcard
+            || resource_type == ResourceType::Wildcard
+            || self.resource_type == resource_type;
+        let resource_matches = match (&self.resource_identifier, resource) {
+            (ResourceIdentifier::Wildcard, _) | (_, ResourceIdentifier::Wildcard) => true,
+            (ResourceIdentifier::Database(granted), ResourceIdentifier::Database(requested)) => {
+                requested.iter().any(|id| granted.contains(id))
+            }
+            (ResourceIdentifier::Token(granted), ResourceIdentifier::Token(requested)) => {
+                requested.iter().any(|id| granted.contains(id))
+            }
+            _ => false,
+        };
+        type_matches && resource_matches
+    }
+
+    /// Remove the actions granted by `other` from this permission
+    ///
+    /// Only applies when `other` is for the same resource type and identifier, otherwise this
+    /// permission is returned unchanged. Returns `None` if no actions remain. The result never
+    /// grants anything that this permission did not.
+    pub fn without(&self, other: &Permission) -> Option<Permission> {
+        if self.resource_type != other.resource_type
+            || self.resource_identifier != other.resource_identifier
+        {
+            return Some(self.clone());
+        }
+        let actions = match (self.actions, other.actions) {
+            (_, Actions::Wildcard) => return None,
+            (
+                Actions::Database(DatabaseActions(granted)),
+                Actions::Database(DatabaseActions(removed)),
+            ) => Actions::Database(DatabaseActions(granted & !removed)),
+            (Actions::Wildcard, Actions::Database(DatabaseActions(removed))) => {
+                Actions::Database(DatabaseActions(!removed))
+            }
+            (Actions::Token(CrudActions(granted)), Actions::Token(CrudActions(removed))) => {
+                Actions::Token(CrudActions(granted & !removed))
+            }
+            (Actions::Wildcard, Actions::Token(CrudActions(removed))) => {
+                Actions::Token(CrudActions(!removed))
+            }
+            // actions for a different kind of resource are not granted, so there is nothing to
+            // remove:
+            (actions, _) => actions,
+        };
+        match actions {
+            Actions::Database(DatabaseActions(0)) | Actions::Token(CrudActions(0)) => None,
+            actions => Some(Permission {
+                resource_type: self.resource_type,
+                resource_identifier: self.resource_identifier.clone(),
+                actions,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
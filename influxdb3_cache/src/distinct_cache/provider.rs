@@ -11,7 +11,7 @@ use influxdb3_catalog::{
     channel::CatalogUpdateReceiver,
     log::{
         CatalogBatch, DatabaseCatalogOp, DeleteDistinctCacheLog, DistinctCacheDefinition,
-        SoftDeleteTableLog,
+        SoftDeleteTableLog, UndeleteTableLog,
     },
 };
 use influxdb3_id::{DbId, DistinctCacheId, TableId};
@@ -293,6 +293,35 @@ fn background_catalog_update(
                         }) => {
                             provider.delete_caches_for_db_and_table(database_id, table_id);
                         }
+                        DatabaseCatalogOp::UndeleteDatabase(_) => {
+                            // the database's caches were removed when it was deleted:
+                            let Some(db) = provider.catalog.db_schema_by_id(&batch.database_id)
+                            else {
+                                continue;
+                            };
+                            for table_def in db.tables().filter(|table| !table.deleted) {
+                                for cache_def in table_def.distinct_caches.resource_iter() {
+                                    provider.create_from_catalog(batch.database_id, cache_def);
+                                }
+                            }
+                        }
+                        DatabaseCatalogOp::UndeleteTable(UndeleteTableLog {
+                            database_id,
+                            table_id,
+                            ..
+                        }) => {
+                            // the table's caches were removed when it was deleted:
+                            let Some(table_def) = provider
+                                .catalog
+                                .db_schema_by_id(database_id)
+                                .and_then(|db| db.table_definition_by_id(table_id))
+                            else {
+                                continue;
+                            };
+                            for cache_def in table_def.distinct_caches.resource_iter() {
+                                provider.create_from_catalog(*database_id, cache_def);
+                            }
+                        }
                         DatabaseCatalogOp::CreateDistinctCache(log) => {
                             provider.create_from_catalog(batch.database_id, log);
                         }
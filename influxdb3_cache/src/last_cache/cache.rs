@@ -10,6 +10,7 @@ use arrow::{
         RecordBatch, StringBuilder, StringDictionaryBuilder, TimestampNanosecondBuilder,
         UInt64Builder, new_null_array,
     },
+    compute::cast,
     datatypes::{
         DataType, Field as ArrowField, GenericStringType, Int32Type,
         SchemaBuilder as ArrowSchemaBuilder, SchemaRef as ArrowSchemaRef,
@@ -326,7 +327,9 @@ impl LastCache {
         let store = target.as_store_mut().expect(
             "cache target should be the actual store after iterating through all key columns",
         );
-        store.push(row);
+        if store.push(row) {
+            self.schema = update_last_cache_schema_for_column_types(&self.schema, &table_def);
+        }
         if self.should_update_schema_from_row(row) {
             let (schema, seen) = update_last_cache_schema_for_new_fields(
                 table_def,
@@ -772,19 +775,20 @@ impl LastCacheStore {
 
     /// Push a [`Row`] from the buffer into this cache
     ///
-    /// If new fields were added to the [`LastCacheStore`] by this push, the return will be a
-    /// list of those field's name and arrow [`DataType`], and `None` otherwise.
-    fn push(&mut self, row: &Row) {
+    /// Returns whether the type of any cached column was widened by this push, see
+    /// [`CacheColumnData::widen_for`].
+    fn push(&mut self, row: &Row) -> bool {
         if row.time <= self.last_time.timestamp_nanos() {
-            return;
+            return false;
         }
+        let mut widened = false;
         let mut seen = HashSet::<ColumnId>::new();
         match self.value_column_ids {
             Some(_) => {
                 for field in row.fields.iter() {
                     seen.insert(field.id);
                     if let Some(c) = self.cache.get_mut(&field.id) {
-                        c.push(&field.value);
+                        widened |= c.push(&field.value);
                     }
                 }
             }
@@ -796,7 +800,7 @@ impl LastCacheStore {
                     seen.insert(field.id);
                     if let Some(col) = self.cache.get_mut(&field.id) {
                         // In this case, the field already has an entry in the cache, so just push:
-                        col.push(&field.value);
+                        widened |= col.push(&field.value);
                     } else if !self.key_column_ids.contains(&field.id) {
                         // In this case, there is not an entry for the field in the cache, so if the
                         // value is not one of the key columns, then it is a new field being added.
@@ -826,6 +830,7 @@ impl LastCacheStore {
             self.instants.truncate(self.count);
         }
         self.last_time = Time::from_timestamp_nanos(row.time);
+        widened
     }
 
     /// Convert the contents of this cache into a arrow [`RecordBatch`]
@@ -875,6 +880,19 @@ impl LastCacheStore {
                 }
             }
         }
+        // values cached before a column's type was widened by another store in the cache have
+        // the column's old type:
+        let arrays = arrays
+            .into_iter()
+            .zip(schema.fields().iter())
+            .map(|(array, field)| {
+                if array.data_type() == field.data_type() {
+                    Ok(array)
+                } else {
+                    cast(&array, field.data_type())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         RecordBatch::try_new(schema, arrays)
     }
 
@@ -940,6 +958,29 @@ fn update_last_cache_schema_for_new_fields(
     (Arc::new(schema_builder.finish()), seen)
 }
 
+/// Update the types of a cache's value columns to the types they have in the table definition
+///
+/// This is used when a column's type was widened in the cache, after it was promoted in the table.
+fn update_last_cache_schema_for_column_types(
+    schema: &ArrowSchemaRef,
+    table_def: &TableDefinition,
+) -> ArrowSchemaRef {
+    let mut schema_builder = ArrowSchemaBuilder::new();
+    for field in schema.fields() {
+        match table_def.column_definition(field.name().as_str()) {
+            Some(def) if matches!(def.data_type, InfluxColumnType::Field(_)) => schema_builder
+                .push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(DataType::from(&def.data_type)),
+                ),
+            _ => schema_builder.push(Arc::clone(field)),
+        }
+    }
+    Arc::new(schema_builder.finish())
+}
+
 /// A column in a [`LastCache`]
 ///
 /// Stores its size so it can evict old data on push. Stores the time-to-live (TTL) in order
@@ -962,11 +1003,15 @@ impl CacheColumn {
     }
 
     /// Push [`FieldData`] from the buffer into this column
-    fn push(&mut self, field_data: &FieldData) {
+    ///
+    /// Returns whether the column's type was widened to hold the value.
+    fn push(&mut self, field_data: &FieldData) -> bool {
+        let widened = self.data.widen_for(field_data);
         self.data.push_front(field_data);
         if self.data.len() > self.size {
             self.data.truncate(self.size);
         }
+        widened
     }
 
     fn push_null(&mut self) {
@@ -1023,6 +1068,32 @@ impl CacheColumnData {
         }
     }
 
+    /// Convert the cached values if the given value has a wider type than the column
+    ///
+    /// This happens when an integer or unsigned integer column is promoted to a float, or a field
+    /// column is altered to a string, after values were cached for it. Returns whether the column
+    /// was converted.
+    fn widen_for(&mut self, field_data: &FieldData) -> bool {
+        fn to_strings<T: ToString>(buf: &VecDeque<Option<T>>) -> VecDeque<Option<String>> {
+            buf.iter().map(|v| v.as_ref().map(T::to_string)).collect()
+        }
+        let widened = match (field_data, &*self) {
+            (FieldData::Float(_), Self::I64(buf)) => {
+                Self::F64(buf.iter().map(|v| v.map(|v| v as f64)).collect())
+            }
+            (FieldData::Float(_), Self::U64(buf)) => {
+                Self::F64(buf.iter().map(|v| v.map(|v| v as f64)).collect())
+            }
+            (FieldData::String(_), Self::I64(buf)) => Self::String(to_strings(buf)),
+            (FieldData::String(_), Self::U64(buf)) => Self::String(to_strings(buf)),
+            (FieldData::String(_), Self::F64(buf)) => Self::String(to_strings(buf)),
+            (FieldData::String(_), Self::Bool(buf)) => Self::String(to_strings(buf)),
+            _ => return false,
+        };
+        *self = widened;
+        true
+    }
+
     /// Push a new element into the [`CacheColumn`]
     fn push_front(&mut self, field_data: &FieldData) {
         match (field_data, self) {
@@ -1036,6 +1107,13 @@ impl CacheColumnData {
             (FieldData::Integer(val), CacheColumnData::I64(buf)) => buf.push_front(Some(*val)),
             (FieldData::UInteger(val), CacheColumnData::U64(buf)) => buf.push_front(Some(*val)),
             (FieldData::Float(val), CacheColumnData::F64(buf)) => buf.push_front(Some(*val)),
+            // the column was promoted to a float:
+            (FieldData::Integer(val), CacheColumnData::F64(buf)) => {
+                buf.push_front(Some(*val as f64))
+            }
+            (FieldData::UInteger(val), CacheColumnData::F64(buf)) => {
+                buf.push_front(Some(*val as f64))
+            }
             (FieldData::Boolean(val), CacheColumnData::Bool(buf)) => buf.push_front(Some(*val)),
             data => panic!("invalid field data for cache column: {data:#?}"),
         }
@@ -52,7 +52,7 @@ mod tests {
     use datafusion::prelude::SessionContext;
     use indexmap::IndexMap;
     use influxdb3_catalog::{
-        catalog::Catalog,
+        catalog::{Catalog, CatalogArgs},
         log::{FieldDataType, LastCacheSize, LastCacheTtl},
     };
     use influxdb3_id::ColumnId;
@@ -1641,4 +1641,47 @@ mod tests {
             assert_batches_sorted_eq!(t.expected, &results);
         }
     }
+
+    #[tokio::test]
+    async fn promoted_column_in_cache() {
+        let writer =
+            TestWriter::new_with_args(CatalogArgs::default().with_type_promotion(true)).await;
+        let rows = writer
+            .write_lp_to_rows("cpu,host=a usage=1i\ncpu,host=b usage=2i", 1_000)
+            .await;
+
+        let table_def = writer.db_schema().table_definition("cpu").unwrap();
+        let mut cache = LastCache::new(CreateLastCacheArgs {
+            table_def: Arc::clone(&table_def),
+            count: LastCacheSize::new(10).unwrap(),
+            ttl: LastCacheTtl::default(),
+            key_columns: LastCacheKeyColumnsArg::SeriesKey,
+            value_columns: LastCacheValueColumnsArg::AcceptNew,
+        })
+        .unwrap();
+        for row in &rows {
+            cache.push(row, Arc::clone(&table_def));
+        }
+
+        // promote the column to a float, the values cached for either host are converted:
+        let rows = writer.write_lp_to_rows("cpu,host=a usage=2.5", 2_000).await;
+        let table_def = writer.db_schema().table_definition("cpu").unwrap();
+        for row in &rows {
+            cache.push(row, Arc::clone(&table_def));
+        }
+
+        let batches = cache.to_record_batches(table_def, &predicates([])).unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+-------+-----------------------------+",
+                "| host | usage | time                        |",
+                "+------+-------+-----------------------------+",
+                "| a    | 1.0   | 1970-01-01T00:00:00.000001Z |",
+                "| a    | 2.5   | 1970-01-01T00:00:00.000002Z |",
+                "| b    | 2.0   | 1970-01-01T00:00:00.000001Z |",
+                "+------+-------+-----------------------------+",
+            ],
+            &batches
+        );
+    }
 }
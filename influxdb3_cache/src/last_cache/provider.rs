@@ -8,7 +8,7 @@ use influxdb3_catalog::{
     channel::CatalogUpdateReceiver,
     log::{
         CatalogBatch, DatabaseCatalogOp, DeleteLastCacheLog, LastCacheDefinition,
-        LastCacheValueColumnsDef, SoftDeleteTableLog,
+        LastCacheValueColumnsDef, SoftDeleteTableLog, UndeleteTableLog,
     },
 };
 use influxdb3_id::{DbId, LastCacheId, TableId};
@@ -356,6 +356,32 @@ fn background_catalog_update(
                         }) => {
                             provider.delete_caches_for_table(&batch.database_id, table_id);
                         }
+                        DatabaseCatalogOp::UndeleteDatabase(_) => {
+                            // the database's caches were removed when it was deleted:
+                            let Some(db) = provider.catalog.db_schema_by_id(&batch.database_id)
+                            else {
+                                continue;
+                            };
+                            for table_def in db.tables().filter(|table| !table.deleted) {
+                                for cache_def in table_def.last_caches.resource_iter() {
+                                    provider
+                                        .create_cache_from_definition(batch.database_id, cache_def);
+                                }
+                            }
+                        }
+                        DatabaseCatalogOp::UndeleteTable(UndeleteTableLog { table_id, .. }) => {
+                            // the table's caches were removed when it was deleted:
+                            let Some(table_def) = provider
+                                .catalog
+                                .db_schema_by_id(&batch.database_id)
+                                .and_then(|db| db.table_definition_by_id(table_id))
+                            else {
+                                continue;
+                            };
+                            for cache_def in table_def.last_caches.resource_iter() {
+                                provider.create_cache_from_definition(batch.database_id, cache_def);
+                            }
+                        }
                         DatabaseCatalogOp::CreateLastCache(log) => {
                             provider.create_cache_from_definition(batch.database_id, log);
                         }
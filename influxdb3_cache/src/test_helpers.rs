@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use influxdb3_catalog::catalog::{Catalog, DatabaseSchema, TableDefinition};
+use influxdb3_catalog::catalog::{Catalog, CatalogArgs, DatabaseSchema, TableDefinition};
 use influxdb3_id::ColumnId;
 use influxdb3_wal::{Gen1Duration, Row, WriteBatch};
 use influxdb3_write::write_buffer::validator::WriteValidator;
@@ -16,13 +16,18 @@ impl TestWriter {
     pub(crate) const DB_NAME: &str = "test_db";
 
     pub(crate) async fn new() -> Self {
+        Self::new_with_args(Default::default()).await
+    }
+
+    pub(crate) async fn new_with_args(args: CatalogArgs) -> Self {
         Self {
             catalog: Arc::new(
-                Catalog::new(
+                Catalog::new_with_args(
                     "test-host",
                     Arc::new(InMemory::new()),
                     Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
                     Default::default(),
+                    args,
                 )
                 .await
                 .unwrap(),
@@ -1,9 +1,14 @@
 //! Implementation of the Catalog that sits entirely in memory.
 
+use anyhow::Context;
+use arrow::ipc::writer::{IpcDataGenerator, IpcWriteOptions, write_message};
 use base64::Engine as _;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD as B64;
 use bimap::BiHashMap;
+use influxdb3_authz::AccessRequest;
 use influxdb3_authz::Actions;
+use influxdb3_authz::CrudActions;
+use influxdb3_authz::DatabaseActions;
 use influxdb3_authz::Permission;
 use influxdb3_authz::ResourceIdentifier;
 use influxdb3_authz::ResourceType;
@@ -31,6 +36,8 @@ use sha2::Sha512;
 use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
 use std::collections::btree_map::Entry;
 use std::hash::Hash;
 use std::iter;
@@ -44,16 +51,26 @@ mod update;
 use schema::sort::SortKey;
 pub use schema::{InfluxColumnType, InfluxFieldType};
 pub use update::HardDeletionTime;
-pub use update::{CatalogUpdate, CreateDatabaseOptions, DatabaseCatalogTransaction, Prompt};
+pub use update::{
+    CatalogUpdate, CreateDatabaseOptions, DatabaseCatalogTransaction, MergePolicy, MergeReport,
+    MergedResource, Prompt, ProvisionDistinctCacheSpec, ProvisionLastCacheSpec, ProvisionTableSpec,
+    TableSpec, TenantProvisionSpec,
+};
 
 use crate::channel::{CatalogSubscriptions, CatalogUpdateReceiver};
 use crate::log::GenerationBatch;
 use crate::log::GenerationOp;
+use crate::log::MultiDatabaseBatch;
 use crate::log::{
-    ClearRetentionPeriodLog, CreateAdminTokenDetails, CreateScopedTokenDetails, CreateDatabaseLog, DatabaseBatch,
-    DatabaseCatalogOp, NodeBatch, NodeCatalogOp, NodeMode, RegenerateAdminTokenDetails,
-    RegisterNodeLog, SetRetentionPeriodLog, StopNodeLog, TokenBatch, TokenCatalogOp,
-    TriggerSpecificationDefinition,
+    AlterColumnTypeLog, ClearRetentionPeriodForTableLog, ClearRetentionPeriodLog,
+    CreateAdminTokenDetails, CreateDatabaseLog, CreateScopedTokenDetails, DatabaseBatch,
+    DatabaseCatalogOp, DropColumnLog, LastCacheValueColumnsDef, MaxCardinality, NodeBatch,
+    NodeCatalogOp, NodeMode, RegenerateAdminTokenDetails, RegisterNodeLog, RenameDatabaseLog,
+    RenameTableLog, ReorderSeriesKeyLog, SetColumnAllowedValuesLog, SetColumnDescriptionLog,
+    SetColumnIndexedLog, SetDatabaseDescriptionLog, SetNullTagPlaceholderLog,
+    SetPrimaryPartitionTagLog, SetRetentionPeriodForTableLog, SetRetentionPeriodLog, StopNodeLog,
+    TokenBatch, TokenCatalogOp, TriggerSpecificationDefinition, UndeleteDatabaseLog,
+    UndeleteTableLog,
 };
 use crate::object_store::ObjectStoreCatalog;
 use crate::resource::CatalogResource;
@@ -76,6 +93,23 @@ pub const INTERNAL_DB_NAME: &str = "_internal";
 
 pub const TIME_COLUMN_NAME: &str = "time";
 
+/// The retention policy reported to InfluxQL for a database whose name does not include one
+pub const AUTOGEN_RETENTION_POLICY: &str = "autogen";
+
+/// Separates the database name from the retention policy name in the name of a database written
+/// to through the v1 API, i.e., `<db_name>/<rp_name>`
+pub const RETENTION_POLICY_SEPARATOR: char = '/';
+
+/// Split the name of a database into the database and retention policy names presented to
+/// InfluxQL
+///
+/// A database named `<db>/<rp>` is the database `<db>` with the retention policy `<rp>`; any
+/// other database has the [`AUTOGEN_RETENTION_POLICY`].
+pub fn split_influxql_database_name(name: &str) -> (&str, &str) {
+    name.split_once(RETENTION_POLICY_SEPARATOR)
+        .unwrap_or((name, AUTOGEN_RETENTION_POLICY))
+}
+
 const DEFAULT_OPERATOR_TOKEN_NAME: &str = "_admin";
 
 /// Limit for the number of tag columns on a table (removed crippled limit)
@@ -92,6 +126,175 @@ pub enum DeletionStatus {
     NotFound,
 }
 
+/// Identifies a database or table that is scheduled to be hard deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletionTarget {
+    Database(DbId),
+    Table { db_id: DbId, table_id: TableId },
+}
+
+/// A soft-deleted database or table along with the time it is scheduled to be hard deleted
+///
+/// See [`Catalog::hard_delete_schedule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledDeletion {
+    pub target: DeletionTarget,
+    /// The name of the database or table, which includes the time it was soft deleted
+    pub name: Arc<str>,
+    pub hard_delete_time: Time,
+}
+
+/// A soft-deleted database or table that has yet to be removed from the catalog
+///
+/// See [`Catalog::list_deleted_resources`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedResource {
+    pub target: DeletionTarget,
+    /// The name of the database or table, which includes the time it was soft deleted
+    pub name: Arc<str>,
+    /// The name the database or table had before it was soft deleted
+    pub original_name: Option<Arc<str>>,
+    pub hard_delete_time: Option<Time>,
+    pub status: DeletionStatus,
+}
+
+/// The soft-deleted databases and tables whose hard deletion time has passed
+///
+/// See [`Catalog::overdue_hard_deletes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OverdueReport {
+    /// Soft-deleted databases, along with their hard deletion time
+    pub databases: Vec<(DbId, Time)>,
+    /// Soft-deleted tables in databases that are not deleted, along with their hard deletion time
+    pub tables: Vec<(DbId, TableId, Time)>,
+}
+
+impl OverdueReport {
+    pub fn is_empty(&self) -> bool {
+        self.databases.is_empty() && self.tables.is_empty()
+    }
+}
+
+/// The resources a token can access, with ids resolved to names
+///
+/// See [`Catalog::token_effective_access`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenAccessView {
+    /// All actions on all resources, as granted to admin tokens
+    AllResources,
+    /// The actions granted on each database and token, sorted by name
+    Scoped {
+        databases: Vec<ResourceAccess>,
+        tokens: Vec<ResourceAccess>,
+    },
+}
+
+/// The actions granted on a single named resource, e.g., `["read", "write"]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceAccess {
+    pub name: Arc<str>,
+    pub actions: Vec<&'static str>,
+}
+
+/// The retention and deletion policies of the databases in the catalog
+///
+/// See [`Catalog::export_policies_yaml`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CatalogPolicies {
+    pub databases: Vec<DatabasePolicy>,
+}
+
+/// The retention and deletion policy of a single database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatabasePolicy {
+    pub name: Arc<str>,
+    /// The retention period in human readable form, e.g., `7days`, or `None` if data is retained
+    /// indefinitely
+    pub retention_period: Option<String>,
+    pub deleted: bool,
+    /// When the database is scheduled to be hard deleted, as an RFC 3339 timestamp
+    pub hard_delete_time: Option<String>,
+}
+
+/// A retention policy as presented to InfluxQL, see [`Catalog::all_influxql_retention_policies`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfluxqlRp {
+    pub name: Arc<str>,
+    /// The retention period of the database, or `None` if data is retained indefinitely
+    pub duration: Option<Duration>,
+}
+
+/// How a measurement name from a write resolves to a table in a database
+///
+/// See [`Catalog::resolve_or_describe_table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableResolution {
+    /// The measurement resolves to an existing table
+    Existing(TableId),
+    /// The table for the measurement was soft deleted, and renamed with a deletion time suffix, so
+    /// a write would create a new table
+    SoftDeleted {
+        table_id: TableId,
+        deleted_name: Arc<str>,
+    },
+    /// There is no table for the measurement, so a write would create a new table
+    New,
+}
+
+/// A catalog batch that was applied to the catalog, as retained in its change log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEntry {
+    /// The sequence number the catalog was updated to by the batch
+    pub sequence_number: CatalogSequenceNumber,
+    pub batch: CatalogBatch,
+}
+
+/// The result of [`Catalog::poll_changes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangePoll {
+    /// The batches applied since the polled sequence, which may be empty
+    Changes(Vec<ChangeEntry>),
+    /// Some of the batches applied since the polled sequence are no longer retained
+    ResyncRequired,
+}
+
+/// Identifies a cache by its kind and identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    Last(LastCacheId),
+    Distinct(DistinctCacheId),
+}
+
+/// Reference to a cache defined anywhere in the catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheRef {
+    pub db_id: DbId,
+    pub table_id: TableId,
+    pub kind: CacheKind,
+    pub name: Arc<str>,
+}
+
+/// Summary of a distinct cache definition, see [`Catalog::distinct_cache_summaries`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistinctCacheSummary {
+    pub table_name: Arc<str>,
+    pub cache_name: Arc<str>,
+    /// The names of the columns cached, in the defined order
+    pub columns: Vec<Arc<str>>,
+    pub max_cardinality: MaxCardinality,
+    /// The max age rendered in human-readable units, e.g., `1day`
+    pub max_age: String,
+}
+
+/// A field name that is defined with different types across tables in the same database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldTypeConflict {
+    /// The name of the field
+    pub field_name: Arc<str>,
+    /// The name of each table that defines the field, along with the field type in that table
+    pub table_types: Vec<(Arc<str>, InfluxFieldType)>,
+}
+
 /// The sequence number of a batch of WAL operations.
 #[derive(
     Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
@@ -141,6 +344,11 @@ pub struct Catalog {
     pub(crate) inner: RwLock<InnerCatalog>,
     limits: CatalogLimits,
     args: CatalogArgs,
+    /// Bounded log of the most recently applied catalog batches, for [`Catalog::poll_changes`]
+    change_log: parking_lot::Mutex<VecDeque<ChangeEntry>>,
+    /// Queue that updates wait in, in first-come order, when [`CatalogArgs::fair_write_permit`]
+    /// is set
+    fair_write_queue: tokio::sync::Mutex<()>,
 }
 
 /// Custom implementation of `Debug` for the `Catalog` type to avoid serializing the object store
@@ -169,20 +377,192 @@ const CATALOG_CHECKPOINT_INTERVAL: u64 = 100;
 #[derive(Clone, Copy, Debug)]
 pub struct CatalogArgs {
     pub default_hard_delete_duration: Duration,
+    /// Whether writes of a float value to an existing integer or unsigned integer field promote
+    /// the field to a float, rather than being rejected
+    ///
+    /// Once a field is promoted, integer writes to it are accepted and stored as floats. Values
+    /// for the field in the write buffer are converted to floats, while parquet files persisted
+    /// before the promotion keep the integer values, which are cast to floats when queried.
+    pub type_promotion: bool,
+    /// Whether catalog updates acquire the write permit in first-come order
+    ///
+    /// By default, an update composes its catalog batch before waiting on the write permit, and
+    /// must start over if another update was applied in the meantime. Under a burst of updates,
+    /// e.g., to one database, a writer can repeatedly lose that race and be starved. In fair mode
+    /// updates first wait their turn in a first-come queue, and each composes and applies its
+    /// batch before the next starts, so updates are applied in the order they were requested and
+    /// never retry due to a concurrent update made through the catalog's update methods. The
+    /// trade-off is that composing the batch no longer overlaps with other writers, which can
+    /// increase the latency of each update when there is contention.
+    pub fair_write_permit: bool,
+    /// The longest retention period that may be set on a database, if any
+    ///
+    /// When set, databases may not have their retention period cleared, i.e., made indefinite.
+    /// This also applies when a database is created, including implicitly by a write: a database
+    /// created without a retention period is given the maximum if `max_retention_mode` is
+    /// [`MaxRetentionMode::Clamp`], and is rejected otherwise.
+    pub max_retention: Option<Duration>,
+    /// How retention periods that exceed `max_retention` are handled
+    pub max_retention_mode: MaxRetentionMode,
+    /// The number of applied catalog batches retained for [`Catalog::poll_changes`]
+    pub change_log_capacity: usize,
+    /// The shortest retention period that may be set on a database or table, if any
+    pub min_retention_period: Option<Duration>,
+    /// Rules that the names of new columns must follow, if any
+    pub column_name_rules: Option<ColumnNameRules>,
+    /// The most columns, including `time`, that a table may have, if lower than
+    /// [`Catalog::NUM_COLUMNS_PER_TABLE_LIMIT`]
+    ///
+    /// Lowering the limit does not change existing tables, see
+    /// [`Catalog::tables_over_current_limits`].
+    pub num_columns_per_table_limit: Option<usize>,
+    /// Whether the catalog is loaded as a read-only replica
+    ///
+    /// A read-only catalog is loaded from object store, which must already hold a catalog, and
+    /// can be brought up to date with [`Catalog::update_to_sequence_number`], but any attempt to
+    /// update it fails with [`CatalogError::ReadOnlyCatalog`]. This is for nodes that only serve
+    /// queries, so that they cannot write to the catalog alongside the node that owns it.
+    pub read_only: bool,
+    /// Whether the specifications of new processing engine triggers are validated
+    ///
+    /// When set, a trigger is rejected with [`CatalogError::InvalidTriggerSpecification`] if its
+    /// specification fails [`TriggerSpecificationDefinition::validate`], or if it is a request
+    /// trigger whose path is already used by another trigger. Request triggers are routed by path
+    /// alone, so the path must be unique across all databases, not only the trigger's own.
+    pub validate_trigger_specifications: bool,
+}
+
+/// Rules for the names of columns, see [`CatalogArgs::column_name_rules`]
+///
+/// The rules are checked when columns are created, so existing columns are not affected by a
+/// change to the rules. The `time` column is exempt. Names containing control characters are
+/// always rejected.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ColumnNameRules {
+    /// The fewest characters a column name may have
+    pub min_length: usize,
+    /// Whether a column name may start with a digit
+    pub allow_leading_digit: bool,
+}
+
+impl ColumnNameRules {
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if name == TIME_COLUMN_NAME {
+            return Ok(());
+        }
+        let invalid = |reason: String| {
+            Err(CatalogError::InvalidColumnName {
+                name: name.to_string(),
+                reason,
+            })
+        };
+        if name.chars().count() < self.min_length {
+            return invalid(format!(
+                "name must be at least {} characters long",
+                self.min_length
+            ));
+        }
+        if name.chars().any(char::is_control) {
+            return invalid("name cannot contain control characters".to_string());
+        }
+        if !self.allow_leading_digit && name.starts_with(|c: char| c.is_ascii_digit()) {
+            return invalid("name cannot start with a digit".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// How a retention period that exceeds [`CatalogArgs::max_retention`] is handled
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MaxRetentionMode {
+    /// Reject the retention period with an error
+    #[default]
+    Reject,
+    /// Set the retention period to the maximum instead
+    Clamp,
 }
 
 impl CatalogArgs {
     pub fn new(default_hard_delete_duration: Duration) -> Self {
         Self {
             default_hard_delete_duration,
+            type_promotion: false,
+            fair_write_permit: false,
+            max_retention: None,
+            max_retention_mode: MaxRetentionMode::Reject,
+            change_log_capacity: Catalog::DEFAULT_CHANGE_LOG_CAPACITY,
+            min_retention_period: None,
+            column_name_rules: None,
+            num_columns_per_table_limit: None,
+            read_only: false,
+            validate_trigger_specifications: false,
         }
     }
+
+    pub fn with_type_promotion(mut self, type_promotion: bool) -> Self {
+        self.type_promotion = type_promotion;
+        self
+    }
+
+    pub fn with_fair_write_permit(mut self, fair_write_permit: bool) -> Self {
+        self.fair_write_permit = fair_write_permit;
+        self
+    }
+
+    pub fn with_max_retention(mut self, max_retention: Duration, mode: MaxRetentionMode) -> Self {
+        self.max_retention = Some(max_retention);
+        self.max_retention_mode = mode;
+        self
+    }
+
+    pub fn with_change_log_capacity(mut self, change_log_capacity: usize) -> Self {
+        self.change_log_capacity = change_log_capacity;
+        self
+    }
+
+    pub fn with_min_retention_period(mut self, min_retention_period: Duration) -> Self {
+        self.min_retention_period = Some(min_retention_period);
+        self
+    }
+
+    pub fn with_column_name_rules(mut self, column_name_rules: ColumnNameRules) -> Self {
+        self.column_name_rules = Some(column_name_rules);
+        self
+    }
+
+    pub fn with_num_columns_per_table_limit(mut self, num_columns_per_table_limit: usize) -> Self {
+        self.num_columns_per_table_limit = Some(num_columns_per_table_limit);
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn with_validate_trigger_specifications(
+        mut self,
+        validate_trigger_specifications: bool,
+    ) -> Self {
+        self.validate_trigger_specifications = validate_trigger_specifications;
+        self
+    }
 }
 
 impl Default for CatalogArgs {
     fn default() -> Self {
         Self {
             default_hard_delete_duration: Catalog::DEFAULT_HARD_DELETE_DURATION,
+            type_promotion: false,
+            fair_write_permit: false,
+            max_retention: None,
+            max_retention_mode: MaxRetentionMode::Reject,
+            change_log_capacity: Catalog::DEFAULT_CHANGE_LOG_CAPACITY,
+            min_retention_period: None,
+            column_name_rules: None,
+            num_columns_per_table_limit: None,
+            read_only: false,
+            validate_trigger_specifications: false,
         }
     }
 }
@@ -192,6 +572,8 @@ pub struct CatalogLimits {
     num_dbs: usize,
     num_tables: usize,
     num_columns_per_table: usize,
+    num_tokens: usize,
+    min_retention_period: Option<Duration>,
 }
 
 impl Default for CatalogLimits {
@@ -200,6 +582,8 @@ impl Default for CatalogLimits {
             num_dbs: Catalog::NUM_DBS_LIMIT,
             num_tables: Catalog::NUM_TABLES_LIMIT,
             num_columns_per_table: Catalog::NUM_COLUMNS_PER_TABLE_LIMIT,
+            num_tokens: Catalog::NUM_TOKENS_LIMIT,
+            min_retention_period: None,
         }
     }
 }
@@ -211,8 +595,18 @@ impl Catalog {
     pub const NUM_COLUMNS_PER_TABLE_LIMIT: usize = usize::MAX;
     /// Limit for the number of tables across all DBs that InfluxDB 3 Core can have
     pub const NUM_TABLES_LIMIT: usize = usize::MAX;
+    /// Limit for the number of tokens, excluding the operator token, that InfluxDB 3 Core can have
+    pub const NUM_TOKENS_LIMIT: usize = usize::MAX;
     /// Default duration for hard deletion of soft-deleted databases and tables
     pub const DEFAULT_HARD_DELETE_DURATION: Duration = Duration::from_secs(10 * 365 * 24 * 60 * 60); // 10 years, effectively infinite
+    /// Default number of applied catalog batches retained for [`Catalog::poll_changes`]
+    pub const DEFAULT_CHANGE_LOG_CAPACITY: usize = 1_000;
+    /// Weight given to each table in [`Catalog::complexity_score`]
+    pub const COMPLEXITY_TABLE_WEIGHT: u64 = 10;
+    /// Weight given to each column in [`Catalog::complexity_score`]
+    pub const COMPLEXITY_COLUMN_WEIGHT: u64 = 1;
+    /// Weight given to each column of a table's series key in [`Catalog::complexity_score`]
+    pub const COMPLEXITY_SERIES_KEY_WEIGHT: u64 = 5;
 
     pub async fn new(
         node_id: impl Into<Arc<str>>,
@@ -240,25 +634,46 @@ impl Catalog {
         let node_id = node_id.into();
         let store =
             ObjectStoreCatalog::new(Arc::clone(&node_id), CATALOG_CHECKPOINT_INTERVAL, store);
-        let subscriptions = Default::default();
+        let subscriptions = Arc::new(tokio::sync::RwLock::new(CatalogSubscriptions::new(
+            &metric_registry,
+        )));
         let metrics = Arc::new(CatalogMetrics::new(&metric_registry));
-        let catalog = store
-            .load_or_create_catalog()
-            .await
-            .map(RwLock::new)
-            .map(|inner| Self {
-                metric_registry,
-                state: parking_lot::Mutex::new(CatalogState::Active),
-                subscriptions,
-                time_provider,
-                store,
-                metrics,
-                inner,
-                limits: Default::default(),
-                args,
-            })?;
+        let inner = if args.read_only {
+            let Some(inner) = store.load_catalog().await? else {
+                return Err(CatalogError::invalid_configuration(
+                    "a read-only catalog can only be loaded from an existing catalog, but none \
+                    was found on object store",
+                ));
+            };
+            inner
+        } else {
+            store.load_or_create_catalog().await?
+        };
+        let catalog = Self {
+            metric_registry,
+            state: parking_lot::Mutex::new(CatalogState::Active),
+            subscriptions,
+            time_provider,
+            store,
+            metrics,
+            inner: RwLock::new(inner),
+            limits: CatalogLimits {
+                num_columns_per_table: args
+                    .num_columns_per_table_limit
+                    .unwrap_or(Catalog::NUM_COLUMNS_PER_TABLE_LIMIT),
+                min_retention_period: args.min_retention_period,
+                ..Default::default()
+            },
+            args,
+            change_log: Default::default(),
+            fair_write_queue: Default::default(),
+        };
 
-        create_internal_db(&catalog).await;
+        // the internal database is created by the node that owns the catalog:
+        if !args.read_only {
+            create_internal_db(&catalog).await;
+        }
+        catalog.metrics.record_catalog_size(&catalog.inner.read());
         catalog.metrics.operation_observer(
             catalog
                 .subscribe_to_updates("catalog_operation_metrics")
@@ -311,6 +726,20 @@ impl Catalog {
         *self.state.lock() = CatalogState::Shutdown;
     }
 
+    /// Whether the catalog was loaded as a read-only replica, see [`CatalogArgs::read_only`]
+    ///
+    /// This is fixed for the lifetime of the catalog, and is not affected by its shutdown state.
+    pub fn is_read_only(&self) -> bool {
+        self.args.read_only
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.is_read_only() {
+            return Err(CatalogError::ReadOnlyCatalog);
+        }
+        Ok(())
+    }
+
     fn num_dbs_limit(&self) -> usize {
         self.limits.num_dbs
     }
@@ -323,14 +752,170 @@ impl Catalog {
         self.limits.num_columns_per_table
     }
 
-    fn default_hard_delete_duration(&self) -> Duration {
+    fn num_tokens_limit(&self) -> usize {
+        self.limits.num_tokens
+    }
+
+    /// Check that creating another token would not exceed the token limit
+    ///
+    /// The operator token does not count towards the limit.
+    fn check_num_tokens_limit(&self) -> Result<()> {
+        let num_tokens = self
+            .inner
+            .read()
+            .tokens
+            .repo()
+            .resource_iter()
+            .filter(|token| token.name.as_ref() != DEFAULT_OPERATOR_TOKEN_NAME)
+            .count();
+        if num_tokens >= self.num_tokens_limit() {
+            return Err(CatalogError::TooManyTokens(self.num_tokens_limit()));
+        }
+        Ok(())
+    }
+
+    /// The duration after a soft delete at which a resource is hard deleted, when it is deleted
+    /// with [`HardDeletionTime::Default`]
+    pub fn default_hard_delete_duration(&self) -> Duration {
         self.args.default_hard_delete_duration
     }
 
+    fn column_name_rules(&self) -> Option<ColumnNameRules> {
+        self.args.column_name_rules
+    }
+
+    /// Check the specification of a new trigger, if enabled, see
+    /// [`CatalogArgs::validate_trigger_specifications`]
+    fn check_trigger_specification(&self, trigger: &TriggerSpecificationDefinition) -> Result<()> {
+        if !self.args.validate_trigger_specifications {
+            return Ok(());
+        }
+        trigger.validate()?;
+        let TriggerSpecificationDefinition::RequestPath { path } = trigger else {
+            return Ok(());
+        };
+        let inner = self.inner.read();
+        for db in inner.databases.resource_iter().filter(|db| !db.deleted) {
+            for existing in db.processing_engine_triggers.resource_iter() {
+                if existing.trigger == *trigger {
+                    return Err(CatalogError::InvalidTriggerSpecification {
+                        trigger_spec: trigger.string_rep(),
+                        reason: format!(
+                            "path '{path}' is already used by trigger '{}' in database '{}'",
+                            existing.trigger_name, db.name
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn type_promotion(&self) -> bool {
+        self.args.type_promotion
+    }
+
+    /// Check that a retention period is not shorter than the configured minimum, if any
+    fn check_min_retention(&self, period: RetentionPeriod) -> Result<()> {
+        match (period, self.limits.min_retention_period) {
+            (RetentionPeriod::Duration(attempted), Some(min)) if attempted < min => {
+                Err(CatalogError::RetentionPeriodTooShort {
+                    min: min.into(),
+                    attempted: attempted.into(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a retention period against the configured maximum, if any
+    ///
+    /// Returns the retention period to use, which is the maximum if the period exceeds it and the
+    /// catalog is configured to clamp, or an error if it is configured to reject. An indefinite
+    /// retention period is always rejected when there is a maximum.
+    fn enforce_max_retention(&self, period: RetentionPeriod) -> Result<RetentionPeriod> {
+        let Some(max) = self.args.max_retention else {
+            return Ok(period);
+        };
+        match (period, self.args.max_retention_mode) {
+            (RetentionPeriod::Duration(duration), _) if duration <= max => Ok(period),
+            (RetentionPeriod::Duration(_), MaxRetentionMode::Clamp) => {
+                Ok(RetentionPeriod::Duration(max))
+            }
+            (RetentionPeriod::Duration(duration), MaxRetentionMode::Reject) => {
+                Err(CatalogError::invalid_configuration(format!(
+                    "retention period of {} exceeds the maximum of {}",
+                    humantime::format_duration(duration),
+                    humantime::format_duration(max)
+                )))
+            }
+            (RetentionPeriod::Indefinite, _) => Err(CatalogError::invalid_configuration(format!(
+                "retention period must be set, and no more than the maximum of {}",
+                humantime::format_duration(max)
+            ))),
+        }
+    }
+
+    /// Check the retention period requested for a new database against the configured minimum
+    /// and maximum, returning the retention period to create the database with
+    ///
+    /// A database requested without a retention period is given the maximum, if there is one and
+    /// the catalog is configured to clamp, see [`CatalogArgs::max_retention`].
+    fn new_database_retention(
+        &self,
+        retention_period: Option<Duration>,
+    ) -> Result<Option<Duration>> {
+        let period =
+            retention_period.map_or(RetentionPeriod::Indefinite, RetentionPeriod::Duration);
+        self.check_min_retention(period)?;
+        let period = match (
+            period,
+            self.args.max_retention,
+            self.args.max_retention_mode,
+        ) {
+            (RetentionPeriod::Indefinite, Some(max), MaxRetentionMode::Clamp) => {
+                RetentionPeriod::Duration(max)
+            }
+            _ => self.enforce_max_retention(period)?,
+        };
+        match period {
+            RetentionPeriod::Duration(duration) => Ok(Some(duration)),
+            RetentionPeriod::Indefinite => Ok(None),
+        }
+    }
+
     pub fn object_store_prefix(&self) -> Arc<str> {
         Arc::clone(&self.store.prefix)
     }
 
+    /// The number of sequenced log files between each checkpoint written to object store
+    pub fn checkpoint_interval(&self) -> u64 {
+        self.store.checkpoint_interval()
+    }
+
+    /// Change how often a checkpoint is written to object store, without restarting the server
+    ///
+    /// A checkpoint is written in the background whenever the catalog sequence number is a
+    /// multiple of `interval`, and takes effect from the next applied batch. On load, the catalog
+    /// reads the latest checkpoint and then every log file written after it, so a larger interval
+    /// means more log files can be read at startup, and a smaller one means more checkpoint PUTs
+    /// while running. With the default interval of 100, loading a catalog with many updates
+    /// should never read the older log files that a checkpoint covers; see the
+    /// `test_load_many_files_with_default_checkpoint_interval` test.
+    ///
+    /// An `interval` of `0` is rejected; use `1` to write a checkpoint for every batch.
+    pub fn set_checkpoint_interval(&self, interval: u64) -> Result<()> {
+        if interval == 0 {
+            return Err(CatalogError::invalid_configuration(
+                "catalog checkpoint interval must be greater than 0",
+            ));
+        }
+        let previous = self.store.checkpoint_interval();
+        self.store.set_checkpoint_interval(interval);
+        info!(previous, interval, "catalog checkpoint interval updated");
+        Ok(())
+    }
+
     pub fn catalog_uuid(&self) -> Uuid {
         self.inner.read().catalog_uuid
     }
@@ -347,9 +932,46 @@ impl Catalog {
         self.inner.read().snapshot()
     }
 
+    /// Replace the state of the catalog with the given snapshot
+    ///
+    /// This does not check where the snapshot came from, see [`Catalog::restore_from_snapshot`]
+    /// for restoring a snapshot that may have been taken from a different catalog.
     pub fn update_from_snapshot(&self, snapshot: CatalogSnapshot) {
         let mut inner = self.inner.write();
         *inner = InnerCatalog::from_snapshot(snapshot);
+        self.metrics.record_catalog_size(&inner);
+        // the logged changes no longer lead up to the catalog's state:
+        self.change_log.lock().clear();
+    }
+
+    /// Replace the state of the catalog with the given snapshot, after checking that the snapshot
+    /// was taken from a catalog with the same `catalog_id` as this one
+    ///
+    /// Pass `force` to restore a snapshot from a different catalog intentionally, e.g., when
+    /// migrating a catalog to a new cluster.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::InvalidConfiguration` - If the snapshot's `catalog_id` does not match this
+    ///   catalog's and `force` is not set; the catalog is not changed
+    pub fn restore_from_snapshot(&self, snapshot: CatalogSnapshot, force: bool) -> Result<()> {
+        let catalog_id = self.catalog_id();
+        if snapshot.catalog_id != catalog_id {
+            if !force {
+                return Err(CatalogError::invalid_configuration(format!(
+                    "snapshot is from catalog '{snapshot_catalog_id}', which does not match this \
+                    catalog '{catalog_id}', restore with force to replace the catalog anyway",
+                    snapshot_catalog_id = snapshot.catalog_id,
+                )));
+            }
+            warn!(
+                %catalog_id,
+                snapshot_catalog_id = %snapshot.catalog_id,
+                "forcing restore of a snapshot from a different catalog"
+            );
+        }
+        self.update_from_snapshot(snapshot);
+        Ok(())
     }
 
     /// Acquire a permit to write the provided `CatalogBatch` to object store
@@ -358,25 +980,48 @@ impl Catalog {
     /// against the current catalog's sequence. If it is behind, due to some other concurrent
     /// update to the catalog, a retry is issued, so that the caller can re-compose the catalog
     /// batch using the latest state of the catalog and try again.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::ReadOnlyCatalog` - If the catalog is read-only, in which case the permit is
+    ///   not acquired
     pub async fn get_permit_and_verify_catalog_batch(
         &self,
         catalog_batch: CatalogBatch,
         sequence: CatalogSequenceNumber,
-    ) -> Prompt<(OrderedCatalogBatch, CatalogWritePermit)> {
+    ) -> Result<Prompt<(OrderedCatalogBatch, CatalogWritePermit)>> {
+        self.check_writable()?;
         // Get the write permit, and update its contents with the next catalog sequence number. If
         // the `catalog_batch` provided results in an update, i.e., changes the catalog, then this
         // will be the sequence number that the catalog is updated to.
         let mut permit = CATALOG_WRITE_PERMIT.lock().await;
         if sequence != self.sequence_number() {
             self.metrics.catalog_operation_retries.inc(1);
-            return Prompt::Retry(());
+            return Ok(Prompt::Retry(()));
         }
         *permit = self.sequence_number().next();
         trace!(
             next_sequence = permit.get(),
             "got permit to write to catalog"
         );
-        Prompt::Success((OrderedCatalogBatch::new(catalog_batch, *permit), permit))
+        Ok(Prompt::Success((
+            OrderedCatalogBatch::new(catalog_batch, *permit),
+            permit,
+        )))
+    }
+
+    /// Check that `catalog_batch` could be applied to the catalog in its current state, without
+    /// applying it
+    ///
+    /// The batch is applied to a copy of the catalog which is then discarded, so this returns the
+    /// same errors that applying the batch would, but the catalog itself, including the ids it
+    /// will assign next, is left untouched. The write permit is not taken and nothing is written
+    /// to object store, so the result is only valid until the catalog is next updated.
+    pub fn validate_batch(&self, catalog_batch: &CatalogBatch) -> Result<()> {
+        let mut inner = self.clone_inner();
+        let sequence = inner.sequence_number().next();
+        inner.apply_catalog_batch(catalog_batch, sequence)?;
+        Ok(())
     }
 
     /// Apply an `OrderedCatalogBatch` to this catalog
@@ -389,7 +1034,7 @@ impl Catalog {
         &self,
         batch: &OrderedCatalogBatch,
         _permit: &CatalogWritePermit,
-    ) -> CatalogBatch {
+    ) -> OrderedCatalogBatch {
         let batch_sequence = batch.sequence_number().get();
         let current_sequence = self.sequence_number().get();
         assert_eq!(
@@ -397,23 +1042,118 @@ impl Catalog {
             current_sequence + 1,
             "catalog batch received out of order"
         );
-        let catalog_batch = self
-            .inner
-            .write()
+        let mut inner = self.inner.write();
+        let catalog_batch = inner
             .apply_catalog_batch(batch.batch(), batch.sequence_number())
             .expect("ordered catalog batch should succeed when applied")
             .expect("ordered catalog batch should contain changes");
-        catalog_batch.into_batch()
+        self.metrics.record_catalog_size(&inner);
+        // record the change while the catalog is still locked, so that pollers never observe the
+        // catalog's sequence ahead of the change log:
+        self.record_change(batch);
+        drop(inner);
+        catalog_batch
+    }
+
+    fn record_change(&self, batch: &OrderedCatalogBatch) {
+        let capacity = self.args.change_log_capacity;
+        if capacity == 0 {
+            return;
+        }
+        let mut change_log = self.change_log.lock();
+        while change_log.len() >= capacity {
+            change_log.pop_front();
+        }
+        change_log.push_back(ChangeEntry {
+            sequence_number: batch.sequence_number(),
+            batch: batch.batch().clone(),
+        });
+    }
+
+    /// Get the catalog batches applied after `after_sequence`, in order
+    ///
+    /// This is a pull-based alternative to [`Catalog::subscribe_to_updates`] for clients that
+    /// cannot hold a subscription open. Only the most recent batches are retained, as configured
+    /// by [`CatalogArgs::change_log_capacity`]. If some of the batches after `after_sequence` are
+    /// no longer retained, [`ChangePoll::ResyncRequired`] is returned, and the client should
+    /// reload the full catalog state before polling again from its current sequence.
+    pub fn poll_changes(&self, after_sequence: CatalogSequenceNumber) -> ChangePoll {
+        let inner = self.inner.read();
+        if after_sequence >= inner.sequence {
+            return ChangePoll::Changes(vec![]);
+        }
+        let change_log = self.change_log.lock();
+        match change_log.front() {
+            Some(oldest) if oldest.sequence_number <= after_sequence.next() => ChangePoll::Changes(
+                change_log
+                    .iter()
+                    .filter(|entry| entry.sequence_number > after_sequence)
+                    .cloned()
+                    .collect(),
+            ),
+            _ => ChangePoll::ResyncRequired,
+        }
     }
 
     pub fn node(&self, node_id: &str) -> Option<Arc<NodeDefinition>> {
         self.inner.read().nodes.get_by_name(node_id)
     }
 
+    /// Get all running nodes that include the given [`NodeMode`], ordered by node id
+    pub fn nodes_in_mode(&self, mode: NodeMode) -> Vec<Arc<NodeDefinition>> {
+        let mut nodes: Vec<Arc<NodeDefinition>> = self
+            .inner
+            .read()
+            .nodes
+            .resource_iter()
+            .filter(|node| node.is_running() && node.mode.contains(&mode))
+            .cloned()
+            .collect();
+        nodes.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        nodes
+    }
+
     pub fn next_db_id(&self) -> DbId {
         self.inner.read().databases.next_id()
     }
 
+    /// The id that the next database created in the catalog will be assigned
+    ///
+    /// Like [`Catalog::peek_next_sequence`], this does not reserve the id, so it is only advisory
+    /// while other updates are being made to the catalog. See also
+    /// [`DatabaseSchema::peek_next_table_id`] and [`TableDefinition::peek_next_column_id`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use influxdb3_catalog::{catalog::Catalog, log::FieldDataType};
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let catalog = Catalog::new_in_memory("example").await.unwrap();
+    /// let db_id = catalog.peek_next_db_id();
+    /// catalog.create_database("foo").await.unwrap();
+    /// assert_eq!(Some(db_id), catalog.db_name_to_id("foo"));
+    ///
+    /// let table_id = catalog.db_schema("foo").unwrap().peek_next_table_id();
+    /// catalog
+    ///     .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+    ///     .await
+    ///     .unwrap();
+    /// let db = catalog.db_schema("foo").unwrap();
+    /// assert_eq!(Some(table_id), db.table_name_to_id("cpu"));
+    ///
+    /// let column_id = db.table_definition("cpu").unwrap().peek_next_column_id();
+    /// let mut txn = catalog.begin("foo").unwrap();
+    /// txn.column_or_create("cpu", "idle", FieldDataType::Float).unwrap();
+    /// catalog.commit(txn).await.unwrap();
+    /// let table = catalog.db_schema("foo").unwrap().table_definition("cpu").unwrap();
+    /// assert_eq!(Some(column_id), table.column_name_to_id("idle"));
+    /// # }
+    /// ```
+    pub fn peek_next_db_id(&self) -> DbId {
+        self.next_db_id()
+    }
+
     pub(crate) fn db_or_create(
         &self,
         db_name: &str,
@@ -423,6 +1163,14 @@ impl Catalog {
         match self.db_schema(db_name) {
             Some(db) => Ok((db, None)),
             None => {
+                // the internal database is created by the catalog itself, so is exempt from the
+                // rules for the names and retention periods of other databases:
+                let retention_period = if db_name == INTERNAL_DB_NAME {
+                    retention_period
+                } else {
+                    Self::validate_database_name(db_name)?;
+                    self.new_database_retention(retention_period)?
+                };
                 let mut inner = self.inner.write();
 
                 if inner.database_count() >= self.num_dbs_limit() {
@@ -448,6 +1196,18 @@ impl Catalog {
         }
     }
 
+    /// Check that `name` is allowed as the name of a new database
+    ///
+    /// These are the rules applied when the catalog creates a database, see
+    /// [`validate_db_name`], with the `<db_name>/<rp_name>` form used by the v1 API accepted.
+    /// Names starting with an underscore, including that of the internal database, are reserved.
+    pub fn validate_database_name(name: &str) -> Result<()> {
+        validate_db_name(name, true).map_err(|reason| CatalogError::InvalidDatabaseName {
+            name: name.to_string(),
+            reason,
+        })
+    }
+
     pub fn db_name_to_id(&self, db_name: &str) -> Option<DbId> {
         self.inner.read().databases.name_to_id(db_name)
     }
@@ -460,10 +1220,65 @@ impl Catalog {
         self.inner.read().databases.get_by_name(db_name)
     }
 
+    /// Get the schema of the named database, unless it has been soft deleted
+    ///
+    /// Soft-deleted databases are renamed, but can still be looked up by their new name with
+    /// [`Catalog::db_schema`]. Anything serving user requests, such as queries, should use this
+    /// method instead so that a deleted database is reported as not found.
+    pub fn active_db_schema(&self, db_name: &str) -> Option<Arc<DatabaseSchema>> {
+        self.db_schema(db_name).filter(|db| !db.deleted)
+    }
+
     pub fn db_schema_by_id(&self, db_id: &DbId) -> Option<Arc<DatabaseSchema>> {
         self.inner.read().databases.get_by_id(db_id)
     }
 
+    /// Find a table by its id alone, without knowing which database it is in
+    ///
+    /// Table ids are only unique within a database, so more than one database can have a table
+    /// with the given id; this returns the first match, in the order the databases were created.
+    /// Use [`Catalog::find_tables_by_id`] to get every match, or [`Catalog::db_schema_by_id`] and
+    /// [`DatabaseSchema::table_definition_by_id`] when the database id is known. Deleted
+    /// databases and tables are included.
+    pub fn find_table_by_id(
+        &self,
+        table_id: TableId,
+    ) -> Option<(Arc<DatabaseSchema>, Arc<TableDefinition>)> {
+        self.inner.read().databases.resource_iter().find_map(|db| {
+            db.table_definition_by_id(&table_id)
+                .map(|table| (Arc::clone(db), table))
+        })
+    }
+
+    /// Find every table with the given id, along with the database it is in, see
+    /// [`Catalog::find_table_by_id`]
+    pub fn find_tables_by_id(
+        &self,
+        table_id: TableId,
+    ) -> Vec<(Arc<DatabaseSchema>, Arc<TableDefinition>)> {
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter_map(|db| {
+                db.table_definition_by_id(&table_id)
+                    .map(|table| (Arc::clone(db), table))
+            })
+            .collect()
+    }
+
+    /// Check if the named database has a table named `table_name`, where neither the table nor the
+    /// database have been soft deleted
+    ///
+    /// Soft-deleted tables are renamed, so this returns `false` both for the name a table had
+    /// before it was deleted, unless a new table has since been created with that name, and for
+    /// the name it was given when deleted.
+    pub fn table_exists_by_name(&self, db_name: &str, table_name: &str) -> bool {
+        self.active_db_schema(db_name)
+            .and_then(|db| db.table_definition(table_name))
+            .is_some_and(|table| !table.deleted)
+    }
+
     /// List names of databases that have not been deleted
     pub fn db_names(&self) -> Vec<String> {
         self.inner
@@ -475,13 +1290,64 @@ impl Catalog {
             .collect()
     }
 
-    pub fn list_db_schema(&self) -> Vec<Arc<DatabaseSchema>> {
-        self.inner
+    /// List the databases, that have not been deleted, which were created after the given time
+    ///
+    /// Databases are returned in the order they were created. Databases without a recorded
+    /// creation time are not included.
+    pub fn databases_created_after(&self, t: Time) -> Vec<Arc<DatabaseSchema>> {
+        let t = t.timestamp_nanos();
+        let mut databases = self
+            .inner
             .read()
             .databases
             .resource_iter()
+            .filter(|db| !db.deleted && db.created_time_ns.is_some_and(|created| created > t))
             .cloned()
-            .collect()
+            .collect::<Vec<_>>();
+        databases.sort_by_key(|db| (db.created_time_ns, db.id));
+        databases
+    }
+
+    /// Get the database, that has not been deleted, which was most recently modified by a catalog
+    /// batch, e.g., by a write that added a table or column
+    ///
+    /// Databases without a recorded modification sequence are not considered.
+    pub fn most_recently_active_database(&self) -> Option<Arc<DatabaseSchema>> {
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted && db.last_modified_sequence.is_some())
+            .max_by_key(|db| db.last_modified_sequence)
+            .cloned()
+    }
+
+    /// List the id, name, and object store prefix for each database that has not been deleted
+    ///
+    /// The prefix follows the conventional layout used for persisted data, i.e.,
+    /// `<catalog prefix>/dbs/<db name>-<db id>`, so it is unique per database and rooted under
+    /// [`Catalog::object_store_prefix`].
+    pub fn database_storage_info(&self) -> Vec<(DbId, Arc<str>, Arc<str>)> {
+        let prefix = self.object_store_prefix();
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted)
+            .map(|db| {
+                let db_prefix = Arc::from(format!("{prefix}/dbs/{}-{}", db.name, db.id));
+                (db.id, Arc::clone(&db.name), db_prefix)
+            })
+            .collect()
+    }
+
+    pub fn list_db_schema(&self) -> Vec<Arc<DatabaseSchema>> {
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .cloned()
+            .collect()
     }
 
     /// Returns the deletion status of a database by its ID.
@@ -505,10 +1371,114 @@ impl Catalog {
         }
     }
 
+    /// Returns the soft-deleted database or table with the earliest hard deletion time
+    ///
+    /// Tables belonging to a soft-deleted database are not considered, since they are removed
+    /// along with their database. Resources without a hard deletion time are also ignored.
+    pub fn oldest_pending_hard_delete(&self) -> Option<(DeletionTarget, Time)> {
+        self.list_deleted_resources()
+            .into_iter()
+            .filter_map(|resource| {
+                resource
+                    .hard_delete_time
+                    .map(|time| (resource.target, time))
+            })
+            .min_by_key(|(_, time)| *time)
+    }
+
+    /// List the soft-deleted databases and tables whose hard deletion time is at or before `now`
+    ///
+    /// Nothing is removed from the catalog, so this can be used to preview what the next hard
+    /// deletion sweep would remove. As with [`Catalog::oldest_pending_hard_delete`], tables in a
+    /// soft-deleted database are not listed, since they are removed along with their database.
+    pub fn overdue_hard_deletes(&self, now: Time) -> OverdueReport {
+        let mut report = OverdueReport::default();
+        for resource in self.list_deleted_resources() {
+            let Some(time) = resource.hard_delete_time.filter(|time| *time <= now) else {
+                continue;
+            };
+            match resource.target {
+                DeletionTarget::Database(db_id) => report.databases.push((db_id, time)),
+                DeletionTarget::Table { db_id, table_id } => {
+                    report.tables.push((db_id, table_id, time))
+                }
+            }
+        }
+        report
+    }
+
+    /// List every soft-deleted database and table that has a hard deletion time, in the order
+    /// they are scheduled to be hard deleted
+    ///
+    /// Resources that are never hard deleted are not listed, nor are tables in a soft-deleted
+    /// database, since they are removed along with their database.
+    pub fn hard_delete_schedule(&self) -> Vec<ScheduledDeletion> {
+        let mut schedule = self
+            .list_deleted_resources()
+            .into_iter()
+            .filter_map(|resource| {
+                Some(ScheduledDeletion {
+                    target: resource.target,
+                    name: resource.name,
+                    hard_delete_time: resource.hard_delete_time?,
+                })
+            })
+            .collect::<Vec<_>>();
+        schedule.sort_by_key(|deletion| deletion.hard_delete_time);
+        schedule
+    }
+
+    /// List every soft-deleted database and table that is still in the catalog
+    ///
+    /// Databases are visited in the order they are stored in the catalog: a soft-deleted database
+    /// is listed on its own, as tables in a soft-deleted database are removed along with it, and
+    /// otherwise the database's soft-deleted tables are listed. Unlike
+    /// [`Catalog::hard_delete_schedule`], resources that are never hard deleted are listed, as are
+    /// those whose hard deletion time has passed, which have a [`DeletionStatus::Hard`] status.
+    pub fn list_deleted_resources(&self) -> Vec<DeletedResource> {
+        let now = self.time_provider.now();
+        let inner = self.inner.read();
+        let mut deleted = Vec::new();
+        for db in inner.databases.resource_iter() {
+            if db.deleted {
+                deleted.push(DeletedResource {
+                    target: DeletionTarget::Database(db.id),
+                    name: Arc::clone(&db.name),
+                    original_name: db.original_name.as_ref().map(Arc::clone),
+                    hard_delete_time: db.hard_delete_time,
+                    status: deletion_status_at(db.hard_delete_time, now),
+                });
+                continue;
+            }
+            for table in db.tables.resource_iter().filter(|t| t.deleted) {
+                deleted.push(DeletedResource {
+                    target: DeletionTarget::Table {
+                        db_id: db.id,
+                        table_id: table.table_id,
+                    },
+                    name: Arc::clone(&table.table_name),
+                    original_name: table.original_name.as_ref().map(Arc::clone),
+                    hard_delete_time: table.hard_delete_time,
+                    status: deletion_status_at(table.hard_delete_time, now),
+                });
+            }
+        }
+        deleted
+    }
+
     pub fn sequence_number(&self) -> CatalogSequenceNumber {
         self.inner.read().sequence
     }
 
+    /// The sequence number that the next update to the catalog will be assigned
+    ///
+    /// This does not reserve the sequence number and is only advisory: a concurrent writer that
+    /// acquires the [`CatalogWritePermit`] first will consume it, in which case the permit
+    /// captured by a subsequent writer will hold a later sequence number.
+    pub fn peek_next_sequence(&self) -> CatalogSequenceNumber {
+        self.sequence_number().next()
+    }
+
     pub fn clone_inner(&self) -> InnerCatalog {
         self.inner.read().clone()
     }
@@ -517,10 +1487,31 @@ impl Catalog {
         Arc::clone(&self.inner.read().catalog_id)
     }
 
+    /// The label of the checkpoint the catalog was loaded from, or that was most recently written
+    /// by [`Catalog::force_checkpoint`]
+    pub fn checkpoint_label(&self) -> Option<String> {
+        self.inner.read().checkpoint_label.clone()
+    }
+
+    /// The sequence number covered by the latest checkpoint on object store, or `None` if there
+    /// is no checkpoint
+    ///
+    /// Catalog files after this sequence are replayed when the catalog is loaded.
+    pub async fn last_checkpoint_sequence(&self) -> Result<Option<CatalogSequenceNumber>> {
+        Ok(self.store.load_checkpoint_sequence().await?)
+    }
+
     pub fn db_exists(&self, db_id: DbId) -> bool {
         self.inner.read().db_exists(db_id)
     }
 
+    /// Get the placeholder that queries render in place of missing tag values for the named
+    /// database, if one is set
+    pub fn null_tag_placeholder(&self, db_name: &str) -> Option<Arc<str>> {
+        self.db_schema(db_name)
+            .and_then(|db| db.null_tag_placeholder.as_ref().map(Arc::clone))
+    }
+
     /// Get active triggers by database and trigger name
     // NOTE: this could be id-based in future
     pub fn active_triggers(&self) -> Vec<(Arc<str>, Arc<str>)> {
@@ -542,6 +1533,315 @@ impl Catalog {
             .collect()
     }
 
+    /// Get the distinct plugin filenames referenced by triggers in all databases that are not
+    /// deleted
+    ///
+    /// Disabled triggers are included, since their plugins are needed if they are re-enabled.
+    pub fn trigger_plugin_paths(&self) -> BTreeSet<Arc<str>> {
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted)
+            .flat_map(|db| {
+                db.processing_engine_triggers
+                    .resource_iter()
+                    .map(|trigger| Arc::from(trigger.plugin_filename.as_str()))
+            })
+            .collect()
+    }
+
+    /// List a page of the tables, that have not been deleted, in the named database
+    ///
+    /// Tables are returned in `TableId` order, starting with the first table whose id comes after
+    /// `after`, or from the beginning if `after` is `None`, and up to `limit` tables are returned.
+    /// To get the next page, pass the id of the last table in this page as `after`.
+    ///
+    /// Tables are stored in the order they were inserted into the database's [`Repository`], and
+    /// since table ids are assigned in increasing order as tables are created, this is also
+    /// `TableId` order. That order is stable across calls and through serialization, so paging
+    /// will not skip or repeat tables. Tables created while paging will appear on a later page.
+    ///
+    /// An empty list is returned if the database does not exist, or if `after` is past the last
+    /// table.
+    pub fn list_tables_paginated(
+        &self,
+        db_name: &str,
+        after: Option<TableId>,
+        limit: usize,
+    ) -> Vec<Arc<TableDefinition>> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        db_schema
+            .tables
+            .resource_iter()
+            .filter(|table| !table.deleted && after.is_none_or(|after| table.table_id > after))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Find every table named `table_name`, across all databases that are not deleted
+    ///
+    /// Returns the id of each table along with the id of its database, in the order the databases
+    /// were created.
+    pub fn table_name_occurrences(&self, table_name: &str) -> Vec<(DbId, TableId)> {
+        self.inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted)
+            .filter_map(|db| {
+                db.table_definition(table_name)
+                    .filter(|table| !table.deleted)
+                    .map(|table| (db.id, table.table_id))
+            })
+            .collect()
+    }
+
+    /// A single number that gives a rough measure of how heavy the named database's schema is
+    ///
+    /// The score is the weighted sum of the number of tables, the total number of columns, and
+    /// the total width of the tables' series keys, using [`Catalog::COMPLEXITY_TABLE_WEIGHT`],
+    /// [`Catalog::COMPLEXITY_COLUMN_WEIGHT`], and [`Catalog::COMPLEXITY_SERIES_KEY_WEIGHT`],
+    /// respectively. Series key columns also count as columns, so they weigh more than other
+    /// columns, since each adds to the cardinality of the table. Deleted tables are not counted,
+    /// and the score for a database that does not exist is zero.
+    pub fn complexity_score(&self, db_name: &str) -> u64 {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return 0;
+        };
+        db_schema
+            .tables
+            .resource_iter()
+            .filter(|table| !table.deleted)
+            .map(|table| {
+                Self::COMPLEXITY_TABLE_WEIGHT
+                    + Self::COMPLEXITY_COLUMN_WEIGHT * table.columns.len() as u64
+                    + Self::COMPLEXITY_SERIES_KEY_WEIGHT * table.series_key.len() as u64
+            })
+            .sum()
+    }
+
+    /// Group the tables of the named database by whether they have a last cache, a distinct
+    /// cache, both, or neither
+    ///
+    /// Tables are listed by name, in the order they were created. Deleted tables are not included,
+    /// and all groups are empty if the database does not exist.
+    pub fn cache_coverage(&self, db_name: &str) -> CacheCoverage {
+        let mut coverage = CacheCoverage::default();
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return coverage;
+        };
+        for table in db_schema.tables.resource_iter().filter(|t| !t.deleted) {
+            let bucket = match (
+                table.last_caches.is_empty(),
+                table.distinct_caches.is_empty(),
+            ) {
+                (false, false) => &mut coverage.both,
+                (false, true) => &mut coverage.last_only,
+                (true, false) => &mut coverage.distinct_only,
+                (true, true) => &mut coverage.neither,
+            };
+            bucket.push(Arc::clone(&table.table_name));
+        }
+        coverage
+    }
+
+    /// Find the tables that exceed the currently configured column or tag column limits
+    ///
+    /// Limits are only checked when columns are added, so tables created before a limit was
+    /// lowered can exceed it. A table that exceeds both limits is reported once for each. Deleted
+    /// databases and tables are not checked.
+    pub fn tables_over_current_limits(&self) -> Vec<LimitViolation> {
+        let columns_limit = self.num_columns_per_table_limit();
+        let inner = self.inner.read();
+        let mut violations = Vec::new();
+        for db in inner.databases.resource_iter().filter(|db| !db.deleted) {
+            for table in db.tables.resource_iter().filter(|t| !t.deleted) {
+                let mut check = |kind: LimitKind, count: usize, limit: usize| {
+                    if count > limit {
+                        violations.push(LimitViolation {
+                            db_id: db.id,
+                            db_name: Arc::clone(&db.name),
+                            table_id: table.table_id,
+                            table_name: Arc::clone(&table.table_name),
+                            kind,
+                            count,
+                            limit,
+                        });
+                    }
+                };
+                check(
+                    LimitKind::ColumnsPerTable,
+                    table.num_columns(),
+                    columns_limit,
+                );
+                check(
+                    LimitKind::TagColumnsPerTable,
+                    table.num_tag_columns(),
+                    NUM_TAG_COLUMNS_LIMIT,
+                );
+            }
+        }
+        violations
+    }
+
+    /// Count the live tables of the named database, along with their columns and caches, and the
+    /// database's processing engine triggers
+    ///
+    /// Returns `None` if the database does not exist.
+    pub fn get_database_stats(&self, db_name: &str) -> Option<DatabaseStats> {
+        let db_schema = self.db_schema(db_name)?;
+        let mut stats = DatabaseStats {
+            table_count: 0,
+            column_count: 0,
+            last_cache_count: 0,
+            distinct_cache_count: 0,
+            trigger_count: db_schema.processing_engine_triggers.len(),
+            retention_period: db_schema.retention_period,
+            deleted: db_schema.deleted,
+        };
+        for table in db_schema.tables.resource_iter().filter(|t| !t.deleted) {
+            stats.table_count += 1;
+            stats.column_count += table.columns.len();
+            stats.last_cache_count += table.last_caches.len();
+            stats.distinct_cache_count += table.distinct_caches.len();
+        }
+        Some(stats)
+    }
+
+    /// The retention and deletion policies of all databases in the catalog, other than the
+    /// internal database, in the order they were created
+    pub fn policies(&self) -> CatalogPolicies {
+        let databases = self
+            .inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| db.name.as_ref() != INTERNAL_DB_NAME)
+            .map(|db| DatabasePolicy {
+                name: Arc::clone(&db.name),
+                retention_period: match db.retention_period {
+                    RetentionPeriod::Indefinite => None,
+                    RetentionPeriod::Duration(duration) => {
+                        Some(humantime::format_duration(duration).to_string())
+                    }
+                },
+                deleted: db.deleted,
+                hard_delete_time: db.hard_delete_time.map(|time| time.to_string()),
+            })
+            .collect();
+        CatalogPolicies { databases }
+    }
+
+    /// The InfluxQL retention policy of every database that has not been deleted, paired with the
+    /// InfluxQL database name
+    ///
+    /// Database names are split as described in [`split_influxql_database_name`]. The duration of
+    /// the policy is the retention period of the database. The list is sorted by database and then
+    /// retention policy name.
+    pub fn all_influxql_retention_policies(&self) -> Vec<(Arc<str>, InfluxqlRp)> {
+        let mut policies = self
+            .inner
+            .read()
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted)
+            .map(|db| {
+                let (db_name, rp_name) = split_influxql_database_name(&db.name);
+                let duration = match db.retention_period {
+                    RetentionPeriod::Indefinite => None,
+                    RetentionPeriod::Duration(duration) => Some(duration),
+                };
+                (
+                    Arc::from(db_name),
+                    InfluxqlRp {
+                        name: Arc::from(rp_name),
+                        duration,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        policies.sort_unstable_by(|(a_db, a_rp), (b_db, b_rp)| {
+            a_db.cmp(b_db).then_with(|| a_rp.name.cmp(&b_rp.name))
+        });
+        policies
+    }
+
+    /// Render the retention and deletion policies of the catalog's databases as YAML, so that they
+    /// can be kept, and compared, in version control
+    ///
+    /// See [`Catalog::policies`].
+    pub fn export_policies_yaml(&self) -> Result<String> {
+        serde_yaml_ng::to_string(&self.policies()).map_err(|e| {
+            CatalogError::unexpected(format!("failed to render policies as YAML: {e}"))
+        })
+    }
+
+    /// A stable 64-bit digest of the logical schema of every database in the catalog
+    ///
+    /// This combines the names of the databases with the [`TableDefinition::schema_fingerprint`]
+    /// of each of their tables, and does not depend on ids or the order in which databases, tables
+    /// and columns were created. Two catalogs with the same logical schema, e.g., in different
+    /// clusters, produce the same digest. Deleted databases and tables, as well as the internal
+    /// database, are not included.
+    pub fn schema_digest(&self) -> u64 {
+        let inner = self.inner.read();
+        let databases = inner
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted && db.name.as_ref() != INTERNAL_DB_NAME)
+            .map(|db| {
+                let mut fingerprints = db
+                    .tables
+                    .resource_iter()
+                    .filter(|t| !t.deleted)
+                    .map(|t| t.schema_fingerprint())
+                    .collect::<Vec<_>>();
+                fingerprints.sort_unstable();
+                (Arc::clone(&db.name), fingerprints)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let mut hasher = Sha512::new();
+        for (name, fingerprints) in databases {
+            hasher.update(name.as_bytes());
+            hasher.update([0]);
+            for fingerprint in fingerprints {
+                hasher.update(fingerprint.to_be_bytes());
+            }
+            hasher.update([1]);
+        }
+        digest_to_u64(&hasher.finalize())
+    }
+
+    /// Get every column of every table in the catalog, along with the database and table it
+    /// belongs to
+    ///
+    /// Deleted databases and tables are skipped. The whole inventory is gathered under a single
+    /// acquisition of the catalog lock, so it is a consistent view, but can be costly for large
+    /// catalogs.
+    pub fn all_columns(&self) -> Vec<ColumnPath> {
+        let inner = self.inner.read();
+        let mut columns = Vec::new();
+        for db in inner.databases.resource_iter().filter(|db| !db.deleted) {
+            for table in db.tables.resource_iter().filter(|table| !table.deleted) {
+                columns.extend(table.columns.resource_iter().map(|column| ColumnPath {
+                    database_id: db.id,
+                    database_name: Arc::clone(&db.name),
+                    table_id: table.table_id,
+                    table_name: Arc::clone(&table.table_name),
+                    column_id: column.id,
+                    column_name: Arc::clone(&column.name),
+                    data_type: column.data_type,
+                    nullable: column.nullable,
+                }));
+            }
+        }
+        columns
+    }
+
     pub fn get_tokens(&self) -> Vec<Arc<TokenInfo>> {
         self.inner
             .read()
@@ -552,6 +1852,223 @@ impl Catalog {
             .collect()
     }
 
+    /// Look up a token by its hash, as with [`TokenProvider::get_token`], but only if it has not
+    /// expired according to the catalog's time provider
+    ///
+    /// [`TokenProvider::get_token`] itself returns expired tokens, so that the authenticator can
+    /// report that the token has expired rather than that it is unknown. Other callers that look
+    /// up tokens should use this method, so that an expired token is never treated as valid.
+    pub fn get_token_checked(&self, token_hash: Vec<u8>) -> Option<Arc<TokenInfo>> {
+        let now_ms = self.time_provider.now().timestamp_millis();
+        self.get_token(token_hash)
+            .filter(|token_info| token_info.expiry_millis() > now_ms)
+    }
+
+    /// Get the tokens that have a permission on the given resource
+    ///
+    /// A token is listed if any of its permissions applies to the resource, see
+    /// [`Permission::applies_to`]. Tokens with wildcard permissions, such as the operator token,
+    /// always match. This is useful for auditing who has access to a database before deleting it.
+    pub fn list_tokens_for_resource(
+        &self,
+        resource_type: ResourceType,
+        resource: ResourceIdentifier,
+    ) -> Vec<Arc<TokenInfo>> {
+        self.inner
+            .read()
+            .tokens
+            .repo()
+            .resource_iter()
+            .filter(|token| {
+                token
+                    .permissions
+                    .iter()
+                    .any(|permission| permission.applies_to(resource_type, &resource))
+            })
+            .map(Arc::clone)
+            .collect()
+    }
+
+    /// Get the tokens that have not yet expired, but whose expiry is before `t`
+    ///
+    /// Tokens without an expiry, e.g., the operator token, are never included. The tokens are
+    /// returned in order of expiry, soonest first.
+    pub fn tokens_expiring_before(&self, t: Time) -> Vec<Arc<TokenInfo>> {
+        let now = self.time_provider.now().timestamp_millis();
+        let threshold = t.timestamp_millis();
+        let mut tokens = self
+            .inner
+            .read()
+            .tokens
+            .repo()
+            .resource_iter()
+            .filter(|token| {
+                token
+                    .maybe_expiry_millis()
+                    .is_some_and(|expiry| expiry > now && expiry < threshold)
+            })
+            .map(Arc::clone)
+            .collect::<Vec<_>>();
+        tokens.sort_by_key(|token| token.expiry_millis());
+        tokens
+    }
+
+    /// Export an audit of every token's permissions as CSV
+    ///
+    /// The CSV has a header row, followed by one row per permission of each token, holding the
+    /// token name, its created and updated times, its expiry, and a description of the permission
+    /// in the same `<resource_type>:<resource_names>:<actions>` form used to create scoped
+    /// tokens, e.g., `db:foo,bar:read,write`. A token without any permissions has a single row
+    /// with an empty permission. Times are RFC 3339, and are empty if not set. Token hashes are
+    /// never included.
+    pub fn token_audit_csv(&self) -> String {
+        let inner = self.inner.read();
+        let mut tokens = inner.tokens.repo().resource_iter().collect::<Vec<_>>();
+        tokens.sort_by_key(|token| token.id);
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer
+            .write_record([
+                "token_name",
+                "created_at",
+                "updated_at",
+                "expiry",
+                "permission",
+            ])
+            .expect("write csv to in-memory buffer");
+        let rfc3339 = |millis: Option<i64>| {
+            millis
+                .and_then(Time::from_timestamp_millis)
+                .map(|time| time.to_rfc3339())
+                .unwrap_or_default()
+        };
+        for token in tokens {
+            let created_at = rfc3339(Some(token.created_at));
+            let updated_at = rfc3339(token.updated_at);
+            let expiry = rfc3339(token.maybe_expiry_millis());
+            let permissions = if token.permissions.is_empty() {
+                vec![String::new()]
+            } else {
+                token
+                    .permissions
+                    .iter()
+                    .map(|permission| describe_permission(&inner, permission))
+                    .collect()
+            };
+            for permission in permissions {
+                writer
+                    .write_record([
+                        token.name.as_ref(),
+                        &created_at,
+                        &updated_at,
+                        &expiry,
+                        &permission,
+                    ])
+                    .expect("write csv to in-memory buffer");
+            }
+        }
+        let bytes = writer.into_inner().expect("flush csv to in-memory buffer");
+        String::from_utf8(bytes).expect("csv output is valid utf-8")
+    }
+
+    /// Resolve the permissions of the token with the given id into the databases and tokens it
+    /// can access, and the actions it can perform on each
+    ///
+    /// A permission on all databases or tokens is expanded to those that currently exist, not
+    /// including deleted databases, so the view can change as resources are created. Ids that no
+    /// longer resolve to a resource are left out. Returns `None` if the token does not exist.
+    pub fn token_effective_access(&self, token_id: &TokenId) -> Option<TokenAccessView> {
+        let inner = self.inner.read();
+        let token = inner.tokens.repo().get_by_id(token_id)?;
+        if token.can(&AccessRequest::Admin) {
+            return Some(TokenAccessView::AllResources);
+        }
+
+        let mut databases: BTreeMap<Arc<str>, u16> = BTreeMap::new();
+        let mut tokens: BTreeMap<Arc<str>, u16> = BTreeMap::new();
+        for permission in &token.permissions {
+            if matches!(
+                permission.resource_type,
+                ResourceType::Database | ResourceType::Wildcard
+            ) {
+                let bits = match permission.actions {
+                    Actions::Database(DatabaseActions(bits)) => bits,
+                    Actions::Wildcard => u16::MAX,
+                    Actions::Token(_) => 0,
+                };
+                let names: Vec<Arc<str>> = match &permission.resource_identifier {
+                    ResourceIdentifier::Database(db_ids) => db_ids
+                        .iter()
+                        .filter_map(|id| inner.databases.get_by_id(id))
+                        .filter(|db| !db.deleted)
+                        .map(|db| Arc::clone(&db.name))
+                        .collect(),
+                    ResourceIdentifier::Wildcard => inner
+                        .databases
+                        .resource_iter()
+                        .filter(|db| !db.deleted)
+                        .map(|db| Arc::clone(&db.name))
+                        .collect(),
+                    ResourceIdentifier::Token(_) => vec![],
+                };
+                for name in names.into_iter().filter(|_| bits != 0) {
+                    *databases.entry(name).or_default() |= bits;
+                }
+            }
+            if matches!(
+                permission.resource_type,
+                ResourceType::Token | ResourceType::Wildcard
+            ) {
+                let bits = match permission.actions {
+                    Actions::Token(CrudActions(bits)) => bits,
+                    Actions::Wildcard => u16::MAX,
+                    Actions::Database(_) => 0,
+                };
+                let names: Vec<Arc<str>> = match &permission.resource_identifier {
+                    ResourceIdentifier::Token(token_ids) => token_ids
+                        .iter()
+                        .filter_map(|id| inner.tokens.repo().id_to_name(id))
+                        .collect(),
+                    ResourceIdentifier::Wildcard => inner
+                        .tokens
+                        .repo()
+                        .resource_iter()
+                        .map(|token| Arc::clone(&token.name))
+                        .collect(),
+                    ResourceIdentifier::Database(_) => vec![],
+                };
+                for name in names.into_iter().filter(|_| bits != 0) {
+                    *tokens.entry(name).or_default() |= bits;
+                }
+            }
+        }
+
+        let resolve = |resources: BTreeMap<Arc<str>, u16>, action_names: &[&'static str]| {
+            resources
+                .into_iter()
+                .map(|(name, bits)| ResourceAccess {
+                    name,
+                    actions: granted_action_names(bits, action_names),
+                })
+                .collect()
+        };
+        Some(TokenAccessView::Scoped {
+            databases: resolve(databases, DATABASE_ACTION_NAMES),
+            tokens: resolve(tokens, TOKEN_ACTION_NAMES),
+        })
+    }
+
+    /// Check if the named token is granted the given access
+    ///
+    /// Returns `false` if the token does not exist.
+    pub fn token_can(&self, token_name: &str, access_request: AccessRequest) -> bool {
+        self.inner
+            .read()
+            .tokens
+            .repo()
+            .get_by_name(token_name)
+            .is_some_and(|token| token.can(&access_request))
+    }
+
     pub async fn create_admin_token(&self, regenerate: bool) -> Result<(Arc<TokenInfo>, String)> {
         // if regen, if token is present already create a new token and hash and update the
         // existing token otherwise we should insert to catalog (essentially an upsert)
@@ -641,6 +2158,7 @@ impl Catalog {
             if self.inner.read().tokens.repo().contains_name(&token_name) {
                 return Err(CatalogError::TokenNameAlreadyExists(token_name.clone()));
             }
+            self.check_num_tokens_limit()?;
 
             let (token_id, created_at, expiry) = {
                 let mut inner = self.inner.write();
@@ -694,6 +2212,7 @@ impl Catalog {
             if self.inner.read().tokens.repo().contains_name(&token_name) {
                 return Err(CatalogError::TokenNameAlreadyExists(token_name.clone()));
             }
+            self.check_num_tokens_limit()?;
 
             let (token_id, created_at, expiry) = {
                 let mut inner = self.inner.write();
@@ -739,6 +2258,7 @@ impl Catalog {
 
     // Return a map of all retention periods indexed by their combined database & table IDs.
     pub fn get_retention_period_cutoff_map(&self) -> BTreeMap<(DbId, TableId), i64> {
+        let now = self.time_provider.now().timestamp_nanos();
         self.list_db_schema()
             .into_iter()
             .flat_map(|db_schema| {
@@ -748,14 +2268,28 @@ impl Catalog {
                         let db_id = db_schema.id();
                         let table_id = table_def.id();
                         db_schema
-                            .get_retention_period_cutoff_ts_nanos(self.time_provider())
-                            .map(|cutoff| ((db_id, table_id), cutoff))
+                            .effective_retention_for_table(&table_id)
+                            .map(|retention| ((db_id, table_id), now - retention.as_nanos() as i64))
                     })
                     .collect::<Vec<_>>()
             })
             .collect()
     }
 
+    /// The most aggressive, i.e., shortest, retention period that applies to the named table
+    ///
+    /// See [`DatabaseSchema::effective_retention_for_table`]. Returns `None` if the database or
+    /// table does not exist.
+    pub fn effective_retention_for_table(
+        &self,
+        db_name: &str,
+        table_name: &str,
+    ) -> Option<Duration> {
+        let db_schema = self.db_schema(db_name)?;
+        let table_id = db_schema.table_name_to_id(table_name)?;
+        db_schema.effective_retention_for_table(&table_id)
+    }
+
     pub fn get_generation_duration(&self, level: u8) -> Option<Duration> {
         self.inner
             .read()
@@ -772,25 +2306,357 @@ impl Catalog {
             .map(|(level, duration)| (*level, *duration))
             .collect()
     }
-}
 
-async fn create_internal_db(catalog: &Catalog) {
-    let result = catalog.create_database(INTERNAL_DB_NAME).await;
-    // what is the best outcome if "_internal" cannot be created?
-    match result {
-        Ok(_) => info!("created internal database"),
-        Err(err) => {
-            match err {
-                CatalogError::AlreadyExists => {
-                    // this is probably ok
-                    debug!("not creating internal db as it exists already");
-                }
-                _ => {
-                    // all other errors are unexpected state
-                    error!(?err, "unexpected error when creating internal db");
-                    panic!("cannot create internal db");
-                }
-            }
+    /// List every table, in databases that have not been deleted, along with the duration
+    /// configured for the given generation `level`
+    ///
+    /// Deleted tables are not included. If the level has no configured duration, this is empty.
+    pub fn tables_for_generation(&self, level: u8) -> Vec<(DbId, TableId, Duration)> {
+        let inner = self.inner.read();
+        let Some(duration) = inner.generation_config.duration_for_level(level) else {
+            return vec![];
+        };
+        inner
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted)
+            .flat_map(|db| {
+                db.tables
+                    .resource_iter()
+                    .filter(|table| !table.deleted)
+                    .map(move |table| (db.id, table.table_id, duration))
+            })
+            .collect()
+    }
+
+    /// Summarize the generation configuration of the catalog
+    pub fn generation_config_summary(&self) -> GenerationConfigSummary {
+        let levels = self
+            .list_generation_durations()
+            .into_iter()
+            .map(|(level, duration)| GenerationLevelSummary {
+                level,
+                duration_seconds: duration.as_secs(),
+            })
+            .collect::<Vec<_>>();
+        GenerationConfigSummary {
+            num_levels: levels.len(),
+            max_level: levels.last().map(|l| l.level),
+            levels,
+        }
+    }
+
+    /// List all last and distinct caches across all databases that have not been deleted
+    pub fn all_caches(&self) -> Vec<CacheRef> {
+        self.list_db_schema()
+            .into_iter()
+            .filter(|db| !db.deleted)
+            .flat_map(|db| {
+                let last_caches = db.list_last_caches().into_iter().map(|c| CacheRef {
+                    db_id: db.id,
+                    table_id: c.table_id,
+                    kind: CacheKind::Last(c.id),
+                    name: Arc::clone(&c.name),
+                });
+                let distinct_caches = db.list_distinct_caches().into_iter().map(|c| CacheRef {
+                    db_id: db.id,
+                    table_id: c.table_id,
+                    kind: CacheKind::Distinct(c.cache_id),
+                    name: Arc::clone(&c.cache_name),
+                });
+                last_caches.chain(distinct_caches).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Group the tables in the given database that have not been deleted by the ordered names of
+    /// their series key columns
+    ///
+    /// Returns an empty map if the database does not exist.
+    pub fn tables_by_series_key_shape(
+        &self,
+        db_name: &str,
+    ) -> BTreeMap<Vec<Arc<str>>, Vec<TableId>> {
+        let mut shapes: BTreeMap<Vec<Arc<str>>, Vec<TableId>> = BTreeMap::new();
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return shapes;
+        };
+        for table_def in db_schema.tables().filter(|t| !t.deleted) {
+            shapes
+                .entry(table_def.series_key_names.clone())
+                .or_default()
+                .push(table_def.table_id);
+        }
+        shapes
+    }
+
+    /// Audit the series keys of all tables in the catalog, including deleted ones, and report any
+    /// series key column ids that do not resolve to a column in their table
+    pub fn validate_all_series_keys(&self) -> Vec<(DbId, TableId, Vec<ColumnId>)> {
+        self.list_db_schema()
+            .into_iter()
+            .flat_map(|db| {
+                db.tables()
+                    .filter_map(|table| {
+                        table
+                            .validate_series_key()
+                            .err()
+                            .map(|missing| (db.id, table.table_id, missing))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Render the catalog as a Graphviz DOT graph
+    ///
+    /// Databases link to their tables and processing engine triggers, and tables link to their
+    /// last and distinct caches. Deleted databases and tables are not included.
+    pub fn to_dot(&self) -> String {
+        fn node(id: &str, label: String, shape: &str) -> String {
+            format!(
+                "    \"{id}\" [label=\"{}\", shape={shape}];",
+                dot_escape(&label)
+            )
+        }
+        fn edge(from: &str, to: &str) -> String {
+            format!("    \"{from}\" -> \"{to}\";")
+        }
+        fn dot_escape(s: &str) -> String {
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        let mut lines = vec!["digraph catalog {".to_string()];
+        for db in self.list_db_schema().into_iter().filter(|db| !db.deleted) {
+            let db_node = format!("db_{}", db.id);
+            lines.push(node(
+                &db_node,
+                format!("{} ({})", db.name, db.id),
+                "cylinder",
+            ));
+            for table in db.tables().filter(|t| !t.deleted) {
+                let table_node = format!("{db_node}_table_{}", table.table_id);
+                lines.push(node(
+                    &table_node,
+                    format!("{} ({})", table.table_name, table.table_id),
+                    "box",
+                ));
+                lines.push(edge(&db_node, &table_node));
+                for cache in table.last_caches.resource_iter() {
+                    let cache_node = format!("{table_node}_last_cache_{}", cache.id);
+                    lines.push(node(
+                        &cache_node,
+                        format!("last cache: {} ({})", cache.name, cache.id),
+                        "ellipse",
+                    ));
+                    lines.push(edge(&table_node, &cache_node));
+                }
+                for cache in table.distinct_caches.resource_iter() {
+                    let cache_node = format!("{table_node}_distinct_cache_{}", cache.cache_id);
+                    lines.push(node(
+                        &cache_node,
+                        format!("distinct cache: {} ({})", cache.cache_name, cache.cache_id),
+                        "ellipse",
+                    ));
+                    lines.push(edge(&table_node, &cache_node));
+                }
+            }
+            for trigger in db.processing_engine_triggers.resource_iter() {
+                let trigger_node = format!("{db_node}_trigger_{}", trigger.trigger_id);
+                lines.push(node(
+                    &trigger_node,
+                    format!("trigger: {} ({})", trigger.trigger_name, trigger.trigger_id),
+                    "diamond",
+                ));
+                lines.push(edge(&db_node, &trigger_node));
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Find field names in the given database that are defined with different types in different
+    /// tables
+    ///
+    /// Data for such fields cannot be unioned across tables without a cast. Returns an empty list
+    /// if the database does not exist or there are no conflicts.
+    pub fn field_type_conflicts(&self, db_name: &str) -> Vec<FieldTypeConflict> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        let mut by_field: BTreeMap<Arc<str>, Vec<(Arc<str>, InfluxFieldType)>> = BTreeMap::new();
+        for (table_name, field_name, field_type) in db_schema.all_field_keys() {
+            by_field
+                .entry(field_name)
+                .or_default()
+                .push((table_name, field_type));
+        }
+        by_field
+            .into_iter()
+            .filter(|(_, table_types)| {
+                table_types
+                    .first()
+                    .is_some_and(|(_, first)| table_types.iter().any(|(_, t)| t != first))
+            })
+            .map(|(field_name, table_types)| FieldTypeConflict {
+                field_name,
+                table_types,
+            })
+            .collect()
+    }
+
+    /// Find tables in the given database that have fields of the given type
+    ///
+    /// Returns the id of each such table along with the names of its fields of that type, or an
+    /// empty list if the database does not exist. Deleted tables are not included.
+    pub fn tables_with_field_type(
+        &self,
+        db_name: &str,
+        ty: InfluxFieldType,
+    ) -> Vec<(TableId, Vec<Arc<str>>)> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        let mut tables: Vec<(TableId, Vec<Arc<str>>)> = vec![];
+        for (table_name, field_name, field_type) in db_schema.all_field_keys() {
+            if field_type != ty {
+                continue;
+            }
+            let table_id = db_schema
+                .table_name_to_id(&table_name)
+                .expect("table listed in field keys should exist");
+            // field keys are listed table by table:
+            match tables.last_mut() {
+                Some((id, fields)) if *id == table_id => fields.push(field_name),
+                _ => tables.push((table_id, vec![field_name])),
+            }
+        }
+        tables
+    }
+
+    /// Describe how a write of the given measurement to the named database would resolve its
+    /// table, without making any changes to the catalog
+    ///
+    /// If the measurement's table was soft deleted more than once, the most recently created of
+    /// the deleted tables is reported. A database that does not exist resolves to
+    /// [`TableResolution::New`], since a write would create both the database and the table.
+    pub fn resolve_or_describe_table(&self, db_name: &str, measurement: &str) -> TableResolution {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return TableResolution::New;
+        };
+        if let Some(table) = db_schema.table_definition(measurement) {
+            return if table.deleted {
+                TableResolution::SoftDeleted {
+                    table_id: table.table_id,
+                    deleted_name: Arc::clone(&table.table_name),
+                }
+            } else {
+                TableResolution::Existing(table.table_id)
+            };
+        }
+        db_schema
+            .tables()
+            .filter(|table| table.deleted && is_deleted_name_of(&table.table_name, measurement))
+            .max_by_key(|table| table.table_id)
+            .map_or(TableResolution::New, |table| TableResolution::SoftDeleted {
+                table_id: table.table_id,
+                deleted_name: Arc::clone(&table.table_name),
+            })
+    }
+
+    /// Find tables in the given database whose sort key does not agree with their series key
+    ///
+    /// See [`TableDefinition::sort_key_consistent`]. This is intended as an audit to run after
+    /// the catalog is loaded. Returns an empty list if the database does not exist.
+    pub fn tables_with_inconsistent_sort_key(&self, db_name: &str) -> Vec<TableId> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        db_schema
+            .tables()
+            .filter(|table| !table.sort_key_consistent())
+            .map(|table| table.table_id)
+            .collect()
+    }
+
+    /// Find tables in the given database that have more than `threshold` columns
+    ///
+    /// Returns the id and column count of each such table, or an empty list if the database does
+    /// not exist.
+    pub fn wide_tables(&self, db_name: &str, threshold: usize) -> Vec<(TableId, usize)> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        db_schema
+            .tables()
+            .filter(|table_def| !table_def.deleted)
+            .map(|table_def| (table_def.table_id, table_def.num_columns()))
+            .filter(|(_, num_columns)| *num_columns > threshold)
+            .collect()
+    }
+
+    /// Get the Arrow schema of a table as an encapsulated Arrow IPC schema message
+    ///
+    /// See [`TableDefinition::schema_ipc_bytes`].
+    pub fn table_schema_ipc(&self, db_name: &str, table_name: &str) -> Result<Vec<u8>> {
+        let Some(table_def) = self
+            .db_schema(db_name)
+            .and_then(|db| db.table_definition(table_name))
+        else {
+            return Err(CatalogError::NotFound);
+        };
+        table_def.schema_ipc_bytes()
+    }
+
+    /// Summarize the distinct caches defined in the given database
+    ///
+    /// Returns an empty list if the database does not exist.
+    pub fn distinct_cache_summaries(&self, db_name: &str) -> Vec<DistinctCacheSummary> {
+        let Some(db_schema) = self.db_schema(db_name) else {
+            return vec![];
+        };
+        db_schema
+            .tables()
+            .filter(|table_def| !table_def.deleted)
+            .flat_map(|table_def| {
+                table_def
+                    .distinct_caches
+                    .resource_iter()
+                    .map(|cache| DistinctCacheSummary {
+                        table_name: Arc::clone(&table_def.table_name),
+                        cache_name: Arc::clone(&cache.cache_name),
+                        columns: cache
+                            .column_ids
+                            .iter()
+                            .map(|id| table_def.column_id_to_name_unchecked(id))
+                            .collect(),
+                        max_cardinality: cache.max_cardinality,
+                        max_age: humantime::format_duration(cache.max_age_seconds.into())
+                            .to_string(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+async fn create_internal_db(catalog: &Catalog) {
+    let result = catalog.create_database(INTERNAL_DB_NAME).await;
+    // what is the best outcome if "_internal" cannot be created?
+    match result {
+        Ok(_) => info!("created internal database"),
+        Err(err) => {
+            match err {
+                CatalogError::AlreadyExists => {
+                    // this is probably ok
+                    debug!("not creating internal db as it exists already");
+                }
+                _ => {
+                    // all other errors are unexpected state
+                    error!(?err, "unexpected error when creating internal db");
+                    panic!("cannot create internal db");
+                }
+            }
         }
     };
 }
@@ -850,7 +2716,9 @@ impl Catalog {
     ) -> Result<Self> {
         let store = ObjectStoreCatalog::new(catalog_id, checkpoint_interval, store);
         let inner = store.load_or_create_catalog().await?;
-        let subscriptions = Default::default();
+        let subscriptions = Arc::new(tokio::sync::RwLock::new(CatalogSubscriptions::new(
+            &metric_registry,
+        )));
 
         let catalog = Self {
             state: parking_lot::Mutex::new(CatalogState::Active),
@@ -862,16 +2730,23 @@ impl Catalog {
             inner: RwLock::new(inner),
             limits: Default::default(),
             args: Default::default(),
+            change_log: Default::default(),
+            fair_write_queue: Default::default(),
         };
 
         create_internal_db(&catalog).await;
+        catalog.metrics.record_catalog_size(&catalog.inner.read());
         Ok(catalog)
     }
 }
 
 impl TokenProvider for Catalog {
     fn get_token(&self, token_hash: Vec<u8>) -> Option<Arc<TokenInfo>> {
-        self.inner.read().tokens.hash_to_info(token_hash)
+        self.inner
+            .read()
+            .tokens
+            .hash_to_info(token_hash)
+            .filter(|token_info| !token_info.disabled)
     }
 }
 
@@ -900,6 +2775,15 @@ fn database_or_deletion_status(
     }
 }
 
+/// The status of a soft-deleted resource at `now`, which is hard deleted once its hard deletion
+/// time has passed
+fn deletion_status_at(hard_delete_time: Option<Time>, now: Time) -> DeletionStatus {
+    hard_delete_time
+        .and_then(|time| now.checked_duration_since(time))
+        .map(DeletionStatus::Hard)
+        .unwrap_or(DeletionStatus::Soft)
+}
+
 fn table_deletion_status(
     db_schema: &DatabaseSchema,
     table_id: TableId,
@@ -1096,6 +2980,9 @@ pub struct InnerCatalog {
     pub(crate) databases: Repository<DbId, DatabaseSchema>,
     /// Collection of tokens in the catalog
     pub(crate) tokens: TokenRepository,
+    /// The operator-supplied label of the checkpoint this catalog was loaded from, or that was
+    /// most recently forced
+    pub(crate) checkpoint_label: Option<String>,
 }
 
 impl InnerCatalog {
@@ -1111,6 +2998,7 @@ impl InnerCatalog {
             // help us prevent starting a catalog that avoids this case, but we also need to keep
             // backward compatibility so, just defaulting this for now...
             generation_config: Default::default(),
+            checkpoint_label: None,
         }
     }
 
@@ -1147,12 +3035,17 @@ impl InnerCatalog {
         );
         let updated = match catalog_batch {
             CatalogBatch::Node(root_batch) => self.apply_node_batch(root_batch)?,
-            CatalogBatch::Database(database_batch) => self.apply_database_batch(database_batch)?,
+            CatalogBatch::Database(database_batch) => {
+                self.apply_database_batch(database_batch, sequence)?
+            }
             CatalogBatch::Token(token_batch) => self.apply_token_batch(token_batch)?,
             CatalogBatch::Delete(delete_batch) => self.apply_delete_batch(delete_batch)?,
             CatalogBatch::Generation(generation_batch) => {
                 self.apply_generation_batch(generation_batch)?
             }
+            CatalogBatch::MultiDatabase(multi_batch) => {
+                self.apply_multi_database_batch(multi_batch, sequence)?
+            }
         };
 
         Ok(updated.then(|| {
@@ -1275,23 +3168,66 @@ impl InnerCatalog {
                         .delete_token(delete_token_details.token_name.to_owned())?;
                     true
                 }
+                TokenCatalogOp::UpdateTokenPermissions(update_token_permissions_details) => {
+                    self.tokens.update_token_permissions(
+                        update_token_permissions_details.token_id,
+                        update_token_permissions_details.permissions.clone(),
+                        update_token_permissions_details.updated_at,
+                    )?;
+                    true
+                }
+                TokenCatalogOp::SetTokenDisabled(set_token_disabled_details) => {
+                    self.tokens.set_token_disabled(
+                        set_token_disabled_details.token_id,
+                        set_token_disabled_details.disabled,
+                        set_token_disabled_details.updated_at,
+                    )?;
+                    true
+                }
+                TokenCatalogOp::UpdateTokenExpiry(update_token_expiry_details) => {
+                    self.tokens.update_token_expiry(
+                        update_token_expiry_details.token_id,
+                        update_token_expiry_details.expiry,
+                        update_token_expiry_details.updated_at,
+                    )?;
+                    true
+                }
+                TokenCatalogOp::RenameToken(rename_token_details) => {
+                    self.tokens.rename_token(
+                        rename_token_details.token_id,
+                        Arc::clone(&rename_token_details.new_name),
+                        rename_token_details.updated_at,
+                    )?;
+                    true
+                }
             };
         }
 
         Ok(is_updated)
     }
 
-    fn apply_database_batch(&mut self, database_batch: &DatabaseBatch) -> Result<bool> {
+    fn apply_database_batch(
+        &mut self,
+        database_batch: &DatabaseBatch,
+        sequence: CatalogSequenceNumber,
+    ) -> Result<bool> {
         if let Some(db) = self.databases.get_by_id(&database_batch.database_id) {
-            let Some(new_db) = DatabaseSchema::new_if_updated_from_batch(&db, database_batch)?
+            let Some(mut new_db) = DatabaseSchema::new_if_updated_from_batch(&db, database_batch)?
             else {
                 return Ok(false);
             };
+            // a rename must not take the name of another database, as the repository's name
+            // mapping would no longer point to that database:
+            if new_db.name != db.name && self.databases.contains_name(&new_db.name) {
+                return Err(CatalogError::AlreadyExists);
+            }
+            new_db.last_modified_sequence = Some(sequence);
             self.databases
                 .update(db.id, new_db)
                 .expect("existing database should be updated");
         } else {
-            let new_db = DatabaseSchema::new_from_batch(database_batch)?;
+            let mut new_db = DatabaseSchema::new_from_batch(database_batch)?;
+            new_db.last_modified_sequence = Some(sequence);
             self.databases
                 .insert(new_db.id, new_db)
                 .expect("new database should be inserted");
@@ -1299,6 +3235,18 @@ impl InnerCatalog {
         Ok(true)
     }
 
+    fn apply_multi_database_batch(
+        &mut self,
+        multi_batch: &MultiDatabaseBatch,
+        sequence: CatalogSequenceNumber,
+    ) -> Result<bool> {
+        let mut updated = false;
+        for database_batch in &multi_batch.batches {
+            updated |= self.apply_database_batch(database_batch, sequence)?;
+        }
+        Ok(updated)
+    }
+
     fn apply_delete_batch(&mut self, delete_batch: &DeleteBatch) -> Result<bool> {
         let mut updated = false;
         for op in &delete_batch.ops {
@@ -1405,6 +3353,25 @@ impl GenerationConfig {
     }
 }
 
+/// Summary of the catalog's generation configuration, as produced by
+/// [`Catalog::generation_config_summary`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GenerationConfigSummary {
+    /// The duration of each configured level, ordered by level
+    pub levels: Vec<GenerationLevelSummary>,
+    /// The number of configured levels
+    pub num_levels: usize,
+    /// The highest configured level, or `None` if no levels are configured
+    pub max_level: Option<u8>,
+}
+
+/// The configured duration of a single generation level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GenerationLevelSummary {
+    pub level: u8,
+    pub duration_seconds: u64,
+}
+
 /// The definition of a node in the catalog
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct NodeDefinition {
@@ -1488,6 +3455,20 @@ pub struct DatabaseSchema {
     pub deleted: bool,
     /// The time when the database is scheduled to be hard deleted.
     pub hard_delete_time: Option<Time>,
+    /// The name the database had before it was deleted, `None` if it is not deleted
+    pub original_name: Option<Arc<str>>,
+    /// Optional user-provided description of the database
+    pub description: Option<Arc<str>>,
+    /// The time, in nanoseconds, of the batch that created the database
+    ///
+    /// This is `None` for databases created before creation times were tracked.
+    pub created_time_ns: Option<i64>,
+    /// Optional value that queries render in place of a missing tag value, instead of `NULL`
+    pub null_tag_placeholder: Option<Arc<str>>,
+    /// The sequence number of the last catalog batch that modified the database
+    ///
+    /// This is `None` for databases last modified before modification sequences were tracked.
+    pub last_modified_sequence: Option<CatalogSequenceNumber>,
 }
 
 impl DatabaseSchema {
@@ -1500,6 +3481,11 @@ impl DatabaseSchema {
             processing_engine_triggers: Repository::new(),
             deleted: false,
             hard_delete_time: None,
+            original_name: None,
+            description: None,
+            created_time_ns: None,
+            null_tag_placeholder: None,
+            last_modified_sequence: None,
         }
     }
 
@@ -1529,6 +3515,11 @@ impl DatabaseSchema {
 
         for catalog_op in &database_batch.ops {
             schema = catalog_op.update_schema(schema)?;
+            if matches!(catalog_op, DatabaseCatalogOp::CreateDatabase(_))
+                && schema.created_time_ns.is_none()
+            {
+                schema.to_mut().created_time_ns = Some(database_batch.time_ns);
+            }
         }
         // If there were updates then it will have become owned, so we should return the new schema.
         if let Cow::Owned(schema) = schema {
@@ -1596,6 +3587,12 @@ impl DatabaseSchema {
         self.tables.get_by_id(table_id)
     }
 
+    /// The id that the next table created in the database will be assigned, see
+    /// [`Catalog::peek_next_db_id`]
+    pub fn peek_next_table_id(&self) -> TableId {
+        self.tables.next_id()
+    }
+
     pub fn table_ids(&self) -> Vec<TableId> {
         self.tables.id_iter().copied().collect()
     }
@@ -1623,6 +3620,27 @@ impl DatabaseSchema {
         self.tables.id_to_name(table_id)
     }
 
+    /// List the field keys of all tables in the database that have not been deleted
+    ///
+    /// Each entry is a tuple of the table name, field name, and field type.
+    pub fn all_field_keys(&self) -> Vec<(Arc<str>, Arc<str>, InfluxFieldType)> {
+        self.tables
+            .resource_iter()
+            .filter(|t| !t.deleted)
+            .flat_map(|t| {
+                t.columns
+                    .resource_iter()
+                    .filter_map(|c| match c.data_type {
+                        InfluxColumnType::Field(field_type) => {
+                            Some((Arc::clone(&t.table_name), Arc::clone(&c.name), field_type))
+                        }
+                        InfluxColumnType::Tag | InfluxColumnType::Timestamp => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     pub fn list_distinct_caches(&self) -> Vec<Arc<DistinctCacheDefinition>> {
         self.tables
             .resource_iter()
@@ -1661,6 +3679,14 @@ impl DatabaseSchema {
         )
     }
 
+    /// The retention period of the database in nanoseconds, or `None` if retention is indefinite
+    pub fn retention_window_ns(&self) -> Option<i64> {
+        match self.retention_period {
+            RetentionPeriod::Duration(d) => Some(d.as_nanos() as i64),
+            RetentionPeriod::Indefinite => None,
+        }
+    }
+
     // Return the oldest allowable timestamp for the given table according to the
     // currently-available set of retention policies. This is returned as a number of nanoseconds
     // since the Unix Epoch.
@@ -1668,13 +3694,25 @@ impl DatabaseSchema {
         &self,
         time_provider: Arc<dyn TimeProvider>,
     ) -> Option<i64> {
-        let retention_period = match self.retention_period {
-            RetentionPeriod::Duration(d) => Some(d.as_nanos() as u64),
-            RetentionPeriod::Indefinite => None,
-        }?;
+        let retention_period = self.retention_window_ns()?;
 
         let now = time_provider.now().timestamp_nanos();
-        Some(now - retention_period as i64)
+        Some(now - retention_period)
+    }
+
+    /// The most aggressive, i.e., shortest, retention period that applies to the given table
+    ///
+    /// Both the table's own retention period and that of the database apply. Returns `None` if
+    /// both are indefinite, or if the table does not exist.
+    pub fn effective_retention_for_table(&self, table_id: &TableId) -> Option<Duration> {
+        let table = self.tables.get_by_id(table_id)?;
+        [table.retention_period, self.retention_period]
+            .into_iter()
+            .filter_map(|retention_period| match retention_period {
+                RetentionPeriod::Duration(duration) => Some(duration),
+                RetentionPeriod::Indefinite => None,
+            })
+            .min()
     }
 
     /// Returns the deletion status of a table by its table ID
@@ -1817,7 +3855,21 @@ impl UpdateDatabaseSchema for DatabaseCatalogOp {
             DatabaseCatalogOp::SoftDeleteDatabase(delete_database) => {
                 delete_database.update_schema(schema)
             }
+            DatabaseCatalogOp::UndeleteDatabase(undelete_database) => {
+                undelete_database.update_schema(schema)
+            }
             DatabaseCatalogOp::SoftDeleteTable(delete_table) => delete_table.update_schema(schema),
+            DatabaseCatalogOp::UndeleteTable(undelete_table) => {
+                undelete_table.update_schema(schema)
+            }
+            DatabaseCatalogOp::SetPrimaryPartitionTag(update) => update.update_schema(schema),
+            DatabaseCatalogOp::RenameTable(rename) => rename.update_schema(schema),
+            DatabaseCatalogOp::SetColumnAllowedValues(update) => update.update_schema(schema),
+            DatabaseCatalogOp::SetColumnIndexed(update) => update.update_schema(schema),
+            DatabaseCatalogOp::SetColumnDescription(update) => update.update_schema(schema),
+            DatabaseCatalogOp::DropColumn(drop_column) => drop_column.update_schema(schema),
+            DatabaseCatalogOp::ReorderSeriesKey(reorder) => reorder.update_schema(schema),
+            DatabaseCatalogOp::AlterColumnType(alter) => alter.update_schema(schema),
             DatabaseCatalogOp::CreateTrigger(create_trigger) => {
                 create_trigger.update_schema(schema)
             }
@@ -1832,6 +3884,11 @@ impl UpdateDatabaseSchema for DatabaseCatalogOp {
             }
             DatabaseCatalogOp::SetRetentionPeriod(update) => update.update_schema(schema),
             DatabaseCatalogOp::ClearRetentionPeriod(update) => update.update_schema(schema),
+            DatabaseCatalogOp::SetRetentionPeriodForTable(update) => update.update_schema(schema),
+            DatabaseCatalogOp::ClearRetentionPeriodForTable(update) => update.update_schema(schema),
+            DatabaseCatalogOp::SetDatabaseDescription(update) => update.update_schema(schema),
+            DatabaseCatalogOp::SetNullTagPlaceholder(update) => update.update_schema(schema),
+            DatabaseCatalogOp::RenameDatabase(rename) => rename.update_schema(schema),
         }
     }
 }
@@ -1876,12 +3933,34 @@ impl UpdateDatabaseSchema for SoftDeleteDatabaseLog {
             let deletion_time = Time::from_timestamp_nanos(self.deletion_time);
             owned.name = make_new_name_using_deleted_time(&self.database_name, deletion_time);
             owned.deleted = true;
+            owned.original_name = Some(Arc::clone(
+                self.original_name.as_ref().unwrap_or(&self.database_name),
+            ));
         }
         owned.hard_delete_time = self.hard_deletion_time.map(Time::from_timestamp_nanos);
         Ok(schema)
     }
 }
 
+impl UpdateDatabaseSchema for UndeleteDatabaseLog {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        if !schema.deleted {
+            return Err(CatalogError::NotDeleted);
+        }
+        // the catalog checks that the name is not taken by another database when the batch is
+        // applied:
+        let owned = schema.to_mut();
+        owned.name = Arc::clone(&self.database_name);
+        owned.deleted = false;
+        owned.hard_delete_time = None;
+        owned.original_name = None;
+        Ok(schema)
+    }
+}
+
 impl UpdateDatabaseSchema for SoftDeleteTableLog {
     fn update_schema<'a>(
         &self,
@@ -1901,6 +3980,9 @@ impl UpdateDatabaseSchema for SoftDeleteTableLog {
                 let table_name = make_new_name_using_deleted_time(&self.table_name, deletion_time);
                 new_table_def.deleted = true;
                 new_table_def.table_name = table_name;
+                new_table_def.original_name = Some(Arc::clone(
+                    self.original_name.as_ref().unwrap_or(&self.table_name),
+                ));
             }
             new_table_def.hard_delete_time =
                 self.hard_deletion_time.map(Time::from_timestamp_nanos);
@@ -1913,6 +3995,37 @@ impl UpdateDatabaseSchema for SoftDeleteTableLog {
     }
 }
 
+impl UpdateDatabaseSchema for UndeleteTableLog {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        let Some(mut table) = schema.tables.get_by_id(&self.table_id) else {
+            return Err(CatalogError::TableNotFound {
+                db_name: Arc::clone(&schema.name),
+                table_name: Arc::clone(&self.deleted_name),
+            });
+        };
+        if !table.deleted {
+            return Err(CatalogError::NotDeleted);
+        }
+        if schema.tables.contains_name(&self.table_name) {
+            return Err(CatalogError::AlreadyExists);
+        }
+        let restored = Arc::make_mut(&mut table);
+        restored.deleted = false;
+        restored.hard_delete_time = None;
+        restored.original_name = None;
+        restored.table_name = Arc::clone(&self.table_name);
+        schema
+            .to_mut()
+            .tables
+            .update(self.table_id, table)
+            .expect("the table should exist");
+        Ok(schema)
+    }
+}
+
 impl UpdateDatabaseSchema for SetRetentionPeriodLog {
     fn update_schema<'a>(
         &self,
@@ -1935,29 +4048,87 @@ impl UpdateDatabaseSchema for ClearRetentionPeriodLog {
     }
 }
 
-struct EnableTrigger(TriggerIdentifier);
-struct DisableTrigger(TriggerIdentifier);
+impl UpdateDatabaseSchema for SetDatabaseDescriptionLog {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        schema.to_mut().description = self.description.as_ref().map(Arc::clone);
+        Ok(schema)
+    }
+}
 
-impl UpdateDatabaseSchema for EnableTrigger {
+impl UpdateDatabaseSchema for SetNullTagPlaceholderLog {
     fn update_schema<'a>(
         &self,
         mut schema: Cow<'a, DatabaseSchema>,
     ) -> Result<Cow<'a, DatabaseSchema>> {
-        let Some(trigger) = schema
-            .processing_engine_triggers
-            .get_by_name(&self.0.trigger_name)
-        else {
-            return Err(CatalogError::ProcessingEngineTriggerNotFound {
-                database_name: self.0.db_name.to_string(),
-                trigger_name: self.0.trigger_name.to_string(),
-            });
-        };
-        if !trigger.disabled {
-            return Ok(schema);
-        }
-        let mut mut_trigger = schema
-            .processing_engine_triggers
-            .get_by_id(&trigger.trigger_id)
+        schema.to_mut().null_tag_placeholder = self.placeholder.as_ref().map(Arc::clone);
+        Ok(schema)
+    }
+}
+
+impl UpdateDatabaseSchema for RenameTableLog {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        let Some(table) = schema.tables.get_by_id(&self.table_id) else {
+            return Err(CatalogError::TableNotFound {
+                db_name: Arc::clone(&schema.name),
+                table_name: Arc::clone(&self.old_name),
+            });
+        };
+        if table.table_name == self.new_name {
+            return Ok(schema);
+        }
+        if schema.tables.contains_name(&self.new_name) {
+            return Err(CatalogError::AlreadyExists);
+        }
+        let mut renamed = table.as_ref().clone();
+        renamed.rename(Arc::clone(&self.new_name));
+        schema
+            .to_mut()
+            .update_table(self.table_id, Arc::new(renamed))?;
+        Ok(schema)
+    }
+}
+
+impl UpdateDatabaseSchema for RenameDatabaseLog {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        if schema.name != self.new_name {
+            schema.to_mut().name = Arc::clone(&self.new_name);
+        }
+        Ok(schema)
+    }
+}
+
+struct EnableTrigger(TriggerIdentifier);
+struct DisableTrigger(TriggerIdentifier);
+
+impl UpdateDatabaseSchema for EnableTrigger {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        let Some(trigger) = schema
+            .processing_engine_triggers
+            .get_by_name(&self.0.trigger_name)
+        else {
+            return Err(CatalogError::ProcessingEngineTriggerNotFound {
+                database_name: self.0.db_name.to_string(),
+                trigger_name: self.0.trigger_name.to_string(),
+            });
+        };
+        if !trigger.disabled {
+            return Ok(schema);
+        }
+        let mut mut_trigger = schema
+            .processing_engine_triggers
+            .get_by_id(&trigger.trigger_id)
             .expect("already checked containment");
         Arc::make_mut(&mut mut_trigger).disabled = false;
         schema
@@ -2056,6 +4227,79 @@ impl UpdateDatabaseSchema for DeleteTriggerLog {
     }
 }
 
+/// Validate a database name
+///
+/// A valid name:
+/// - Starts with a letter or a number
+/// - Is ASCII not UTF-8
+/// - Contains only letters, numbers, underscores or hyphens
+/// - if `accept_rp` is true, then a single slash ('/') is allowed, separating the
+///   database name from the retention policy name, e.g., '<db_name>/<rp_name>'; the characters
+///   are not otherwise restricted, as v1 clients may use names such as `foo.bar/autogen`
+///
+/// This is used both by the HTTP API and by the catalog when it creates a database, see
+/// [`Catalog::validate_database_name`].
+pub fn validate_db_name(name: &str, accept_rp: bool) -> Result<(), ValidateDbNameError> {
+    if name.is_empty() {
+        return Err(ValidateDbNameError::Empty);
+    }
+    let mut is_first_char = true;
+    let mut rp_seperator_found = false;
+    let mut last_char = None;
+    for char in name.chars() {
+        if !char.is_ascii() {
+            return Err(ValidateDbNameError::InvalidChar);
+        }
+        if !is_first_char {
+            match (accept_rp, rp_seperator_found, char) {
+                (true, true, RETENTION_POLICY_SEPARATOR) => {
+                    return Err(ValidateDbNameError::InvalidRetentionPolicy);
+                }
+                (true, false, RETENTION_POLICY_SEPARATOR) => {
+                    rp_seperator_found = true;
+                }
+                (false, _, char)
+                    if !(char.is_ascii_alphanumeric() || char == '_' || char == '-') =>
+                {
+                    return Err(ValidateDbNameError::InvalidChar);
+                }
+                _ => (),
+            }
+        } else {
+            if !char.is_ascii_alphanumeric() {
+                return Err(ValidateDbNameError::InvalidStartChar);
+            }
+            is_first_char = false;
+        }
+        last_char.replace(char);
+    }
+
+    if last_char.is_some_and(|c| c == RETENTION_POLICY_SEPARATOR) {
+        return Err(ValidateDbNameError::InvalidRetentionPolicy);
+    }
+
+    Ok(())
+}
+
+/// Why a database name is not valid, see [`validate_db_name`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ValidateDbNameError {
+    #[error(
+        "invalid character in database name: must be ASCII, \
+        containing only letters, numbers, underscores, or hyphens"
+    )]
+    InvalidChar,
+    #[error("db name did not start with a number or letter")]
+    InvalidStartChar,
+    #[error(
+        "db name with invalid retention policy, if providing a \
+        retention policy name, must be of form '<db_name>/<rp_name>'"
+    )]
+    InvalidRetentionPolicy,
+    #[error("db name cannot be empty")]
+    Empty,
+}
+
 fn make_new_name_using_deleted_time(name: &str, deletion_time: Time) -> Arc<str> {
     Arc::from(format!(
         "{}-{}",
@@ -2064,6 +4308,133 @@ fn make_new_name_using_deleted_time(name: &str, deletion_time: Time) -> Arc<str>
     ))
 }
 
+/// Whether `deleted_name` is the name given to a resource originally named `name` when it was soft
+/// deleted
+///
+/// See [`make_new_name_using_deleted_time`].
+fn is_deleted_name_of(deleted_name: &str, name: &str) -> bool {
+    original_name_of_deleted(deleted_name) == Some(name)
+}
+
+/// The name a resource had before it was soft deleted and given `deleted_name`, or `None` if
+/// `deleted_name` does not have the deletion time appended
+///
+/// See [`make_new_name_using_deleted_time`].
+pub(crate) fn original_name_of_deleted(deleted_name: &str) -> Option<&str> {
+    deleted_name
+        .rsplit_once('-')
+        .filter(|(_, time)| {
+            chrono::NaiveDateTime::parse_from_str(time, SOFT_DELETION_TIME_FORMAT).is_ok()
+        })
+        .map(|(name, _)| name)
+}
+
+/// The names of the database actions, in the order of the bits that represent them
+const DATABASE_ACTION_NAMES: &[&str] = &["read", "write", "create"];
+
+/// The names of the token actions, in the order of the bits that represent them
+const TOKEN_ACTION_NAMES: &[&str] = &["read", "write", "create", "delete"];
+
+/// The names of the actions whose bits are set in `bits`
+fn granted_action_names(bits: u16, names: &[&'static str]) -> Vec<&'static str> {
+    names
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| bits & (1 << i) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Describe a permission as `<resource_type>:<resource_names>:<actions>`, resolving database and
+/// token ids to their names
+///
+/// Ids that no longer resolve to a name are written as the id itself.
+fn describe_permission(inner: &InnerCatalog, permission: &Permission) -> String {
+    let resource_type = match permission.resource_type {
+        ResourceType::Database => "db",
+        ResourceType::Token => "token",
+        ResourceType::Wildcard => "*",
+    };
+    let resource_names = match &permission.resource_identifier {
+        ResourceIdentifier::Database(db_ids) => db_ids
+            .iter()
+            .map(|id| {
+                inner
+                    .databases
+                    .id_to_name(id)
+                    .map_or_else(|| id.to_string(), |name| name.to_string())
+            })
+            .collect::<Vec<_>>(),
+        ResourceIdentifier::Token(token_ids) => token_ids
+            .iter()
+            .map(|id| {
+                inner
+                    .tokens
+                    .repo()
+                    .id_to_name(id)
+                    .map_or_else(|| id.to_string(), |name| name.to_string())
+            })
+            .collect(),
+        ResourceIdentifier::Wildcard => vec!["*".to_string()],
+    };
+    let actions = match permission.actions {
+        Actions::Database(DatabaseActions(bits)) => {
+            granted_action_names(bits, DATABASE_ACTION_NAMES)
+        }
+        Actions::Token(CrudActions(bits)) => granted_action_names(bits, TOKEN_ACTION_NAMES),
+        Actions::Wildcard => vec!["*"],
+    };
+    format!(
+        "{resource_type}:{names}:{actions}",
+        names = resource_names.join(","),
+        actions = actions.join(",")
+    )
+}
+
+/// Take the leading 8 bytes of a hash digest as a `u64`
+fn digest_to_u64(digest: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Whether a column of the `existing` type can be promoted to the `attempted` type
+///
+/// Only integer and unsigned integer fields can be promoted, and only to a float.
+pub(crate) fn is_type_promotion(existing: InfluxColumnType, attempted: InfluxColumnType) -> bool {
+    matches!(
+        (existing, attempted),
+        (
+            InfluxColumnType::Field(InfluxFieldType::Integer | InfluxFieldType::UInteger),
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        )
+    )
+}
+
+/// Whether a field column of the `existing` type can be changed to the `attempted` type by
+/// [`Catalog::alter_column_type`]
+///
+/// Integer and unsigned integer fields can be widened to a float, as with type promotion, and
+/// any field that is not a string can be changed to a string.
+pub(crate) fn is_column_type_change(
+    existing: InfluxColumnType,
+    attempted: InfluxColumnType,
+) -> bool {
+    is_type_promotion(existing, attempted)
+        || matches!(
+            (existing, attempted),
+            (
+                InfluxColumnType::Field(
+                    InfluxFieldType::Integer
+                        | InfluxFieldType::UInteger
+                        | InfluxFieldType::Float
+                        | InfluxFieldType::Boolean,
+                ),
+                InfluxColumnType::Field(InfluxFieldType::String),
+            )
+        )
+}
+
 /// Definition of a table in the catalog
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct TableDefinition {
@@ -2095,6 +4466,12 @@ pub struct TableDefinition {
     pub deleted: bool,
     /// The time when the table is scheduled to be hard deleted.
     pub hard_delete_time: Option<Time>,
+    /// The name the table had before it was deleted, `None` if it is not deleted
+    pub original_name: Option<Arc<str>>,
+    /// Optional series key tag used as a hint for partitioning the table's data in storage
+    pub primary_partition_tag: Option<ColumnId>,
+    /// Retention period for the table, which applies in addition to that of its database
+    pub retention_period: RetentionPeriod,
 }
 
 impl TableDefinition {
@@ -2167,6 +4544,9 @@ impl TableDefinition {
             distinct_caches: Repository::new(),
             deleted: false,
             hard_delete_time: None,
+            original_name: None,
+            primary_partition_tag: None,
+            retention_period: RetentionPeriod::Indefinite,
         })
     }
 
@@ -2202,12 +4582,22 @@ impl TableDefinition {
         &self,
         table_definition: &CreateTableLog,
     ) -> Result<Cow<'_, Self>> {
-        Self::add_fields(Cow::Borrowed(self), &table_definition.field_definitions)
+        Self::add_fields(
+            Cow::Borrowed(self),
+            &table_definition.field_definitions,
+            false,
+        )
     }
 
+    /// Add the given fields to the table
+    ///
+    /// Fields that already exist in the table must have the same type, unless `type_promotion` is
+    /// set and the existing type can be promoted to the new type (see `is_type_promotion`), in
+    /// which case the existing column is changed to the new type.
     pub(crate) fn add_fields<'a>(
         mut table: Cow<'a, Self>,
         fields: &Vec<FieldDefinition>,
+        type_promotion: bool,
     ) -> Result<Cow<'a, Self>> {
         let mut new_fields: Vec<(ColumnId, Arc<str>, InfluxColumnType)> =
             Vec::with_capacity(fields.len());
@@ -2217,7 +4607,11 @@ impl TableDefinition {
                 .get_by_id(&field_def.id)
                 .map(|def| def.data_type)
             {
-                if existing_type != field_def.data_type.into() {
+                if type_promotion && is_type_promotion(existing_type, field_def.data_type.into()) {
+                    table
+                        .to_mut()
+                        .promote_column(field_def.id, field_def.data_type.into())?;
+                } else if existing_type != field_def.data_type.into() {
                     return Err(CatalogError::FieldTypeMismatch {
                         table_name: table.table_name.to_string(),
                         column_name: field_def.name.to_string(),
@@ -2241,6 +4635,197 @@ impl TableDefinition {
         Ok(table)
     }
 
+    /// Change the type of an existing column and rebuild the table's schema
+    ///
+    /// This is used both for type promotion on write, and by [`Catalog::alter_column_type`].
+    fn promote_column(&mut self, id: ColumnId, column_type: InfluxColumnType) -> Result<()> {
+        let Some(existing) = self.columns.get_by_id(&id) else {
+            return Err(CatalogError::NotFound);
+        };
+        let mut promoted = existing.as_ref().clone();
+        promoted.data_type = column_type;
+        self.columns.update(id, promoted)?;
+
+        let mut schema_builder = SchemaBuilder::with_capacity(self.columns.len());
+        schema_builder.measurement(self.table_name.as_ref());
+        for col_def in self.columns.resource_iter() {
+            schema_builder.influx_column(col_def.name.as_ref(), col_def.data_type);
+        }
+        schema_builder.with_series_key(&self.series_key_names);
+        self.schema = schema_builder.build().expect("schema should be valid");
+        Ok(())
+    }
+
+    /// Give the table a new name, rebuilding its schema so that the new name is used as the
+    /// measurement
+    ///
+    /// Columns, the series key, the sort key, and caches are all kept as they are.
+    fn rename(&mut self, new_name: Arc<str>) {
+        let mut schema_builder = SchemaBuilder::with_capacity(self.columns.len());
+        schema_builder.measurement(new_name.as_ref());
+        for col_def in self.columns.resource_iter() {
+            schema_builder.influx_column(col_def.name.as_ref(), col_def.data_type);
+        }
+        schema_builder.with_series_key(&self.series_key_names);
+        self.schema = schema_builder.build().expect("schema should be valid");
+
+        // caches record the name of their table, so keep those in step:
+        for mut cache in self
+            .last_caches
+            .resource_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+        {
+            Arc::make_mut(&mut cache).table = Arc::clone(&new_name);
+            self.last_caches
+                .update(cache.id, cache)
+                .expect("last cache should be updated");
+        }
+        for mut cache in self
+            .distinct_caches
+            .resource_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+        {
+            Arc::make_mut(&mut cache).table_name = Arc::clone(&new_name);
+            self.distinct_caches
+                .update(cache.cache_id, cache)
+                .expect("distinct cache should be updated");
+        }
+
+        self.table_name = new_name;
+    }
+
+    /// Check that the column can be dropped from the table
+    ///
+    /// The column must exist, must not be the `time` column, and must not be used by any of the
+    /// table's last or distinct caches.
+    pub(crate) fn check_drop_column(&self, column_id: ColumnId) -> Result<()> {
+        let Some(column) = self.columns.get_by_id(&column_id) else {
+            return Err(CatalogError::NotFound);
+        };
+        if column.data_type == InfluxColumnType::Timestamp {
+            return Err(CatalogError::CannotDropTimeColumn);
+        }
+        let last_cache = self.last_caches.resource_iter().find(|cache| {
+            cache.key_columns.contains(&column_id)
+                || matches!(
+                    &cache.value_columns,
+                    LastCacheValueColumnsDef::Explicit { columns } if columns.contains(&column_id)
+                )
+        });
+        let distinct_cache = self
+            .distinct_caches
+            .resource_iter()
+            .find(|cache| cache.column_ids.contains(&column_id));
+        if let Some(cache_name) = last_cache
+            .map(|cache| &cache.name)
+            .or(distinct_cache.map(|cache| &cache.cache_name))
+        {
+            return Err(CatalogError::invalid_configuration(format!(
+                "cannot drop column '{column_name}' because it is used by the cache '{cache_name}'",
+                column_name = column.name,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Remove a column from the table, rebuilding the schema without it
+    ///
+    /// If the column is part of the series key it is removed from the series key, and the sort
+    /// key is recomputed. See [`TableDefinition::check_drop_column`] for when a column can not
+    /// be dropped.
+    fn drop_column(&mut self, column_id: ColumnId) -> Result<()> {
+        self.check_drop_column(column_id)?;
+        self.columns.remove(&column_id);
+        if self.series_key.contains(&column_id) {
+            self.series_key.retain(|id| *id != column_id);
+            self.series_key_names = self
+                .series_key
+                .iter()
+                .map(|id| {
+                    self.columns
+                        .id_to_name(id)
+                        .expect("invalid column id in series key definition")
+                })
+                .collect();
+            self.sort_key = Self::make_sort_key(
+                &self.series_key_names,
+                self.columns.contains_name(TIME_COLUMN_NAME),
+            );
+        }
+        if self.primary_partition_tag == Some(column_id) {
+            self.primary_partition_tag = None;
+        }
+
+        let mut schema_builder = SchemaBuilder::with_capacity(self.columns.len());
+        schema_builder.measurement(self.table_name.as_ref());
+        for col_def in self.columns.resource_iter() {
+            schema_builder.influx_column(col_def.name.as_ref(), col_def.data_type);
+        }
+        schema_builder.with_series_key(&self.series_key_names);
+        self.schema = schema_builder.build().expect("schema should be valid");
+        Ok(())
+    }
+
+    /// Check that `new_order` is a reordering of the table's series key
+    ///
+    /// Every column in the series key must appear exactly once, and no other columns may be
+    /// given.
+    pub(crate) fn check_series_key_order(&self, new_order: &[ColumnId]) -> Result<()> {
+        let current = self.series_key.iter().collect::<BTreeSet<_>>();
+        let new = new_order.iter().collect::<BTreeSet<_>>();
+        if new.len() != new_order.len() {
+            return Err(CatalogError::invalid_configuration(
+                "the new series key order contains duplicate columns",
+            ));
+        }
+        if let Some(id) = new.difference(&current).next() {
+            return Err(CatalogError::invalid_configuration(format!(
+                "column with id {id} is not in the series key of table '{}'",
+                self.table_name
+            )));
+        }
+        if let Some(id) = current.difference(&new).next() {
+            return Err(CatalogError::invalid_configuration(format!(
+                "the new series key order of table '{}' is missing the column with id {id}",
+                self.table_name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Change the order of the columns in the series key, recomputing the sort key and rebuilding
+    /// the schema to match
+    ///
+    /// See [`TableDefinition::check_series_key_order`] for the orders that are accepted.
+    fn reorder_series_key(&mut self, new_order: Vec<ColumnId>) -> Result<()> {
+        self.check_series_key_order(&new_order)?;
+        self.series_key = new_order;
+        self.series_key_names = self
+            .series_key
+            .iter()
+            .map(|id| {
+                self.columns
+                    .id_to_name(id)
+                    .expect("invalid column id in series key definition")
+            })
+            .collect();
+        self.sort_key = Self::make_sort_key(
+            &self.series_key_names,
+            self.columns.contains_name(TIME_COLUMN_NAME),
+        );
+
+        let mut schema_builder = SchemaBuilder::with_capacity(self.columns.len());
+        schema_builder.measurement(self.table_name.as_ref());
+        for col_def in self.columns.resource_iter() {
+            schema_builder.influx_column(col_def.name.as_ref(), col_def.data_type);
+        }
+        schema_builder.with_series_key(&self.series_key_names);
+        self.schema = schema_builder.build().expect("schema should be valid");
+        Ok(())
+    }
+
     /// Check if the column exists in the [`TableDefinition`]
     pub fn column_exists(&self, column: impl AsRef<str>) -> bool {
         self.columns.contains_name(column.as_ref())
@@ -2326,6 +4911,34 @@ impl TableDefinition {
         Ok(())
     }
 
+    /// A stable 64-bit fingerprint of the table's logical schema
+    ///
+    /// This covers the table name, the name and type of every column, and the series key, but not
+    /// any ids, so two tables with the same logical schema have the same fingerprint regardless of
+    /// the order in which their columns were created, or which catalog they are in.
+    pub fn schema_fingerprint(&self) -> u64 {
+        let columns = self
+            .columns
+            .resource_iter()
+            .map(|c| (Arc::clone(&c.name), c.data_type.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        let mut hasher = Sha512::new();
+        hasher.update(self.table_name.as_bytes());
+        hasher.update([0]);
+        for (name, data_type) in columns {
+            hasher.update(name.as_bytes());
+            hasher.update([0]);
+            hasher.update(data_type.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update([1]);
+        for name in &self.series_key_names {
+            hasher.update(name.as_bytes());
+            hasher.update([0]);
+        }
+        digest_to_u64(&hasher.finalize())
+    }
+
     pub fn index_column_ids(&self) -> Vec<ColumnId> {
         self.columns
             .iter()
@@ -2336,10 +4949,28 @@ impl TableDefinition {
             .collect()
     }
 
+    /// The ids of field columns that have been marked as indexed
+    ///
+    /// Unlike [`TableDefinition::index_column_ids`], this does not include tags, which are always
+    /// indexed as part of the series key.
+    pub fn indexed_column_ids(&self) -> Vec<ColumnId> {
+        self.columns
+            .iter()
+            .filter(|(_, def)| def.indexed)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     pub fn influx_schema(&self) -> &Schema {
         &self.schema
     }
 
+    /// The id that the next column added to the table will be assigned, see
+    /// [`Catalog::peek_next_db_id`]
+    pub fn peek_next_column_id(&self) -> ColumnId {
+        self.columns.next_id()
+    }
+
     pub fn num_columns(&self) -> usize {
         self.influx_schema().len()
     }
@@ -2351,6 +4982,20 @@ impl TableDefinition {
             .count()
     }
 
+    /// Count the columns in the table by type, in a single pass over the columns
+    ///
+    /// Returns `(tag_count, field_count, has_time)`, where `has_time` is `1` if the table has a
+    /// time column, and `0` otherwise.
+    pub fn column_type_counts(&self) -> (usize, usize, usize) {
+        self.columns
+            .resource_iter()
+            .fold((0, 0, 0), |(tags, fields, time), col| match col.data_type {
+                InfluxColumnType::Tag => (tags + 1, fields, time),
+                InfluxColumnType::Field(_) => (tags, fields + 1, time),
+                InfluxColumnType::Timestamp => (tags, fields, 1),
+            })
+    }
+
     pub fn field_type_by_name(&self, name: impl AsRef<str>) -> Option<InfluxColumnType> {
         self.columns
             .get_by_name(name.as_ref())
@@ -2398,47 +5043,245 @@ impl TableDefinition {
     pub fn series_key_names(&self) -> &[Arc<str>] {
         &self.series_key_names
     }
-}
 
-trait TableUpdate {
-    fn table_id(&self) -> TableId;
-    fn table_name(&self) -> Arc<str>;
-    fn update_table<'a>(&self, table: Cow<'a, TableDefinition>)
-    -> Result<Cow<'a, TableDefinition>>;
-}
+    /// The ids of the series key tag columns, in series key order, which form the dimension the
+    /// table's data is partitioned on
+    ///
+    /// Unlike the primary key, this never includes the `time` column.
+    pub fn partition_template_columns(&self) -> Vec<ColumnId> {
+        self.series_key
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.columns
+                    .get_by_id(id)
+                    .is_some_and(|c| !matches!(c.data_type, InfluxColumnType::Timestamp))
+            })
+            .collect()
+    }
 
-impl<T: TableUpdate> UpdateDatabaseSchema for T {
-    fn update_schema<'a>(
-        &self,
-        mut schema: Cow<'a, DatabaseSchema>,
-    ) -> Result<Cow<'a, DatabaseSchema>> {
-        let Some(table) = schema.tables.get_by_id(&self.table_id()) else {
-            return Err(CatalogError::TableNotFound {
-                db_name: Arc::clone(&schema.name),
-                table_name: Arc::clone(&self.table_name()),
-            });
-        };
-        if let Cow::Owned(new_table) = self.update_table(Cow::Borrowed(table.as_ref()))? {
-            schema
-                .to_mut()
-                .update_table(new_table.table_id, Arc::new(new_table))?;
+    /// Check that every column id in the series key resolves to a column in the table
+    ///
+    /// Returns the ids that do not resolve, if any.
+    pub fn validate_series_key(&self) -> Result<(), Vec<ColumnId>> {
+        let missing = self
+            .series_key
+            .iter()
+            .filter(|id| !self.columns.contains_id(id))
+            .copied()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
         }
-        Ok(schema)
     }
-}
 
-impl TableUpdate for AddFieldsLog {
-    fn table_id(&self) -> TableId {
-        self.table_id
-    }
-    fn table_name(&self) -> Arc<str> {
-        Arc::clone(&self.table_name)
+    /// Check that the sort key is the series key followed by `time`, as it is when the table is
+    /// created, or when its columns change
+    ///
+    /// A mismatch indicates that the table definition was corrupted, e.g., by a faulty replay.
+    pub fn sort_key_consistent(&self) -> bool {
+        self.sort_key
+            == Self::make_sort_key(
+                &self.series_key_names,
+                self.columns.contains_name(TIME_COLUMN_NAME),
+            )
     }
-    fn update_table<'a>(
-        &self,
+
+    /// Check if this table has the same structure as `other`, without regard for the name or id
+    /// of either table
+    ///
+    /// Columns are compared by name, type, and nullability, irrespective of their ids or the order
+    /// in which they were added. The series key and sort key must also match. Caches and deletion
+    /// state are not considered.
+    pub fn schema_equal_ignoring_name(&self, other: &TableDefinition) -> bool {
+        let columns = |table: &TableDefinition| {
+            table
+                .columns
+                .resource_iter()
+                .map(|col| (Arc::clone(&col.name), (col.data_type, col.nullable)))
+                .collect::<BTreeMap<_, _>>()
+        };
+        self.series_key_names == other.series_key_names
+            && self.sort_key == other.sort_key
+            && columns(self) == columns(other)
+    }
+
+    /// Serialize the table's Arrow schema as an encapsulated Arrow IPC schema message
+    ///
+    /// The bytes can be read back into an Arrow schema with
+    /// `arrow::ipc::convert::try_schema_from_ipc_buffer`.
+    pub fn schema_ipc_bytes(&self) -> Result<Vec<u8>> {
+        let options = IpcWriteOptions::default();
+        let encoded =
+            IpcDataGenerator::default().schema_to_bytes(self.schema.as_arrow().as_ref(), &options);
+        let mut bytes = vec![];
+        write_message(&mut bytes, encoded, &options)
+            .context("failed to encode table schema as an arrow IPC message")?;
+        Ok(bytes)
+    }
+
+    /// Compare the columns of this table with those of `other`, by name
+    ///
+    /// See [`ColumnDiff`] for how the columns are bucketed. Nullability and column ids are not
+    /// considered.
+    pub fn column_diff(&self, other: &TableDefinition) -> ColumnDiff {
+        let mut diff = ColumnDiff::default();
+        for col in self.columns.resource_iter() {
+            match other.columns.get_by_name(&col.name) {
+                None => diff.only_in_self.push(Arc::clone(&col.name)),
+                Some(other_col) if other_col.data_type != col.data_type => diff
+                    .type_mismatch
+                    .push((Arc::clone(&col.name), col.data_type, other_col.data_type)),
+                Some(_) => (),
+            }
+        }
+        diff.only_in_other = other
+            .columns
+            .resource_iter()
+            .filter(|col| !self.columns.contains_name(&col.name))
+            .map(|col| Arc::clone(&col.name))
+            .collect();
+        diff.only_in_self.sort();
+        diff.only_in_other.sort();
+        diff.type_mismatch.sort_by(|a, b| a.0.cmp(&b.0));
+        diff
+    }
+}
+
+/// The difference between the columns of two tables, as produced by
+/// [`TableDefinition::column_diff`]
+///
+/// Each list is sorted by column name.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ColumnDiff {
+    /// Columns that exist only in the table `column_diff` was called on
+    pub only_in_self: Vec<Arc<str>>,
+    /// Columns that exist only in the other table
+    pub only_in_other: Vec<Arc<str>>,
+    /// Columns that exist in both tables but with different types, along with the type in this
+    /// table followed by the type in the other table
+    pub type_mismatch: Vec<(Arc<str>, InfluxColumnType, InfluxColumnType)>,
+}
+
+impl ColumnDiff {
+    /// Whether both tables have the same set of column names and types
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty()
+            && self.only_in_other.is_empty()
+            && self.type_mismatch.is_empty()
+    }
+}
+
+/// A column along with the database and table that it belongs to, as produced by
+/// [`Catalog::all_columns`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ColumnPath {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    pub column_name: Arc<str>,
+    pub data_type: InfluxColumnType,
+    pub nullable: bool,
+}
+
+/// The tables of a database, grouped by the kinds of cache they have, as produced by
+/// [`Catalog::cache_coverage`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CacheCoverage {
+    /// Tables with both a last cache and a distinct cache
+    pub both: Vec<Arc<str>>,
+    /// Tables with a last cache but no distinct cache
+    pub last_only: Vec<Arc<str>>,
+    /// Tables with a distinct cache but no last cache
+    pub distinct_only: Vec<Arc<str>>,
+    /// Tables with no caches
+    pub neither: Vec<Arc<str>>,
+}
+
+/// A table that exceeds one of the catalog's currently configured limits, as reported by
+/// [`Catalog::tables_over_current_limits`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LimitViolation {
+    pub db_id: DbId,
+    pub db_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub kind: LimitKind,
+    /// The number of columns, or tag columns, that the table has
+    pub count: usize,
+    pub limit: usize,
+}
+
+/// The limit exceeded by a [`LimitViolation`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LimitKind {
+    /// The number of columns in a table, including `time`
+    ColumnsPerTable,
+    /// The number of tag columns in a table
+    TagColumnsPerTable,
+}
+
+/// Counts of the resources in a database, as produced by [`Catalog::get_database_stats`]
+///
+/// Deleted tables, and their columns and caches, are not counted.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DatabaseStats {
+    pub table_count: usize,
+    /// The total number of columns across all tables, including their `time` columns
+    pub column_count: usize,
+    pub last_cache_count: usize,
+    pub distinct_cache_count: usize,
+    pub trigger_count: usize,
+    pub retention_period: RetentionPeriod,
+    /// Whether the database has been soft deleted
+    pub deleted: bool,
+}
+
+trait TableUpdate {
+    fn table_id(&self) -> TableId;
+    fn table_name(&self) -> Arc<str>;
+    fn update_table<'a>(&self, table: Cow<'a, TableDefinition>)
+    -> Result<Cow<'a, TableDefinition>>;
+}
+
+impl<T: TableUpdate> UpdateDatabaseSchema for T {
+    fn update_schema<'a>(
+        &self,
+        mut schema: Cow<'a, DatabaseSchema>,
+    ) -> Result<Cow<'a, DatabaseSchema>> {
+        let Some(table) = schema.tables.get_by_id(&self.table_id()) else {
+            return Err(CatalogError::TableNotFound {
+                db_name: Arc::clone(&schema.name),
+                table_name: Arc::clone(&self.table_name()),
+            });
+        };
+        if let Cow::Owned(new_table) = self.update_table(Cow::Borrowed(table.as_ref()))? {
+            schema
+                .to_mut()
+                .update_table(new_table.table_id, Arc::new(new_table))?;
+        }
+        Ok(schema)
+    }
+}
+
+impl TableUpdate for AddFieldsLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
         table: Cow<'a, TableDefinition>,
     ) -> Result<Cow<'a, TableDefinition>> {
-        TableDefinition::add_fields(table, &self.field_definitions)
+        // type promotions are only logged by a transaction when type promotion is enabled, so
+        // they are always applied when replaying the log:
+        TableDefinition::add_fields(table, &self.field_definitions, true)
     }
 }
 
@@ -2477,6 +5320,191 @@ impl TableUpdate for DeleteDistinctCacheLog {
     }
 }
 
+impl TableUpdate for SetPrimaryPartitionTagLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        table.to_mut().primary_partition_tag = self.column_id;
+        Ok(table)
+    }
+}
+
+impl TableUpdate for SetRetentionPeriodForTableLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        let retention_period = RetentionPeriod::Duration(self.retention_period);
+        if table.retention_period != retention_period {
+            table.to_mut().retention_period = retention_period;
+        }
+        Ok(table)
+    }
+}
+
+impl TableUpdate for ClearRetentionPeriodForTableLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        if table.retention_period != RetentionPeriod::Indefinite {
+            table.to_mut().retention_period = RetentionPeriod::Indefinite;
+        }
+        Ok(table)
+    }
+}
+
+impl TableUpdate for DropColumnLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        table.to_mut().drop_column(self.column_id)?;
+        Ok(table)
+    }
+}
+
+impl TableUpdate for ReorderSeriesKeyLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        table.to_mut().reorder_series_key(self.series_key.clone())?;
+        Ok(table)
+    }
+}
+
+impl TableUpdate for AlterColumnTypeLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        table
+            .to_mut()
+            .promote_column(self.column_id, self.column_type.into())?;
+        Ok(table)
+    }
+}
+
+impl TableUpdate for SetColumnDescriptionLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        let Some(column) = table.columns.get_by_id(&self.column_id) else {
+            return Err(CatalogError::NotFound);
+        };
+        if column.description != self.description {
+            let mut column = column.as_ref().clone();
+            column.description = self.description.clone();
+            table.to_mut().columns.update(self.column_id, column)?;
+        }
+        Ok(table)
+    }
+}
+
+impl TableUpdate for SetColumnIndexedLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        let Some(column) = table.columns.get_by_id(&self.column_id) else {
+            return Err(CatalogError::NotFound);
+        };
+        if !matches!(column.data_type, InfluxColumnType::Field(_)) {
+            return Err(CatalogError::invalid_configuration(format!(
+                "only field columns can be marked as indexed, '{}' is a {}",
+                column.name, column.data_type
+            )));
+        }
+        if column.indexed != self.indexed {
+            let mut column = column.as_ref().clone();
+            column.indexed = self.indexed;
+            table.to_mut().columns.update(self.column_id, column)?;
+        }
+        Ok(table)
+    }
+}
+
+impl TableUpdate for SetColumnAllowedValuesLog {
+    fn table_id(&self) -> TableId {
+        self.table_id
+    }
+    fn table_name(&self) -> Arc<str> {
+        Arc::clone(&self.table_name)
+    }
+    fn update_table<'a>(
+        &self,
+        mut table: Cow<'a, TableDefinition>,
+    ) -> Result<Cow<'a, TableDefinition>> {
+        let Some(column) = table.columns.get_by_id(&self.column_id) else {
+            return Err(CatalogError::NotFound);
+        };
+        if column.data_type != InfluxColumnType::Tag {
+            return Err(CatalogError::InvalidColumnType {
+                column_name: Arc::clone(&column.name),
+                expected: InfluxColumnType::Tag,
+                got: column.data_type,
+            });
+        }
+        if column.allowed_values != self.allowed_values {
+            let mut column = column.as_ref().clone();
+            column.allowed_values = self.allowed_values.clone();
+            table.to_mut().columns.update(self.column_id, column)?;
+        }
+        Ok(table)
+    }
+}
+
 impl TableUpdate for LastCacheDefinition {
     fn table_id(&self) -> TableId {
         self.table_id
@@ -2523,6 +5551,16 @@ pub struct ColumnDefinition {
     pub data_type: InfluxColumnType,
     /// Whether this column can hold `NULL` values
     pub nullable: bool,
+    /// The set of values a tag column is allowed to hold, if it is restricted
+    ///
+    /// This is only ever set on tag columns.
+    pub allowed_values: Option<BTreeSet<Arc<str>>>,
+    /// Whether the storage layer should index this column, even though it is not a tag
+    ///
+    /// This is only ever set on field columns.
+    pub indexed: bool,
+    /// A free-form description of the column, for documentation only
+    pub description: Option<Arc<str>>,
 }
 
 impl ColumnDefinition {
@@ -2537,6 +5575,9 @@ impl ColumnDefinition {
             name: name.into(),
             data_type,
             nullable,
+            allowed_values: None,
+            indexed: false,
+            description: None,
         }
     }
 }
@@ -2605,26 +5646,99 @@ impl TokenRepository {
         Ok(())
     }
 
-    pub(crate) fn delete_token(&mut self, token_name: String) -> Result<()> {
-        let token_id = self
+    pub(crate) fn update_token_permissions(
+        &mut self,
+        token_id: TokenId,
+        permissions: Vec<Permission>,
+        updated_at: i64,
+    ) -> Result<()> {
+        let mut token_info = self
             .repo
-            .name_to_id(&token_name)
+            .get_by_id(&token_id)
             .ok_or_else(|| CatalogError::NotFound)?;
-        self.repo.remove(&token_id);
-        self.hash_lookup_map.remove_by_left(&token_id);
+        let updatable = Arc::make_mut(&mut token_info);
+        updatable.set_permissions(permissions);
+        updatable.updated_at = Some(updated_at);
+        self.repo.update(token_id, token_info)?;
         Ok(())
     }
-}
-
-impl CatalogResource for TokenInfo {
-    type Identifier = TokenId;
 
-    fn id(&self) -> Self::Identifier {
-        self.id
+    pub(crate) fn set_token_disabled(
+        &mut self,
+        token_id: TokenId,
+        disabled: bool,
+        updated_at: i64,
+    ) -> Result<()> {
+        let mut token_info = self
+            .repo
+            .get_by_id(&token_id)
+            .ok_or_else(|| CatalogError::NotFound)?;
+        let updatable = Arc::make_mut(&mut token_info);
+        updatable.disabled = disabled;
+        updatable.updated_at = Some(updated_at);
+        self.repo.update(token_id, token_info)?;
+        Ok(())
     }
 
-    fn name(&self) -> Arc<str> {
-        Arc::clone(&self.name)
+    pub(crate) fn update_token_expiry(
+        &mut self,
+        token_id: TokenId,
+        expiry: Option<i64>,
+        updated_at: i64,
+    ) -> Result<()> {
+        let mut token_info = self
+            .repo
+            .get_by_id(&token_id)
+            .ok_or_else(|| CatalogError::NotFound)?;
+        let updatable = Arc::make_mut(&mut token_info);
+        updatable.expiry_millis = expiry.unwrap_or(i64::MAX);
+        updatable.updated_at = Some(updated_at);
+        self.repo.update(token_id, token_info)?;
+        Ok(())
+    }
+
+    pub(crate) fn rename_token(
+        &mut self,
+        token_id: TokenId,
+        new_name: Arc<str>,
+        updated_at: i64,
+    ) -> Result<()> {
+        // the name map is bi-directional, so updating the token to a name held by another token
+        // would silently drop that token's entry:
+        if self.repo.contains_name(&new_name) {
+            return Err(CatalogError::TokenNameAlreadyExists(new_name.to_string()));
+        }
+        let mut token_info = self
+            .repo
+            .get_by_id(&token_id)
+            .ok_or_else(|| CatalogError::NotFound)?;
+        let updatable = Arc::make_mut(&mut token_info);
+        updatable.name = new_name;
+        updatable.updated_at = Some(updated_at);
+        self.repo.update(token_id, token_info)?;
+        Ok(())
+    }
+
+    pub(crate) fn delete_token(&mut self, token_name: String) -> Result<()> {
+        let token_id = self
+            .repo
+            .name_to_id(&token_name)
+            .ok_or_else(|| CatalogError::NotFound)?;
+        self.repo.remove(&token_id);
+        self.hash_lookup_map.remove_by_left(&token_id);
+        Ok(())
+    }
+}
+
+impl CatalogResource for TokenInfo {
+    type Identifier = TokenId;
+
+    fn id(&self) -> Self::Identifier {
+        self.id
+    }
+
+    fn name(&self) -> Arc<str> {
+        Arc::clone(&self.name)
     }
 }
 
@@ -2644,7 +5758,8 @@ mod tests {
 
     use crate::{
         log::{
-            FieldDataType, LastCacheSize, LastCacheTtl, MaxAge, MaxCardinality, create,
+            FieldDataType, LastCacheSize, LastCacheTtl, MaxAge, MaxCardinality, TriggerSettings,
+            ValidPluginFilename, create,
             versions::v3::{DeleteBatch, DeleteOp},
         },
         object_store::CatalogFilePath,
@@ -2652,6 +5767,7 @@ mod tests {
     };
 
     use super::*;
+    use influxdb3_authz::DatabaseActions;
     use influxdb3_test_helpers::object_store::RequestCountedObjectStore;
     use iox_time::MockProvider;
     use object_store::{local::LocalFileSystem, memory::InMemory};
@@ -2724,6 +5840,11 @@ mod tests {
             processing_engine_triggers: Default::default(),
             deleted: false,
             hard_delete_time: None,
+            original_name: None,
+            description: None,
+            created_time_ns: None,
+            null_tag_placeholder: None,
+            last_modified_sequence: None,
         };
         database
             .tables
@@ -5800,4 +8921,5630 @@ mod tests {
         assert!(table_def.deleted);
         assert_eq!(table_def.hard_delete_time, Some(new_specific_time));
     }
+
+    #[test_log::test(tokio::test)]
+    async fn test_field_type_conflicts() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("value", FieldDataType::Float),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "mem",
+                &["host"],
+                &[
+                    ("value", FieldDataType::Integer),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let conflicts = catalog.field_type_conflicts("foo");
+        assert_eq!(
+            conflicts,
+            vec![FieldTypeConflict {
+                field_name: "value".into(),
+                table_types: vec![
+                    ("cpu".into(), InfluxFieldType::Float),
+                    ("mem".into(), InfluxFieldType::Integer),
+                ],
+            }]
+        );
+        assert!(catalog.field_type_conflicts("bar").is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_peek_next_sequence() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let current = catalog.sequence_number();
+        assert_eq!(catalog.peek_next_sequence(), current.next());
+        // peeking does not change the catalog:
+        assert_eq!(catalog.peek_next_sequence(), current.next());
+        assert_eq!(catalog.sequence_number(), current);
+
+        let batch = catalog.create_database("foo").await.unwrap();
+        assert_eq!(batch.sequence_number(), current.next());
+        assert_eq!(catalog.peek_next_sequence(), current.next().next());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_force_checkpoint_with_label() {
+        let obj_store = Arc::new(InMemory::new());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+
+        let init = async || {
+            Catalog::new(
+                "test",
+                Arc::clone(&obj_store) as _,
+                Arc::clone(&time_provider) as _,
+                Default::default(),
+            )
+            .await
+            .unwrap()
+        };
+
+        let catalog = init().await;
+        catalog.create_database("foo").await.unwrap();
+        assert!(catalog.checkpoint_label().is_none());
+        catalog
+            .force_checkpoint(Some("pre-upgrade".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(catalog.checkpoint_label().as_deref(), Some("pre-upgrade"));
+        // later checkpoints written at the checkpoint interval keep the label:
+        catalog.create_database("bar").await.unwrap();
+        assert_eq!(catalog.snapshot().label.as_deref(), Some("pre-upgrade"));
+        let prefix = catalog.object_store_prefix();
+        drop(catalog);
+
+        // the label is stored in the checkpoint file:
+        let bytes = obj_store
+            .get(CatalogFilePath::checkpoint(prefix.as_ref()).as_ref())
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(bytes).unwrap();
+        assert_eq!(snapshot.label.as_deref(), Some("pre-upgrade"));
+
+        // and survives a reload of the catalog:
+        let catalog = init().await;
+        assert_eq!(catalog.checkpoint_label().as_deref(), Some("pre-upgrade"));
+        assert!(catalog.db_schema("foo").is_some());
+
+        // a forced checkpoint without a label clears it:
+        catalog.force_checkpoint(None).await.unwrap();
+        assert!(catalog.checkpoint_label().is_none());
+        assert!(catalog.snapshot().label.is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_database_storage_info() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+
+        let prefix = catalog.object_store_prefix();
+        let info = catalog.database_storage_info();
+        // includes the internal database:
+        assert_eq!(info.len(), 3);
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        assert!(info.contains(&(
+            foo_id,
+            "foo".into(),
+            format!("test/dbs/foo-{foo_id}").into()
+        )));
+
+        let mut prefixes = info
+            .iter()
+            .map(|(_, _, db_prefix)| Arc::clone(db_prefix))
+            .collect::<Vec<_>>();
+        for db_prefix in &prefixes {
+            assert!(db_prefix.starts_with(&format!("{prefix}/")));
+        }
+        prefixes.sort();
+        prefixes.dedup();
+        assert_eq!(prefixes.len(), 3);
+    }
+
+    #[test]
+    fn test_retention_window_ns() {
+        let mut db_schema = DatabaseSchema::new(DbId::from(0), "test".into());
+        assert_eq!(db_schema.retention_window_ns(), None);
+        let time_provider: Arc<dyn TimeProvider> = Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(1_000_000_000_000),
+        ));
+        assert_eq!(
+            db_schema.get_retention_period_cutoff_ts_nanos(Arc::clone(&time_provider)),
+            None
+        );
+
+        db_schema.retention_period = RetentionPeriod::Duration(Duration::from_secs(60));
+        assert_eq!(db_schema.retention_window_ns(), Some(60_000_000_000));
+        assert_eq!(
+            db_schema.get_retention_period_cutoff_ts_nanos(time_provider),
+            Some(1_000_000_000_000 - 60_000_000_000)
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_all_caches() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        for db_name in ["foo", "bar"] {
+            catalog.create_database(db_name).await.unwrap();
+            catalog
+                .create_table(
+                    db_name,
+                    "cpu",
+                    &["host", "region"],
+                    &[("usage", FieldDataType::Float)],
+                )
+                .await
+                .unwrap();
+        }
+        catalog
+            .create_last_cache(
+                "foo",
+                "cpu",
+                Some("foo_last"),
+                Some(&["host"]),
+                Some(&["usage"]),
+                LastCacheSize::new(1).unwrap(),
+                LastCacheTtl::from_secs(600),
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_distinct_cache(
+                "bar",
+                "cpu",
+                Some("bar_distinct"),
+                &["region"],
+                MaxCardinality::from_usize_unchecked(100),
+                MaxAge::from_secs(10),
+            )
+            .await
+            .unwrap();
+
+        let foo = catalog.db_schema("foo").unwrap();
+        let bar = catalog.db_schema("bar").unwrap();
+        let caches = catalog.all_caches();
+        assert_eq!(caches.len(), 2);
+        assert!(caches.contains(&CacheRef {
+            db_id: foo.id,
+            table_id: foo.table_name_to_id("cpu").unwrap(),
+            kind: CacheKind::Last(foo.list_last_caches()[0].id),
+            name: "foo_last".into(),
+        }));
+        assert!(caches.contains(&CacheRef {
+            db_id: bar.id,
+            table_id: bar.table_name_to_id("cpu").unwrap(),
+            kind: CacheKind::Distinct(bar.list_distinct_caches()[0].cache_id),
+            name: "bar_distinct".into(),
+        }));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_copy_table_schema() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("src").await.unwrap();
+        catalog.create_database("dst").await.unwrap();
+        catalog
+            .create_table(
+                "src",
+                "cpu",
+                &["region", "host"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("state", FieldDataType::String),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_last_cache(
+                "src",
+                "cpu",
+                Some("cpu_last"),
+                Some(&["host"]),
+                Some(&["usage"]),
+                LastCacheSize::new(1).unwrap(),
+                LastCacheTtl::from_secs(600),
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_distinct_cache(
+                "src",
+                "cpu",
+                Some("cpu_distinct"),
+                &["region", "host"],
+                MaxCardinality::from_usize_unchecked(100),
+                MaxAge::from_secs(10),
+            )
+            .await
+            .unwrap();
+        // create another table in the destination so that ids differ from the source:
+        catalog
+            .create_table("dst", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+
+        let table_id = catalog
+            .copy_table_schema("src", "cpu", "dst", "cpu_copy")
+            .await
+            .unwrap();
+
+        let src = catalog
+            .db_schema("src")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        let dst = catalog
+            .db_schema("dst")
+            .unwrap()
+            .table_definition_by_id(&table_id)
+            .unwrap();
+        assert_eq!(dst.table_name.as_ref(), "cpu_copy");
+        let fingerprint = |table: &TableDefinition| {
+            table
+                .columns
+                .resource_iter()
+                .map(|c| (Arc::clone(&c.name), c.data_type))
+                .collect::<BTreeMap<_, _>>()
+        };
+        assert_eq!(fingerprint(&src), fingerprint(&dst));
+        assert_eq!(src.series_key_names(), dst.series_key_names());
+        assert_eq!(src.sort_key, dst.sort_key);
+
+        let dst_last_cache = dst.last_caches.get_by_name("cpu_last").unwrap();
+        assert_eq!(dst_last_cache.table_id, table_id);
+        assert_eq!(
+            dst_last_cache.key_columns,
+            vec![dst.column_name_to_id("host").unwrap()]
+        );
+        let dst_distinct_cache = dst.distinct_caches.get_by_name("cpu_distinct").unwrap();
+        assert_eq!(
+            dst_distinct_cache.column_ids,
+            vec![
+                dst.column_name_to_id("region").unwrap(),
+                dst.column_name_to_id("host").unwrap()
+            ]
+        );
+
+        // the destination table must not already exist:
+        let err = catalog
+            .copy_table_schema("src", "cpu", "dst", "cpu_copy")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::AlreadyExists), "{err:?}");
+        // the destination database must exist:
+        let err = catalog
+            .copy_table_schema("src", "cpu", "missing", "cpu")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::NotFound), "{err:?}");
+        assert!(catalog.db_schema("missing").is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_num_tokens_limit() {
+        let mut catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.limits.num_tokens = 2;
+
+        for i in 0..2 {
+            catalog
+                .create_scoped_token(format!("token_{i}"), vec![], None)
+                .await
+                .unwrap();
+        }
+        let err = catalog
+            .create_scoped_token("token_2".to_string(), vec![], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::TooManyTokens(2)), "{err:?}");
+        let err = catalog
+            .create_named_admin_token_with_permission("admin_2".to_string(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::TooManyTokens(2)), "{err:?}");
+
+        // the operator token can always be created:
+        catalog.create_admin_token(false).await.unwrap();
+        assert_eq!(catalog.get_tokens().len(), 3);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tables_by_series_key_shape() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for (table_name, tags) in [
+            ("cpu", &["host", "region"][..]),
+            ("mem", &["host", "region"][..]),
+            ("disk", &["host"][..]),
+        ] {
+            catalog
+                .create_table("foo", table_name, tags, &[("value", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+
+        let db_schema = catalog.db_schema("foo").unwrap();
+        let id = |name: &str| db_schema.table_name_to_id(name).unwrap();
+        let shapes = catalog.tables_by_series_key_shape("foo");
+        let expected: BTreeMap<Vec<Arc<str>>, Vec<TableId>> = BTreeMap::from([
+            (vec!["host".into()], vec![id("disk")]),
+            (
+                vec!["host".into(), "region".into()],
+                vec![id("cpu"), id("mem")],
+            ),
+        ]);
+        assert_eq!(shapes, expected);
+        assert!(catalog.tables_by_series_key_shape("bar").is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_to_dot() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_distinct_cache(
+                "foo",
+                "cpu",
+                Some("cpu_hosts"),
+                &["host"],
+                MaxCardinality::from_usize_unchecked(100),
+                MaxAge::from_secs(10),
+            )
+            .await
+            .unwrap();
+
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        let table_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+        let dot = catalog.to_dot();
+        assert!(dot.starts_with("digraph catalog {"));
+        assert!(dot.ends_with('}'));
+        assert_contains!(
+            &dot,
+            format!("\"db_{db_id}\" [label=\"foo ({db_id})\", shape=cylinder];")
+        );
+        assert_contains!(
+            &dot,
+            format!("\"db_{db_id}_table_{table_id}\" [label=\"cpu ({table_id})\", shape=box];")
+        );
+        assert_contains!(
+            &dot,
+            format!("\"db_{db_id}\" -> \"db_{db_id}_table_{table_id}\";")
+        );
+        assert_contains!(&dot, "distinct cache: cpu_hosts (0)");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_validate_all_series_keys() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        assert!(catalog.validate_all_series_keys().is_empty());
+
+        // corrupt the series key of the cpu table so that it references a column that does not
+        // exist:
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        let table_id = {
+            let mut inner = catalog.inner.write();
+            let mut db_schema = inner.databases.get_by_id(&db_id).unwrap().as_ref().clone();
+            let mut table_def = db_schema.table_definition("cpu").unwrap();
+            Arc::make_mut(&mut table_def)
+                .series_key
+                .push(ColumnId::from(100));
+            let table_id = table_def.table_id;
+            db_schema.update_table(table_id, table_def).unwrap();
+            inner.databases.update(db_id, db_schema).unwrap();
+            table_id
+        };
+
+        assert_eq!(
+            catalog.validate_all_series_keys(),
+            vec![(db_id, table_id, vec![ColumnId::from(100)])]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_database_description_round_trip() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        assert!(catalog.db_schema("foo").unwrap().description.is_none());
+
+        catalog
+            .set_database_description("foo", Some("metrics for tenant foo"))
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().description.as_deref(),
+            Some("metrics for tenant foo")
+        );
+
+        // setting a description on a database that does not exist fails:
+        assert!(matches!(
+            catalog
+                .set_database_description("bar", Some("nope"))
+                .await
+                .unwrap_err(),
+            CatalogError::NotFound
+        ));
+
+        // the description survives serialization of the catalog snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let deserialized = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        new_catalog.update_from_snapshot(deserialized);
+        assert_eq!(
+            new_catalog.db_schema("foo").unwrap().description.as_deref(),
+            Some("metrics for tenant foo")
+        );
+
+        // and can be cleared:
+        catalog.set_database_description("foo", None).await.unwrap();
+        assert!(catalog.db_schema("foo").unwrap().description.is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_databases_created_after() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+
+        time_provider.set(Time::from_timestamp_nanos(10));
+        catalog.create_database("foo").await.unwrap();
+        time_provider.set(Time::from_timestamp_nanos(20));
+        catalog.create_database("bar").await.unwrap();
+        time_provider.set(Time::from_timestamp_nanos(30));
+        // databases created implicitly by a write also record their creation time:
+        let mut txn = catalog.begin("baz").unwrap();
+        txn.table_or_create("cpu").unwrap();
+        txn.column_or_create("cpu", "usage", FieldDataType::Float)
+            .unwrap();
+        catalog.commit(txn).await.unwrap();
+
+        assert_eq!(catalog.db_schema("foo").unwrap().created_time_ns, Some(10));
+        // subsequent updates to the database do not change its creation time:
+        time_provider.set(Time::from_timestamp_nanos(40));
+        catalog
+            .create_table("foo", "mem", &["host"], &[("used", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        assert_eq!(catalog.db_schema("foo").unwrap().created_time_ns, Some(10));
+
+        let names = |t: i64| {
+            catalog
+                .databases_created_after(Time::from_timestamp_nanos(t))
+                .into_iter()
+                .map(|db| db.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(0), ["foo", "bar", "baz"].map(Arc::<str>::from));
+        assert_eq!(names(10), ["bar", "baz"].map(Arc::<str>::from));
+        assert_eq!(names(25), ["baz"].map(Arc::<str>::from));
+        assert!(names(30).is_empty());
+
+        // deleted databases are not included:
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert_eq!(names(0), ["foo", "baz"].map(Arc::<str>::from));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_schema_equal_ignoring_name() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        let fields = [
+            ("usage", FieldDataType::Float),
+            ("up", FieldDataType::Boolean),
+        ];
+        catalog
+            .create_table("foo", "cpu", &["region", "host"], &fields)
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "cpu_copy", &["region", "host"], &fields)
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu_int",
+                &["region", "host"],
+                &[
+                    ("usage", FieldDataType::Integer),
+                    ("up", FieldDataType::Boolean),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "cpu_reordered", &["host", "region"], &fields)
+            .await
+            .unwrap();
+
+        let db = catalog.db_schema("foo").unwrap();
+        let cpu = db.table_definition("cpu").unwrap();
+        let cpu_copy = db.table_definition("cpu_copy").unwrap();
+        assert_ne!(cpu.table_id, cpu_copy.table_id);
+        assert!(cpu.schema_equal_ignoring_name(&cpu_copy));
+        assert!(cpu_copy.schema_equal_ignoring_name(&cpu));
+
+        // a differing field type is not equal:
+        let cpu_int = db.table_definition("cpu_int").unwrap();
+        assert!(!cpu.schema_equal_ignoring_name(&cpu_int));
+
+        // nor is a differing series key order:
+        let cpu_reordered = db.table_definition("cpu_reordered").unwrap();
+        assert!(!cpu.schema_equal_ignoring_name(&cpu_reordered));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_reserve_ids_in_transaction() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        let mut txn = catalog.begin("foo").unwrap();
+        let db_id = txn.db_schema().id;
+        let cpu_id = txn.db_schema().table_name_to_id("cpu").unwrap();
+        let table_id = txn.reserve_table_id();
+        let column_id = txn.reserve_column_id("cpu").unwrap();
+        assert!(txn.reserve_column_id("not_a_table").is_err());
+        // reserved ids are not handed out again by the transaction:
+        assert_ne!(txn.reserve_table_id(), table_id);
+        assert_ne!(txn.reserve_column_id("cpu").unwrap(), column_id);
+
+        txn.push_op(DatabaseCatalogOp::CreateTable(CreateTableLog {
+            database_id: db_id,
+            database_name: "foo".into(),
+            table_name: "mem".into(),
+            table_id,
+            field_definitions: vec![
+                FieldDefinition::new(ColumnId::from(0), "host", FieldDataType::Tag),
+                FieldDefinition::new(ColumnId::from(1), "used", FieldDataType::Integer),
+                FieldDefinition::new(ColumnId::from(2), "time", FieldDataType::Timestamp),
+            ],
+            key: vec![ColumnId::from(0)],
+        }));
+        txn.push_op(DatabaseCatalogOp::AddFields(AddFieldsLog {
+            database_name: "foo".into(),
+            database_id: db_id,
+            table_name: "cpu".into(),
+            table_id: cpu_id,
+            field_definitions: vec![FieldDefinition::new(
+                column_id,
+                "idle",
+                FieldDataType::Float,
+            )],
+        }));
+        catalog.commit(txn).await.unwrap();
+
+        let db = catalog.db_schema("foo").unwrap();
+        assert_eq!(db.table_name_to_id("mem"), Some(table_id));
+        assert_eq!(
+            db.table_definition("cpu")
+                .unwrap()
+                .column_name_to_id("idle"),
+            Some(column_id)
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_restrict_token() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        let db_permission = |actions: u16| Permission {
+            resource_type: ResourceType::Database,
+            resource_identifier: ResourceIdentifier::Database(vec![db_id]),
+            actions: Actions::Database(DatabaseActions(actions)),
+        };
+        let (read, write) = (1, 2);
+        catalog
+            .create_scoped_token("rw".to_string(), vec![db_permission(read | write)], None)
+            .await
+            .unwrap();
+        let read_request = AccessRequest::Database(db_id, DatabaseActions(read));
+        let write_request = AccessRequest::Database(db_id, DatabaseActions(write));
+        assert!(catalog.token_can("rw", read_request));
+        assert!(catalog.token_can("rw", write_request));
+
+        catalog
+            .restrict_token("rw", vec![db_permission(write)])
+            .await
+            .unwrap();
+        assert!(catalog.token_can("rw", read_request));
+        assert!(!catalog.token_can("rw", write_request));
+
+        // the restriction survives serialization of the catalog snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let deserialized = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        new_catalog.update_from_snapshot(deserialized);
+        assert!(new_catalog.token_can("rw", read_request));
+        assert!(!new_catalog.token_can("rw", write_request));
+
+        // removing the remaining action drops the permission entirely:
+        catalog
+            .restrict_token("rw", vec![db_permission(read)])
+            .await
+            .unwrap();
+        assert!(!catalog.token_can("rw", read_request));
+        let token = catalog
+            .get_tokens()
+            .into_iter()
+            .find(|t| t.name.as_ref() == "rw")
+            .unwrap();
+        assert!(token.permissions.is_empty());
+
+        // the operator token cannot be restricted:
+        catalog.create_admin_token(false).await.unwrap();
+        assert!(matches!(
+            catalog
+                .restrict_token("_admin", vec![db_permission(write)])
+                .await
+                .unwrap_err(),
+            CatalogError::CannotModifyOperatorToken
+        ));
+        assert!(matches!(
+            catalog
+                .restrict_token("not_a_token", vec![db_permission(write)])
+                .await
+                .unwrap_err(),
+            CatalogError::NotFound
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_distinct_cache_summaries() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        assert!(catalog.distinct_cache_summaries("foo").is_empty());
+        catalog
+            .create_distinct_cache(
+                "foo",
+                "cpu",
+                Some("region_host"),
+                &["region", "host"],
+                MaxCardinality::from_usize_unchecked(1_000),
+                MaxAge::from_secs(90_000),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            catalog.distinct_cache_summaries("foo"),
+            vec![DistinctCacheSummary {
+                table_name: "cpu".into(),
+                cache_name: "region_host".into(),
+                columns: vec!["region".into(), "host".into()],
+                max_cardinality: MaxCardinality::from_usize_unchecked(1_000),
+                max_age: "1day 1h".to_string(),
+            }]
+        );
+        assert!(catalog.distinct_cache_summaries("bar").is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_wide_tables() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "narrow", &["t1"], &[("f1", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let fields = (0..10)
+            .map(|i| (format!("f{i}"), FieldDataType::Integer))
+            .collect::<Vec<_>>();
+        catalog
+            .create_table("foo", "wide", &["t1", "t2"], &fields)
+            .await
+            .unwrap();
+
+        let wide_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("wide")
+            .unwrap();
+        // the wide table has 2 tags, 10 fields, and time:
+        assert_eq!(catalog.wide_tables("foo", 3), vec![(wide_id, 13)]);
+        assert_eq!(catalog.wide_tables("foo", 1).len(), 2);
+        assert!(catalog.wide_tables("foo", 13).is_empty());
+        assert!(catalog.wide_tables("bar", 0).is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_merge_from() {
+        // the catalog being merged in, with a database whose name overlaps with the local
+        // catalog, and one that does not:
+        let other = Catalog::new_in_memory("other").await.unwrap();
+        for db_name in ["foo", "baz"] {
+            other.create_database(db_name).await.unwrap();
+            other
+                .create_table(
+                    db_name,
+                    "cpu",
+                    &["host"],
+                    &[("usage", FieldDataType::Float)],
+                )
+                .await
+                .unwrap();
+        }
+        other
+            .create_last_cache(
+                "baz",
+                "cpu",
+                Some("cache"),
+                None as Option<&[&str]>,
+                None as Option<&[&str]>,
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let other_baz_id = other.db_name_to_id("baz").unwrap();
+        other
+            .create_scoped_token(
+                "baz_reader".to_string(),
+                vec![Permission {
+                    resource_type: ResourceType::Database,
+                    resource_identifier: ResourceIdentifier::Database(vec![other_baz_id]),
+                    actions: Actions::Database(DatabaseActions(1)),
+                }],
+                None,
+            )
+            .await
+            .unwrap();
+        let snapshot = other.snapshot();
+
+        let local = async || {
+            let catalog = Catalog::new_in_memory("local").await.unwrap();
+            catalog.create_database("bar").await.unwrap();
+            catalog.create_database("foo").await.unwrap();
+            catalog
+                .create_table("foo", "mem", &["host"], &[("used", FieldDataType::Integer)])
+                .await
+                .unwrap();
+            catalog
+        };
+
+        // skip existing: the overlapping database is left alone
+        let catalog = local().await;
+        let report = catalog
+            .merge_from(&snapshot, MergePolicy::SkipExisting, false)
+            .await
+            .unwrap();
+        assert_eq!(report.databases_skipped, vec![Arc::<str>::from("foo")]);
+        assert_eq!(report.databases_imported.len(), 1);
+        let foo = catalog.db_schema("foo").unwrap();
+        assert!(foo.table_definition("mem").is_some());
+        assert!(foo.table_definition("cpu").is_none());
+        let baz = catalog.db_schema("baz").unwrap();
+        assert_eq!(report.databases_imported[0].id, baz.id);
+        // the id was remapped to avoid colliding with existing databases:
+        assert_ne!(baz.id, other_baz_id);
+        assert!(baz.table_definition("cpu").is_some());
+        assert_eq!(baz.list_last_caches().len(), 1);
+        // the token was imported and its permission remapped to the new database id:
+        assert_eq!(report.tokens_imported.len(), 1);
+        assert!(catalog.token_can(
+            "baz_reader",
+            AccessRequest::Database(baz.id, DatabaseActions(1))
+        ));
+        assert!(!catalog.token_can(
+            "baz_reader",
+            AccessRequest::Database(other_baz_id, DatabaseActions(1))
+        ));
+
+        // rename incoming: the overlapping database is imported under a new name
+        let catalog = local().await;
+        let report = catalog
+            .merge_from(&snapshot, MergePolicy::RenameIncoming, false)
+            .await
+            .unwrap();
+        assert!(report.databases_skipped.is_empty());
+        assert_eq!(report.databases_imported.len(), 2);
+        let renamed = report
+            .databases_imported
+            .iter()
+            .find(|db| db.incoming_name.as_ref() == "foo")
+            .unwrap();
+        assert_eq!(renamed.name.as_ref(), "foo_1");
+        assert!(
+            catalog
+                .db_schema("foo_1")
+                .unwrap()
+                .table_definition("cpu")
+                .is_some()
+        );
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .is_none()
+        );
+
+        // error: nothing is imported
+        let catalog = local().await;
+        assert!(matches!(
+            catalog
+                .merge_from(&snapshot, MergePolicy::Error, false)
+                .await
+                .unwrap_err(),
+            CatalogError::AlreadyExists
+        ));
+        assert!(catalog.db_schema("baz").is_none());
+        assert!(
+            catalog
+                .get_tokens()
+                .iter()
+                .all(|token| token.name.as_ref() != "baz_reader")
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_merge_from_skips_unscoped_tokens() {
+        let other = Catalog::new_in_memory("other").await.unwrap();
+        other.create_database("baz").await.unwrap();
+        other
+            .create_scoped_token(
+                "all_dbs_reader".to_string(),
+                vec![Permission {
+                    resource_type: ResourceType::Database,
+                    resource_identifier: ResourceIdentifier::Wildcard,
+                    actions: Actions::Database(DatabaseActions(1)),
+                }],
+                None,
+            )
+            .await
+            .unwrap();
+        other
+            .create_named_admin_token_with_permission("edge_admin".to_string(), None)
+            .await
+            .unwrap();
+        let snapshot = other.snapshot();
+
+        let local = async || {
+            let catalog = Catalog::new_in_memory("local").await.unwrap();
+            catalog.create_database("foo").await.unwrap();
+            catalog
+        };
+
+        // the wildcard and admin tokens would have access to the existing database, so they are
+        // skipped:
+        let catalog = local().await;
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let report = catalog
+            .merge_from(&snapshot, MergePolicy::SkipExisting, false)
+            .await
+            .unwrap();
+        assert!(report.tokens_imported.is_empty());
+        let mut skipped = report.tokens_skipped.clone();
+        skipped.sort();
+        assert_eq!(
+            skipped,
+            vec![Arc::<str>::from("all_dbs_reader"), Arc::from("edge_admin")]
+        );
+        assert!(!catalog.token_can(
+            "all_dbs_reader",
+            AccessRequest::Database(foo_id, DatabaseActions(1))
+        ));
+        assert!(!catalog.token_can("edge_admin", AccessRequest::Admin));
+        // the databases are still imported:
+        assert!(catalog.db_schema("baz").is_some());
+
+        // unless the caller opts in:
+        let catalog = local().await;
+        let report = catalog
+            .merge_from(&snapshot, MergePolicy::SkipExisting, true)
+            .await
+            .unwrap();
+        assert_eq!(report.tokens_imported.len(), 2);
+        assert!(report.tokens_skipped.is_empty());
+        assert!(catalog.token_can(
+            "all_dbs_reader",
+            AccessRequest::Database(foo_id, DatabaseActions(1))
+        ));
+        assert!(catalog.token_can("edge_admin", AccessRequest::Admin));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_last_checkpoint_sequence() {
+        let obj_store = Arc::new(InMemory::new());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        // create a catalog that checkpoints every 10 sequences
+        let catalog = Catalog::new_with_checkpoint_interval(
+            "test",
+            Arc::clone(&obj_store) as _,
+            Arc::clone(&time_provider) as _,
+            Default::default(),
+            10,
+        )
+        .await
+        .unwrap();
+        // a checkpoint is written when the catalog is first created:
+        assert_eq!(
+            catalog.last_checkpoint_sequence().await.unwrap(),
+            Some(CatalogSequenceNumber::new(0))
+        );
+
+        catalog.create_database("test_db").await.unwrap();
+        for i in 0..10 {
+            catalog
+                .create_table(
+                    "test_db",
+                    format!("table_{i}").as_str(),
+                    &["t1"],
+                    &[("f1", FieldDataType::String)],
+                )
+                .await
+                .unwrap();
+        }
+        assert!(catalog.sequence_number() > CatalogSequenceNumber::new(10));
+
+        // checkpoints are persisted in the background, so wait for it:
+        let mut sequence = None;
+        for _ in 0..100 {
+            sequence = catalog.last_checkpoint_sequence().await.unwrap();
+            if sequence == Some(CatalogSequenceNumber::new(10)) {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(sequence, Some(CatalogSequenceNumber::new(10)));
+
+        // there is no checkpoint for a catalog that was never initialized:
+        let store = ObjectStoreCatalog::new("not_a_catalog", 10, Arc::clone(&obj_store) as _);
+        assert!(store.load_checkpoint_sequence().await.unwrap().is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_nodes_in_mode() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let process_uuid_getter: Arc<dyn ProcessUuidGetter> =
+            Arc::new(influxdb3_process::ProcessUuidWrapper::new());
+        for node_id in ["node-b", "node-a", "node-c"] {
+            catalog
+                .register_node(
+                    node_id,
+                    2,
+                    vec![NodeMode::Core],
+                    Arc::clone(&process_uuid_getter),
+                )
+                .await
+                .unwrap();
+        }
+        catalog
+            .update_node_state_stopped("node-c", Arc::clone(&process_uuid_getter))
+            .await
+            .unwrap();
+
+        let nodes = catalog
+            .nodes_in_mode(NodeMode::Core)
+            .into_iter()
+            .map(|n| Arc::clone(&n.node_id))
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, vec![Arc::from("node-a"), Arc::from("node-b")]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_primary_partition_tag_round_trip() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        let table_def = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        assert!(table_def.primary_partition_tag.is_none());
+        let host_id = table_def.column_name_to_id("host").unwrap();
+
+        catalog
+            .set_primary_partition_tag("foo", "cpu", Some("host"))
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .primary_partition_tag,
+            Some(host_id)
+        );
+
+        // the partition tag survives serialization of the catalog snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let deserialized = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        new_catalog.update_from_snapshot(deserialized);
+        assert_eq!(
+            new_catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .primary_partition_tag,
+            Some(host_id)
+        );
+
+        // and can be cleared:
+        catalog
+            .set_primary_partition_tag("foo", "cpu", None)
+            .await
+            .unwrap();
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .primary_partition_tag
+                .is_none()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_primary_partition_tag_validation() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        // fields are not tags:
+        assert!(matches!(
+            catalog
+                .set_primary_partition_tag("foo", "cpu", Some("usage"))
+                .await
+                .unwrap_err(),
+            CatalogError::InvalidColumnType { .. }
+        ));
+        // neither is the time column:
+        assert!(matches!(
+            catalog
+                .set_primary_partition_tag("foo", "cpu", Some("time"))
+                .await
+                .unwrap_err(),
+            CatalogError::InvalidColumnType { .. }
+        ));
+        // columns that do not exist are rejected:
+        assert!(matches!(
+            catalog
+                .set_primary_partition_tag("foo", "cpu", Some("region"))
+                .await
+                .unwrap_err(),
+            CatalogError::NotFound
+        ));
+        // as are tables that do not exist:
+        assert!(matches!(
+            catalog
+                .set_primary_partition_tag("foo", "mem", Some("host"))
+                .await
+                .unwrap_err(),
+            CatalogError::TableNotFound { .. }
+        ));
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .primary_partition_tag
+                .is_none()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_oldest_pending_hard_delete() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        assert!(catalog.oldest_pending_hard_delete().is_none());
+
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        let cpu_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        let later = Time::from_timestamp_nanos(5_000_000_000);
+        let earlier = Time::from_timestamp_nanos(2_000_000_000);
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Timestamp(later))
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.oldest_pending_hard_delete(),
+            Some((DeletionTarget::Database(bar_id), later))
+        );
+
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Timestamp(earlier))
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.oldest_pending_hard_delete(),
+            Some((
+                DeletionTarget::Table {
+                    db_id: foo_id,
+                    table_id: cpu_id
+                },
+                earlier
+            ))
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_type_promotion_integer_to_float() {
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_type_promotion(true),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("count", FieldDataType::Integer),
+                    ("total", FieldDataType::UInteger),
+                ],
+            )
+            .await
+            .unwrap();
+        let count_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap()
+            .column_name_to_id("count")
+            .unwrap();
+
+        let mut txn = catalog.begin("foo").unwrap();
+        assert_eq!(
+            txn.column_or_create("cpu", "count", FieldDataType::Float)
+                .unwrap(),
+            count_id
+        );
+        txn.column_or_create("cpu", "total", FieldDataType::Float)
+            .unwrap();
+        let Prompt::Success(_) = catalog.commit(txn).await.unwrap() else {
+            panic!("transaction should commit");
+        };
+
+        let table_def = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        for name in ["count", "total"] {
+            assert_eq!(
+                table_def.column_definition(name).unwrap().data_type,
+                InfluxColumnType::Field(InfluxFieldType::Float)
+            );
+            assert_eq!(
+                table_def
+                    .schema
+                    .as_arrow()
+                    .field_with_name(name)
+                    .unwrap()
+                    .data_type(),
+                &arrow::datatypes::DataType::Float64
+            );
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_type_promotion_rejects_other_conflicts() {
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_type_promotion(true),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("count", FieldDataType::Integer),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let mut txn = catalog.begin("foo").unwrap();
+        // a string can not be promoted from an integer:
+        assert!(matches!(
+            txn.column_or_create("cpu", "count", FieldDataType::String)
+                .unwrap_err(),
+            CatalogError::InvalidColumnType { .. }
+        ));
+        // nor can a float be demoted to an integer, though integer writes to a float column
+        // are accepted, without changing its type:
+        let usage_id = txn
+            .column_or_create("cpu", "usage", FieldDataType::Integer)
+            .unwrap();
+        let table_def = txn.db_schema().table_definition("cpu").unwrap();
+        assert_eq!(
+            table_def
+                .column_definition_by_id(&usage_id)
+                .unwrap()
+                .data_type,
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        );
+
+        // and without type promotion enabled, integer to float conflicts are rejected:
+        let catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[("count", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        let mut txn = catalog.begin("foo").unwrap();
+        assert!(matches!(
+            txn.column_or_create("cpu", "count", FieldDataType::Float)
+                .unwrap_err(),
+            CatalogError::InvalidColumnType { .. }
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_table_schema_ipc_round_trip() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("count", FieldDataType::UInteger),
+                    ("status", FieldDataType::String),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let bytes = catalog.table_schema_ipc("foo", "cpu").unwrap();
+        let decoded = arrow::ipc::convert::try_schema_from_ipc_buffer(&bytes).unwrap();
+        let expected = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap()
+            .schema
+            .as_arrow();
+        let fields = |schema: &arrow::datatypes::Schema| {
+            schema
+                .fields()
+                .iter()
+                .map(|f| (f.name().clone(), f.data_type().clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields(&decoded), fields(&expected));
+
+        assert!(matches!(
+            catalog.table_schema_ipc("foo", "mem").unwrap_err(),
+            CatalogError::NotFound
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_replace_table() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[("usage", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        let old_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        let sequence = catalog.sequence_number();
+        let new_id = catalog
+            .replace_table(
+                "foo",
+                "cpu",
+                TableSpec::new(
+                    "cpu",
+                    &["region", "host"],
+                    &[("usage", FieldDataType::Float)],
+                ),
+            )
+            .await
+            .unwrap();
+        assert_ne!(old_id, new_id);
+        // the replacement was written in a single batch:
+        assert_eq!(catalog.sequence_number(), sequence.next());
+
+        let db_schema = catalog.db_schema("foo").unwrap();
+        let old_table = db_schema.table_definition_by_id(&old_id).unwrap();
+        assert!(old_table.deleted);
+        assert!(old_table.hard_delete_time.is_some());
+        assert_ne!(old_table.table_name.as_ref(), "cpu");
+        assert!(old_table.table_name.starts_with("cpu-"));
+
+        let new_table = db_schema.table_definition("cpu").unwrap();
+        assert_eq!(new_table.table_id, new_id);
+        assert!(!new_table.deleted);
+        assert_eq!(
+            new_table.series_key_names,
+            vec![Arc::from("region"), Arc::from("host")]
+        );
+        assert_eq!(
+            new_table.column_definition("usage").unwrap().data_type,
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        );
+
+        // replacing a table that does not exist fails without changing the catalog:
+        assert!(matches!(
+            catalog
+                .replace_table(
+                    "foo",
+                    "mem",
+                    TableSpec::new("mem", &["host"], &[("free", FieldDataType::Integer)]),
+                )
+                .await
+                .unwrap_err(),
+            CatalogError::NotFound
+        ));
+        assert_eq!(catalog.sequence_number(), sequence.next());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tables_with_field_type() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("online", FieldDataType::Boolean),
+                    ("throttled", FieldDataType::Boolean),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "disk",
+                &["host"],
+                &[
+                    ("healthy", FieldDataType::Boolean),
+                    ("used", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        let db_schema = catalog.db_schema("foo").unwrap();
+        let cpu_id = db_schema.table_name_to_id("cpu").unwrap();
+        let disk_id = db_schema.table_name_to_id("disk").unwrap();
+        let mem_id = db_schema.table_name_to_id("mem").unwrap();
+
+        let mut booleans = catalog.tables_with_field_type("foo", InfluxFieldType::Boolean);
+        for (_, fields) in booleans.iter_mut() {
+            fields.sort();
+        }
+        assert_eq!(
+            booleans,
+            vec![
+                (cpu_id, vec![Arc::from("online"), Arc::from("throttled")]),
+                (disk_id, vec![Arc::from("healthy")]),
+            ]
+        );
+        assert_eq!(
+            catalog.tables_with_field_type("foo", InfluxFieldType::Integer),
+            vec![(mem_id, vec![Arc::from("free")])]
+        );
+        assert!(
+            catalog
+                .tables_with_field_type("foo", InfluxFieldType::String)
+                .is_empty()
+        );
+        assert!(
+            catalog
+                .tables_with_field_type("bar", InfluxFieldType::Boolean)
+                .is_empty()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_column_diff() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "a",
+                &["host", "region"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("count", FieldDataType::Integer),
+                    ("up", FieldDataType::Boolean),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "b",
+                &["host", "dc"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("count", FieldDataType::Float),
+                    ("status", FieldDataType::String),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let db = catalog.db_schema("foo").unwrap();
+        let a = db.table_definition("a").unwrap();
+        let b = db.table_definition("b").unwrap();
+
+        let diff = a.column_diff(&b);
+        assert_eq!(diff.only_in_self, ["region", "up"].map(Arc::<str>::from));
+        assert_eq!(diff.only_in_other, ["dc", "status"].map(Arc::<str>::from));
+        assert_eq!(
+            diff.type_mismatch,
+            [(
+                Arc::<str>::from("count"),
+                InfluxColumnType::Field(InfluxFieldType::Integer),
+                InfluxColumnType::Field(InfluxFieldType::Float),
+            )]
+        );
+        assert!(!diff.is_empty());
+
+        // the diff is symmetric when the tables are swapped:
+        let reverse = b.column_diff(&a);
+        assert_eq!(reverse.only_in_self, diff.only_in_other);
+        assert_eq!(reverse.only_in_other, diff.only_in_self);
+
+        // a table has no difference with itself:
+        assert!(a.column_diff(&a).is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tokens_expiring_before() {
+        use iox_time::MockProvider;
+        let now = Time::from_timestamp_nanos(0);
+        let time_provider = Arc::new(MockProvider::new(now));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+
+        catalog.create_admin_token(false).await.unwrap();
+        for (name, expiry_secs) in [("ten", 10), ("hundred", 100), ("thousand", 1000)] {
+            catalog
+                .create_scoped_token(name.to_string(), vec![], Some(expiry_secs))
+                .await
+                .unwrap();
+        }
+        catalog
+            .create_scoped_token("forever".to_string(), vec![], None)
+            .await
+            .unwrap();
+
+        let names = |tokens: Vec<Arc<TokenInfo>>| {
+            tokens
+                .into_iter()
+                .map(|t| Arc::clone(&t.name))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(catalog.tokens_expiring_before(now + Duration::from_secs(500))),
+            ["ten", "hundred"].map(Arc::<str>::from)
+        );
+        assert!(
+            catalog
+                .tokens_expiring_before(now + Duration::from_secs(5))
+                .is_empty()
+        );
+
+        // once a token has expired it is no longer reported:
+        time_provider.set(now + Duration::from_secs(20));
+        assert_eq!(
+            names(catalog.tokens_expiring_before(now + Duration::from_secs(10_000))),
+            ["hundred", "thousand"].map(Arc::<str>::from)
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_set_retention_for_databases() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        for name in ["foo", "bar", "baz"] {
+            catalog.create_database(name).await.unwrap();
+        }
+        let week = RetentionPeriod::Duration(Duration::from_secs(7 * 24 * 60 * 60));
+
+        // both databases are changed in a single batch:
+        let sequence = catalog.sequence_number();
+        let changed = catalog
+            .set_retention_for_databases(&["foo", "bar"], week)
+            .await
+            .unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(catalog.sequence_number(), sequence.next());
+        let retention = |name: &str| catalog.db_schema(name).unwrap().retention_period;
+        assert_eq!(retention("foo"), week);
+        assert_eq!(retention("bar"), week);
+        assert_eq!(retention("baz"), RetentionPeriod::Indefinite);
+
+        // databases that already have the retention period are not counted:
+        let changed = catalog
+            .set_retention_for_databases(&["foo", "baz"], week)
+            .await
+            .unwrap();
+        assert_eq!(changed, 1);
+
+        // a database named more than once is only updated once:
+        let sequence = catalog.sequence_number();
+        let changed = catalog
+            .set_retention_for_databases(&["foo", "foo"], RetentionPeriod::Indefinite)
+            .await
+            .unwrap();
+        assert_eq!(changed, 1);
+        assert_eq!(catalog.sequence_number(), sequence.next());
+        assert_eq!(retention("foo"), RetentionPeriod::Indefinite);
+        catalog
+            .set_retention_for_databases(&["foo"], week)
+            .await
+            .unwrap();
+
+        // nothing is written when no database needs to change:
+        let sequence = catalog.sequence_number();
+        let changed = catalog
+            .set_retention_for_databases(&["foo", "bar"], week)
+            .await
+            .unwrap();
+        assert_eq!(changed, 0);
+        assert_eq!(catalog.sequence_number(), sequence);
+
+        // naming a missing database fails without changing any of the others:
+        let sequence = catalog.sequence_number();
+        let err = catalog
+            .set_retention_for_databases(&["foo", "missing"], RetentionPeriod::Indefinite)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::NotFound));
+        assert_eq!(retention("foo"), week);
+        assert_eq!(catalog.sequence_number(), sequence);
+    }
+
+    #[test_log::test(tokio::test(flavor = "multi_thread", worker_threads = 4))]
+    async fn test_fair_write_permit_does_not_retry_local_updates() {
+        use metric::{Attributes, Metric, U64Counter};
+        let metric_registry = Arc::new(Registry::new());
+        let catalog = Arc::new(
+            Catalog::new_with_args(
+                "test",
+                Arc::new(InMemory::new()) as _,
+                Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+                Arc::clone(&metric_registry),
+                CatalogArgs::default().with_fair_write_permit(true),
+            )
+            .await
+            .unwrap(),
+        );
+        catalog.create_database("foo").await.unwrap();
+
+        // concurrent updates to the same database each wait their turn, rather than composing a
+        // batch that another update makes stale:
+        let handles = (0..20)
+            .map(|i| {
+                let catalog = Arc::clone(&catalog);
+                tokio::spawn(async move {
+                    catalog
+                        .create_table(
+                            "foo",
+                            &format!("table_{i}"),
+                            &["host"],
+                            &[("usage", FieldDataType::Float)],
+                        )
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(20, catalog.db_schema("foo").unwrap().table_count());
+        let retries = metric_registry
+            .get_instrument::<Metric<U64Counter>>(metrics::CATALOG_OPERATION_RETRIES_METRIC_NAME)
+            .unwrap()
+            .get_observer(&Attributes::from([]))
+            .unwrap()
+            .fetch();
+        assert_eq!(0, retries);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_fair_write_permit_serves_updates_in_order() {
+        let catalog = Arc::new(
+            Catalog::new_with_args(
+                "test",
+                Arc::new(InMemory::new()) as _,
+                Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+                Arc::new(Registry::new()),
+                CatalogArgs::default().with_fair_write_permit(true),
+            )
+            .await
+            .unwrap(),
+        );
+
+        // hold the queue so that each update waits on it in the order that it was spawned; on the
+        // current thread runtime, yielding lets the spawned update run until it is queued:
+        let queue = catalog.fair_write_queue.lock().await;
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let catalog = Arc::clone(&catalog);
+            handles.push(tokio::spawn(async move {
+                catalog.create_database(&format!("db_{i}")).await.unwrap()
+            }));
+            tokio::task::yield_now().await;
+        }
+        drop(queue);
+
+        let mut applied = Vec::new();
+        for handle in handles {
+            let batch = handle.await.unwrap();
+            let db_id = batch.batch().as_database().unwrap().database_id;
+            applied.push((batch.sequence_number(), db_id));
+        }
+        // the updates were applied in spawn order, with each database id assigned in turn:
+        for pair in applied.windows(2) {
+            assert_eq!(pair[1].0, pair[0].0.next());
+            assert!(pair[1].1 > pair[0].1);
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_column_type_counts() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("count", FieldDataType::Integer),
+                    ("up", FieldDataType::Boolean),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        assert_eq!(table.column_type_counts(), (2, 3, 1));
+        assert_eq!(table.column_type_counts().0, table.num_tag_columns());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_token_audit_csv() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        catalog
+            .create_scoped_token(
+                "auditor".to_string(),
+                vec![
+                    Permission {
+                        resource_type: ResourceType::Database,
+                        resource_identifier: ResourceIdentifier::Database(vec![foo_id, bar_id]),
+                        actions: Actions::Database(DatabaseActions(1 | 2)),
+                    },
+                    Permission {
+                        resource_type: ResourceType::Token,
+                        resource_identifier: ResourceIdentifier::Wildcard,
+                        actions: Actions::Token(CrudActions(1)),
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_scoped_token("nothing".to_string(), vec![], Some(60))
+            .await
+            .unwrap();
+
+        let csv = catalog.token_audit_csv();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            rows[0],
+            "token_name,created_at,updated_at,expiry,permission"
+        );
+        let auditor_rows = rows
+            .iter()
+            .filter(|row| row.starts_with("auditor,"))
+            .collect::<Vec<_>>();
+        assert_eq!(auditor_rows.len(), 2);
+        assert!(auditor_rows[0].ends_with(",,,\"db:foo,bar:read,write\""));
+        assert!(auditor_rows[1].ends_with(",,,token:*:read"));
+
+        // a token without permissions still gets a row:
+        let nothing_rows = rows
+            .iter()
+            .filter(|row| row.starts_with("nothing,"))
+            .collect::<Vec<_>>();
+        assert_eq!(nothing_rows.len(), 1);
+        assert!(nothing_rows[0].ends_with(','));
+        assert_eq!(rows.len(), 4);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tables_with_inconsistent_sort_key() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for table in ["cpu", "mem"] {
+            catalog
+                .create_table(
+                    "foo",
+                    table,
+                    &["region", "host"],
+                    &[("usage", FieldDataType::Float)],
+                )
+                .await
+                .unwrap();
+        }
+        assert!(catalog.tables_with_inconsistent_sort_key("foo").is_empty());
+
+        let db = catalog.db_schema("foo").unwrap();
+        let mut cpu = db.table_definition("cpu").unwrap().as_ref().clone();
+        assert!(cpu.sort_key_consistent());
+
+        // reverse the sort key so that it no longer agrees with the series key:
+        cpu.sort_key = SortKey::from_columns(["time", "host", "region"]);
+        assert!(!cpu.sort_key_consistent());
+        let cpu_id = cpu.table_id;
+        {
+            let mut inner = catalog.inner.write();
+            let mut db = inner.databases.get_by_id(&db.id).unwrap();
+            Arc::make_mut(&mut db).tables.update(cpu_id, cpu).unwrap();
+            inner.databases.update(db.id, db).unwrap();
+        }
+
+        assert_eq!(catalog.tables_with_inconsistent_sort_key("foo"), [cpu_id]);
+        assert!(catalog.tables_with_inconsistent_sort_key("bar").is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_null_tag_placeholder_round_trip() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        assert!(catalog.null_tag_placeholder("foo").is_none());
+
+        catalog
+            .set_null_tag_placeholder("foo", Some("<none>"))
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.null_tag_placeholder("foo").as_deref(),
+            Some("<none>")
+        );
+        assert!(matches!(
+            catalog
+                .set_null_tag_placeholder("bar", Some("<none>"))
+                .await
+                .unwrap_err(),
+            CatalogError::NotFound
+        ));
+
+        // the placeholder survives serialization of the catalog snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let deserialized = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        new_catalog.update_from_snapshot(deserialized);
+        assert_eq!(
+            new_catalog.null_tag_placeholder("foo").as_deref(),
+            Some("<none>")
+        );
+
+        // and can be cleared:
+        catalog.set_null_tag_placeholder("foo", None).await.unwrap();
+        assert!(catalog.null_tag_placeholder("foo").is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_trigger_plugin_paths() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        for (db, trigger, plugin) in [
+            ("foo", "t1", "downsample.py"),
+            ("foo", "t2", "alerts/notify.py"),
+            ("bar", "t1", "downsample.py"),
+        ] {
+            catalog
+                .create_processing_engine_trigger(
+                    db,
+                    trigger,
+                    "test".into(),
+                    ValidPluginFilename::from_validated_name(plugin),
+                    "all_tables",
+                    TriggerSettings::default(),
+                    &None,
+                    false,
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            catalog.trigger_plugin_paths(),
+            BTreeSet::from(["alerts/notify.py", "downsample.py"].map(Arc::<str>::from))
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_max_retention_reject() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_max_retention(30 * day, MaxRetentionMode::Reject),
+        )
+        .await
+        .unwrap();
+
+        // databases must be created with a retention period no longer than the maximum, whether
+        // explicitly or by a write:
+        for retention_period in [None, Some(365 * day)] {
+            let err = catalog
+                .create_database_opts("foo", CreateDatabaseOptions { retention_period })
+                .await
+                .unwrap_err();
+            assert!(matches!(err, CatalogError::InvalidConfiguration { .. }));
+        }
+        assert!(matches!(
+            catalog.begin("foo"),
+            Err(CatalogError::InvalidConfiguration { .. })
+        ));
+        assert!(catalog.db_schema("foo").is_none());
+        catalog
+            .create_database_opts(
+                "foo",
+                CreateDatabaseOptions {
+                    retention_period: Some(day),
+                },
+            )
+            .await
+            .unwrap();
+
+        catalog
+            .set_retention_period_for_database("foo", 7 * day)
+            .await
+            .unwrap();
+        let err = catalog
+            .set_retention_period_for_database("foo", 365 * day)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidConfiguration { .. }));
+        assert_contains!(err.to_string(), "30days");
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(7 * day)
+        );
+
+        // an indefinite retention period is rejected when there is a maximum:
+        let err = catalog
+            .clear_retention_period_for_database("foo")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidConfiguration { .. }));
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(7 * day)
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_max_retention_clamp() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_max_retention(30 * day, MaxRetentionMode::Clamp),
+        )
+        .await
+        .unwrap();
+
+        // databases created without a retention period, or with one over the maximum, are given
+        // the maximum:
+        catalog.create_database("foo").await.unwrap();
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(30 * day)
+        );
+        catalog
+            .create_database_opts(
+                "bar",
+                CreateDatabaseOptions {
+                    retention_period: Some(365 * day),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.db_schema("bar").unwrap().retention_period,
+            RetentionPeriod::Duration(30 * day)
+        );
+        // the internal database is exempt:
+        assert_eq!(
+            catalog
+                .db_schema(INTERNAL_DB_NAME)
+                .unwrap()
+                .retention_period,
+            RetentionPeriod::Indefinite
+        );
+
+        catalog
+            .set_retention_period_for_database("foo", 7 * day)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(7 * day)
+        );
+        catalog
+            .set_retention_period_for_database("foo", 365 * day)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(30 * day)
+        );
+
+        // clamping does not apply to an indefinite retention period:
+        assert!(matches!(
+            catalog
+                .clear_retention_period_for_database("foo")
+                .await
+                .unwrap_err(),
+            CatalogError::InvalidConfiguration { .. }
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_resolve_or_describe_table() {
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for table in ["cpu", "mem"] {
+            catalog
+                .create_table("foo", table, &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        catalog
+            .soft_delete_table("foo", "mem", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        let db = catalog.db_schema("foo").unwrap();
+        let cpu_id = db.table_name_to_id("cpu").unwrap();
+        let mem_id = db.table_name_to_id("mem-19700101T000000").unwrap();
+        let sequence = catalog.sequence_number();
+
+        assert_eq!(
+            catalog.resolve_or_describe_table("foo", "cpu"),
+            TableResolution::Existing(cpu_id)
+        );
+        assert_eq!(
+            catalog.resolve_or_describe_table("foo", "mem"),
+            TableResolution::SoftDeleted {
+                table_id: mem_id,
+                deleted_name: "mem-19700101T000000".into(),
+            }
+        );
+        assert_eq!(
+            catalog.resolve_or_describe_table("foo", "disk"),
+            TableResolution::New
+        );
+        // a table whose name only shares a prefix with a deleted table is new:
+        assert_eq!(
+            catalog.resolve_or_describe_table("foo", "me"),
+            TableResolution::New
+        );
+        assert_eq!(
+            catalog.resolve_or_describe_table("bar", "cpu"),
+            TableResolution::New
+        );
+
+        // nothing was created:
+        assert_eq!(catalog.sequence_number(), sequence);
+        assert!(catalog.db_schema("bar").is_none());
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("disk")
+                .is_none()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_generation_config_summary() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let summary = catalog.generation_config_summary();
+        assert!(summary.levels.is_empty());
+        assert_eq!(summary.num_levels, 0);
+        assert_eq!(summary.max_level, None);
+
+        catalog
+            .set_gen1_duration(Duration::from_secs(600))
+            .await
+            .unwrap();
+        catalog
+            .set_generation_duration(3, Duration::from_secs(3600))
+            .await
+            .unwrap();
+
+        let summary = catalog.generation_config_summary();
+        assert_eq!(
+            summary.levels,
+            [
+                GenerationLevelSummary {
+                    level: 1,
+                    duration_seconds: 600
+                },
+                GenerationLevelSummary {
+                    level: 3,
+                    duration_seconds: 3600
+                },
+            ]
+        );
+        assert_eq!(summary.num_levels, 2);
+        assert_eq!(summary.max_level, Some(3));
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "levels": [
+                    {"level": 1, "duration_seconds": 600},
+                    {"level": 3, "duration_seconds": 3600},
+                ],
+                "num_levels": 2,
+                "max_level": 3,
+            })
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_effective_retention_for_table() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        assert_eq!(catalog.effective_retention_for_table("foo", "cpu"), None);
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        catalog
+            .set_retention_period_for_database("foo", week)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.effective_retention_for_table("foo", "cpu"),
+            Some(week)
+        );
+
+        // the cutoff map agrees with the effective retention:
+        let db_schema = catalog.db_schema("foo").unwrap();
+        let table_id = db_schema.table_name_to_id("cpu").unwrap();
+        let cutoff = catalog.get_retention_period_cutoff_map()[&(db_schema.id, table_id)];
+        let now = catalog.time_provider().now().timestamp_nanos();
+        assert!(cutoff <= now - week.as_nanos() as i64);
+
+        assert_eq!(catalog.effective_retention_for_table("foo", "mem"), None);
+        assert_eq!(catalog.effective_retention_for_table("bar", "cpu"), None);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_poll_changes() {
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_change_log_capacity(3),
+        )
+        .await
+        .unwrap();
+        let start = catalog.sequence_number();
+        assert_eq!(catalog.poll_changes(start), ChangePoll::Changes(vec![]));
+
+        let mut batches = vec![];
+        for name in ["foo", "bar", "baz", "qux"] {
+            batches.push(catalog.create_database(name).await.unwrap());
+        }
+        let entry = |batch: &OrderedCatalogBatch| ChangeEntry {
+            sequence_number: batch.sequence_number(),
+            batch: batch.batch().clone(),
+        };
+
+        // poll from an older sequence that is still within the window:
+        assert_eq!(
+            catalog.poll_changes(batches[1].sequence_number()),
+            ChangePoll::Changes(vec![entry(&batches[2]), entry(&batches[3])])
+        );
+        assert_eq!(
+            catalog.poll_changes(batches[0].sequence_number()),
+            ChangePoll::Changes(batches[1..].iter().map(entry).collect())
+        );
+        // the change for the first database has been evicted from the window:
+        assert_eq!(catalog.poll_changes(start), ChangePoll::ResyncRequired);
+        // nothing has changed since the latest sequence:
+        assert_eq!(
+            catalog.poll_changes(catalog.sequence_number()),
+            ChangePoll::Changes(vec![])
+        );
+    }
+
+    #[test]
+    fn test_validate_database_name() {
+        for name in [
+            "foo",
+            "Foo_Bar",
+            "foo-bar",
+            "1st_db",
+            "a",
+            "foo/autogen",
+            "foo.bar",
+            "foo.bar/autogen",
+        ] {
+            Catalog::validate_database_name(name).unwrap();
+        }
+
+        let reason = |name: &str| match Catalog::validate_database_name(name).unwrap_err() {
+            CatalogError::InvalidDatabaseName { name: n, reason } => {
+                assert_eq!(n, name);
+                reason
+            }
+            other => panic!("unexpected error: {other}"),
+        };
+        assert_eq!(
+            reason(INTERNAL_DB_NAME),
+            ValidateDbNameError::InvalidStartChar
+        );
+        assert_eq!(reason("_foo"), ValidateDbNameError::InvalidStartChar);
+        assert_eq!(reason(""), ValidateDbNameError::Empty);
+        assert_eq!(reason("-foo"), ValidateDbNameError::InvalidStartChar);
+        assert_eq!(reason("föo"), ValidateDbNameError::InvalidChar);
+        assert_eq!(
+            reason("foo/bar/baz"),
+            ValidateDbNameError::InvalidRetentionPolicy
+        );
+    }
+
+    #[test]
+    fn test_validate_db_name() {
+        assert_eq!(
+            validate_db_name("foo/bar", false),
+            Err(ValidateDbNameError::InvalidChar)
+        );
+        assert!(validate_db_name("foo/bar", true).is_ok());
+        assert_eq!(
+            validate_db_name("foo/bar/baz", true),
+            Err(ValidateDbNameError::InvalidRetentionPolicy)
+        );
+        assert_eq!(
+            validate_db_name("foo/", true),
+            Err(ValidateDbNameError::InvalidRetentionPolicy)
+        );
+        assert_eq!(
+            validate_db_name("foo/bar/baz", false),
+            Err(ValidateDbNameError::InvalidChar)
+        );
+        assert_eq!(
+            validate_db_name("foo.bar", false),
+            Err(ValidateDbNameError::InvalidChar)
+        );
+        assert!(validate_db_name("foo.bar", true).is_ok());
+        assert!(validate_db_name("foo.bar/autogen", true).is_ok());
+        assert_eq!(
+            validate_db_name("_foo", false),
+            Err(ValidateDbNameError::InvalidStartChar)
+        );
+        assert_eq!(validate_db_name("", false), Err(ValidateDbNameError::Empty));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_create_database_validates_name() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        for name in ["_foo", "föo", ""] {
+            assert!(matches!(
+                catalog.create_database(name).await,
+                Err(CatalogError::InvalidDatabaseName { .. })
+            ));
+            assert!(matches!(
+                catalog.begin(name),
+                Err(CatalogError::InvalidDatabaseName { .. })
+            ));
+            assert!(catalog.db_schema(name).is_none());
+        }
+        // databases written to through the v1 API include the retention policy in their name:
+        catalog.create_database("foo/autogen").await.unwrap();
+        catalog.create_database("foo.bar/autogen").await.unwrap();
+        assert!(catalog.db_schema(INTERNAL_DB_NAME).is_some());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_all_columns() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "mem",
+                &["host", "region"],
+                &[("free", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "bar",
+                "disk",
+                &["path"],
+                &[("used", FieldDataType::UInteger)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("bar", "gone", &["t"], &[("f", FieldDataType::String)])
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("bar", "gone", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        let columns = catalog.all_columns();
+        let expected: usize = catalog
+            .list_db_schema()
+            .iter()
+            .filter(|db| !db.deleted)
+            .flat_map(|db| db.tables())
+            .filter(|table| !table.deleted)
+            .map(|table| table.num_columns())
+            .sum();
+        assert_eq!(columns.len(), expected);
+        assert!(columns.iter().all(|c| c.table_name.as_ref() != "gone"));
+
+        let usage = columns
+            .iter()
+            .find(|c| c.column_name.as_ref() == "usage")
+            .unwrap();
+        assert_eq!(usage.database_name.as_ref(), "foo");
+        assert_eq!(usage.database_id, catalog.db_name_to_id("foo").unwrap());
+        assert_eq!(usage.table_name.as_ref(), "cpu");
+        assert_eq!(
+            usage.data_type,
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        );
+        assert!(usage.nullable);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_most_recently_active_database() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        assert_eq!(
+            catalog
+                .most_recently_active_database()
+                .unwrap()
+                .name
+                .as_ref(),
+            "bar"
+        );
+
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let active = catalog.most_recently_active_database().unwrap();
+        assert_eq!(active.name.as_ref(), "foo");
+        assert_eq!(
+            active.last_modified_sequence,
+            Some(catalog.sequence_number())
+        );
+
+        catalog
+            .create_table("bar", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog
+                .most_recently_active_database()
+                .unwrap()
+                .name
+                .as_ref(),
+            "bar"
+        );
+
+        // deleted databases are not reported:
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog
+                .most_recently_active_database()
+                .unwrap()
+                .name
+                .as_ref(),
+            "foo"
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_disable_and_enable_token() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let (token_info, _) = catalog
+            .create_scoped_token("reader".to_string(), vec![], None)
+            .await
+            .unwrap();
+        let hash = token_info.hash.clone();
+        assert!(catalog.get_token(hash.clone()).is_some());
+
+        catalog.disable_token("reader").await.unwrap();
+        assert!(catalog.get_token(hash.clone()).is_none());
+        // the token is kept for auditing:
+        let disabled = catalog
+            .get_tokens()
+            .into_iter()
+            .find(|token| token.name.as_ref() == "reader")
+            .unwrap();
+        assert!(disabled.disabled);
+
+        // disabled state survives a round trip through a snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        catalog.update_from_snapshot(snapshot);
+        assert!(catalog.get_token(hash.clone()).is_none());
+
+        catalog.enable_token("reader").await.unwrap();
+        assert_eq!(catalog.get_token(hash).unwrap().id, token_info.id);
+
+        assert!(matches!(
+            catalog.disable_token(DEFAULT_OPERATOR_TOKEN_NAME).await,
+            Err(CatalogError::CannotModifyOperatorToken)
+        ));
+        assert!(matches!(
+            catalog.disable_token("missing").await,
+            Err(CatalogError::NotFound)
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tables_for_generation() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "bar",
+                "disk",
+                &["path"],
+                &[("used", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("bar", "gone", &["t"], &[("f", FieldDataType::String)])
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("bar", "gone", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        assert!(catalog.tables_for_generation(1).is_empty());
+
+        let gen1 = Duration::from_secs(600);
+        catalog.set_gen1_duration(gen1).await.unwrap();
+
+        let foo = catalog.db_schema("foo").unwrap();
+        let bar = catalog.db_schema("bar").unwrap();
+        let mut expected = vec![
+            (foo.id, foo.table_name_to_id("cpu").unwrap(), gen1),
+            (foo.id, foo.table_name_to_id("mem").unwrap(), gen1),
+            (bar.id, bar.table_name_to_id("disk").unwrap(), gen1),
+        ];
+        expected.sort();
+        let mut tables = catalog.tables_for_generation(1);
+        tables.sort();
+        assert_eq!(tables, expected);
+
+        assert!(catalog.tables_for_generation(2).is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_rename_database() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let table_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        catalog.rename_database("foo", "baz").await.unwrap();
+        assert!(catalog.db_schema("foo").is_none());
+        let baz = catalog.db_schema("baz").unwrap();
+        assert_eq!(baz.id, foo_id);
+        assert_eq!(baz.table_name_to_id("cpu"), Some(table_id));
+        assert_eq!(catalog.db_id_to_name(&foo_id).as_deref(), Some("baz"));
+
+        // the new name is bound to the same id after a reload from a snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        catalog.update_from_snapshot(snapshot);
+        assert_eq!(catalog.db_name_to_id("baz"), Some(foo_id));
+        assert_eq!(catalog.db_name_to_id("foo"), None);
+
+        // the old name can be reused:
+        catalog.create_database("foo").await.unwrap();
+        assert_ne!(catalog.db_name_to_id("foo"), Some(foo_id));
+
+        assert!(matches!(
+            catalog.rename_database("baz", "bar").await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(matches!(
+            catalog.rename_database("missing", "qux").await,
+            Err(CatalogError::NotFound)
+        ));
+        assert!(matches!(
+            catalog.rename_database(INTERNAL_DB_NAME, "qux").await,
+            Err(CatalogError::CannotRenameInternalDatabase)
+        ));
+
+        // triggers refer to their database by name, so it can't be renamed while it has any:
+        catalog
+            .create_processing_engine_trigger(
+                "bar",
+                "trigger",
+                "test".into(),
+                ValidPluginFilename::from_validated_name("plugin.py"),
+                "all_tables",
+                TriggerSettings::default(),
+                &None,
+                true,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog.rename_database("bar", "qux").await,
+            Err(CatalogError::CannotRenameDatabaseWithTriggers { .. })
+        ));
+        catalog
+            .delete_processing_engine_trigger("bar", "trigger", false)
+            .await
+            .unwrap();
+        catalog.rename_database("bar", "qux").await.unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_rename_table() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "mem", &["host"], &[("free", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_last_cache(
+                "foo",
+                "cpu",
+                Some("cpu_last"),
+                Some(&["host"]),
+                Some(&["usage"]),
+                LastCacheSize::new(1).unwrap(),
+                LastCacheTtl::from_secs(600),
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_distinct_cache(
+                "foo",
+                "cpu",
+                Some("cpu_hosts"),
+                &["host"],
+                MaxCardinality::from_usize_unchecked(100),
+                MaxAge::from_secs(10),
+            )
+            .await
+            .unwrap();
+        let before = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+
+        catalog
+            .rename_table("foo", "cpu", "processor")
+            .await
+            .unwrap();
+        let db = catalog.db_schema("foo").unwrap();
+        assert!(db.table_definition("cpu").is_none());
+        let after = db.table_definition("processor").unwrap();
+        assert_eq!(after.table_id, before.table_id);
+        assert_eq!(after.columns, before.columns);
+        assert_eq!(after.series_key, before.series_key);
+        assert_eq!(after.series_key_names, before.series_key_names);
+        assert_eq!(after.sort_key, before.sort_key);
+        assert_eq!(
+            after.influx_schema().measurement(),
+            Some(&"processor".to_owned())
+        );
+        assert_eq!(
+            after
+                .last_caches
+                .get_by_name("cpu_last")
+                .unwrap()
+                .table
+                .as_ref(),
+            "processor"
+        );
+        assert_eq!(
+            after
+                .distinct_caches
+                .get_by_name("cpu_hosts")
+                .unwrap()
+                .table_name
+                .as_ref(),
+            "processor"
+        );
+
+        assert!(matches!(
+            catalog.rename_table("foo", "processor", "mem").await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(matches!(
+            catalog.rename_table("foo", "cpu", "other").await,
+            Err(CatalogError::NotFound)
+        ));
+
+        // a table cannot be renamed while a trigger refers to it by name, but triggers on other
+        // tables do not matter:
+        catalog
+            .create_processing_engine_trigger(
+                "foo",
+                "trigger",
+                "test".into(),
+                ValidPluginFilename::from_validated_name("plugin.py"),
+                "table:mem",
+                TriggerSettings::default(),
+                &None,
+                true,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog.rename_table("foo", "mem", "memory").await,
+            Err(CatalogError::CannotRenameTableWithTriggers { .. })
+        ));
+        catalog
+            .rename_table("foo", "processor", "cpu")
+            .await
+            .unwrap();
+        catalog
+            .delete_processing_engine_trigger("foo", "trigger", false)
+            .await
+            .unwrap();
+        catalog.rename_table("foo", "mem", "memory").await.unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_set_column_allowed_values() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+
+        catalog
+            .set_column_allowed_values("foo", "cpu", "region", Some(&["us-east", "us-west"]))
+            .await
+            .unwrap();
+        let allowed = |catalog: &Catalog| {
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .column_definition("region")
+                .unwrap()
+                .allowed_values
+                .clone()
+        };
+        let expected: BTreeSet<Arc<str>> = ["us-east".into(), "us-west".into()].into();
+        assert_eq!(allowed(&catalog), Some(expected.clone()));
+
+        // the allowed values survive a round trip through a snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        catalog.update_from_snapshot(snapshot);
+        assert_eq!(allowed(&catalog), Some(expected));
+
+        catalog
+            .set_column_allowed_values("foo", "cpu", "region", None)
+            .await
+            .unwrap();
+        assert_eq!(allowed(&catalog), None);
+
+        // only tag columns can be restricted:
+        let err = catalog
+            .set_column_allowed_values("foo", "cpu", "usage", Some(&["1.0"]))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CatalogError::InvalidColumnType {
+                expected: InfluxColumnType::Tag,
+                ..
+            }
+        ));
+        assert!(
+            catalog
+                .set_column_allowed_values("foo", "cpu", "host", Some(&[]))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_catalog_batch_affected_resources() {
+        let db_id = DbId::from(1);
+        let batch = CatalogBatch::database(
+            0,
+            db_id,
+            "foo",
+            vec![
+                DatabaseCatalogOp::CreateTable(CreateTableLog {
+                    database_id: db_id,
+                    database_name: "foo".into(),
+                    table_name: "cpu".into(),
+                    table_id: TableId::from(0),
+                    field_definitions: vec![FieldDefinition::new(
+                        ColumnId::from(0),
+                        "host",
+                        FieldDataType::Tag,
+                    )],
+                    key: vec![ColumnId::from(0)],
+                }),
+                DatabaseCatalogOp::AddFields(AddFieldsLog {
+                    database_name: "foo".into(),
+                    database_id: db_id,
+                    table_name: "mem".into(),
+                    table_id: TableId::from(3),
+                    field_definitions: vec![FieldDefinition::new(
+                        ColumnId::from(1),
+                        "free",
+                        FieldDataType::Integer,
+                    )],
+                }),
+            ],
+        );
+        let affected = batch.affected_resources();
+        assert_eq!(affected.databases, [db_id].into());
+        assert_eq!(
+            affected.tables,
+            [(db_id, TableId::from(0)), (db_id, TableId::from(3))].into()
+        );
+
+        let delete = CatalogBatch::delete(
+            0,
+            vec![
+                DeleteOp::DeleteDatabase(DbId::from(2)),
+                DeleteOp::DeleteTable(DbId::from(3), TableId::from(4)),
+            ],
+        );
+        let affected = delete.affected_resources();
+        assert_eq!(affected.databases, [DbId::from(2), DbId::from(3)].into());
+        assert_eq!(affected.tables, [(DbId::from(3), TableId::from(4))].into());
+
+        let token = CatalogBatch::Token(TokenBatch {
+            time_ns: 0,
+            ops: vec![],
+        });
+        assert_eq!(token.affected_resources(), Default::default());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_table_retention_period() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(
+            1_000_000_000_000_000_000,
+        )));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let db_schema = catalog.db_schema("foo").unwrap();
+        let key = (db_schema.id, db_schema.table_name_to_id("cpu").unwrap());
+        let now = time_provider.now().timestamp_nanos();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let week = 7 * day;
+
+        // the table has a retention period but the database is indefinite:
+        catalog
+            .set_retention_period_for_table("foo", "cpu", day)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.effective_retention_for_table("foo", "cpu"),
+            Some(day)
+        );
+        assert_eq!(
+            catalog.get_retention_period_cutoff_map()[&key],
+            now - day.as_nanos() as i64
+        );
+
+        // the table retention period survives a round trip through a snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        catalog.update_from_snapshot(snapshot);
+        assert_eq!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .retention_period,
+            RetentionPeriod::Duration(day)
+        );
+
+        // the shorter of the table and database retention periods wins:
+        catalog
+            .set_retention_period_for_database("foo", week)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.get_retention_period_cutoff_map()[&key],
+            now - day.as_nanos() as i64
+        );
+
+        // the table is indefinite but the database has a retention period:
+        catalog
+            .clear_retention_period_for_table("foo", "cpu")
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.effective_retention_for_table("foo", "cpu"),
+            Some(week)
+        );
+        assert_eq!(
+            catalog.get_retention_period_cutoff_map()[&key],
+            now - week.as_nanos() as i64
+        );
+
+        catalog
+            .clear_retention_period_for_database("foo")
+            .await
+            .unwrap();
+        assert!(catalog.get_retention_period_cutoff_map().is_empty());
+
+        assert!(matches!(
+            catalog
+                .set_retention_period_for_table("foo", "mem", day)
+                .await,
+            Err(CatalogError::TableNotFound { .. })
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_list_tables_paginated() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for name in ["t0", "t1", "t2", "t3", "t4"] {
+            catalog
+                .create_table("foo", name, &["tag"], &[("field", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        catalog
+            .soft_delete_table("foo", "t2", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        let names = |tables: &[Arc<TableDefinition>]| {
+            tables
+                .iter()
+                .map(|t| t.table_name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let page = catalog.list_tables_paginated("foo", None, 2);
+        assert_eq!(names(&page), ["t0", "t1"]);
+        let page = catalog.list_tables_paginated("foo", Some(page[1].table_id), 2);
+        assert_eq!(names(&page), ["t3", "t4"]);
+        let page = catalog.list_tables_paginated("foo", Some(page[1].table_id), 2);
+        assert!(page.is_empty());
+
+        // a cursor well past the end is not an error:
+        assert!(
+            catalog
+                .list_tables_paginated("foo", Some(TableId::from(100)), 2)
+                .is_empty()
+        );
+        assert!(catalog.list_tables_paginated("bar", None, 2).is_empty());
+        assert_eq!(
+            names(&catalog.list_tables_paginated("foo", None, 10)),
+            ["t0", "t1", "t3", "t4"]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_default_hard_delete_duration() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            Catalog::DEFAULT_HARD_DELETE_DURATION,
+            catalog.default_hard_delete_duration()
+        );
+
+        let duration = Duration::from_secs(60 * 60);
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            time_provider as _,
+            CatalogArgs::new(duration),
+        )
+        .await
+        .unwrap();
+        assert_eq!(duration, catalog.default_hard_delete_duration());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_overdue_hard_deletes() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        for table in ["t1", "t2"] {
+            catalog
+                .create_table("foo", table, &["tag"], &[("field", FieldDataType::String)])
+                .await
+                .unwrap();
+        }
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        let t1_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("t1")
+            .unwrap();
+
+        catalog
+            .soft_delete_table("foo", "t1", HardDeletionTime::Now)
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("foo", "t2", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Default)
+            .await
+            .unwrap();
+
+        time_provider.set(Time::from_timestamp_nanos(1));
+        let report = catalog.overdue_hard_deletes(time_provider.now());
+        assert!(report.databases.is_empty());
+        assert_eq!(
+            report.tables,
+            [(foo_id, t1_id, Time::from_timestamp_nanos(0))]
+        );
+        // the report does not remove anything:
+        assert!(
+            catalog
+                .db_schema_by_id(&foo_id)
+                .unwrap()
+                .table_definition_by_id(&t1_id)
+                .is_some()
+        );
+
+        let bar_hard_delete_time =
+            Time::from_timestamp_nanos(0) + catalog.default_hard_delete_duration();
+        let report = catalog.overdue_hard_deletes(bar_hard_delete_time);
+        assert_eq!(report.databases, [(bar_id, bar_hard_delete_time)]);
+        assert_eq!(report.tables.len(), 1);
+
+        catalog.hard_delete_table(&foo_id, &t1_id).await.unwrap();
+        catalog.hard_delete_database(&bar_id).await.unwrap();
+        assert!(
+            catalog
+                .overdue_hard_deletes(bar_hard_delete_time)
+                .is_empty()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_drop_column() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host", "region"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        let region_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap()
+            .column_name_to_id("region")
+            .unwrap();
+
+        catalog.drop_column("foo", "cpu", "region").await.unwrap();
+        catalog.drop_column("foo", "cpu", "usage").await.unwrap();
+
+        let check = |catalog: &Catalog| {
+            let table = catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap();
+            assert!(!table.column_exists("region"));
+            assert!(!table.column_exists("usage"));
+            assert!(table.columns.get_by_id(&region_id).is_none());
+            assert_eq!(table.series_key_names, [Arc::from("host")]);
+            assert_eq!(table.sort_key, SortKey::from_columns(["host", "time"]));
+            assert_eq!(table.schema.len(), 2);
+            assert!(table.schema.find_index_of("region").is_none());
+        };
+        check(&catalog);
+
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.update_from_snapshot(snapshot);
+        check(&catalog);
+
+        assert!(matches!(
+            catalog.drop_column("foo", "cpu", "time").await,
+            Err(CatalogError::CannotDropTimeColumn)
+        ));
+        assert!(matches!(
+            catalog.drop_column("foo", "cpu", "region").await,
+            Err(CatalogError::NotFound)
+        ));
+
+        // the id of the dropped column is not reused:
+        let mut txn = catalog.begin("foo").unwrap();
+        txn.column_or_create("cpu", "region", FieldDataType::Tag)
+            .unwrap();
+        catalog.commit(txn).await.unwrap();
+        let new_region_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap()
+            .column_name_to_id("region")
+            .unwrap();
+        assert_ne!(region_id, new_region_id);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_list_tokens_for_resource() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        let db_permission = |db_ids: Vec<DbId>| Permission {
+            resource_type: ResourceType::Database,
+            resource_identifier: ResourceIdentifier::Database(db_ids),
+            actions: Actions::Database(DatabaseActions(2)),
+        };
+        catalog.create_admin_token(false).await.unwrap();
+        catalog
+            .create_scoped_token("foo".to_string(), vec![db_permission(vec![foo_id])], None)
+            .await
+            .unwrap();
+        catalog
+            .create_scoped_token(
+                "multi".to_string(),
+                vec![
+                    Permission {
+                        resource_type: ResourceType::Token,
+                        resource_identifier: ResourceIdentifier::Wildcard,
+                        actions: Actions::Token(CrudActions(1)),
+                    },
+                    db_permission(vec![bar_id]),
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let names = |tokens: Vec<Arc<TokenInfo>>| {
+            let mut names = tokens
+                .iter()
+                .map(|t| t.name.to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(catalog.list_tokens_for_resource(
+                ResourceType::Database,
+                ResourceIdentifier::Database(vec![foo_id])
+            )),
+            ["_admin", "foo"]
+        );
+        assert_eq!(
+            names(catalog.list_tokens_for_resource(
+                ResourceType::Database,
+                ResourceIdentifier::Database(vec![bar_id])
+            )),
+            ["_admin", "multi"]
+        );
+        assert_eq!(
+            names(catalog.list_tokens_for_resource(
+                ResourceType::Token,
+                ResourceIdentifier::Token(vec![TokenId::from(0)])
+            )),
+            ["_admin", "multi"]
+        );
+        assert_eq!(
+            names(
+                catalog
+                    .list_tokens_for_resource(ResourceType::Database, ResourceIdentifier::Wildcard)
+            ),
+            ["_admin", "foo", "multi"]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_set_column_indexed() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("request_id", FieldDataType::String),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .set_column_indexed("foo", "cpu", "request_id", true)
+            .await
+            .unwrap();
+
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        let host_id = table.column_name_to_id("host").unwrap();
+        let request_id = table.column_name_to_id("request_id").unwrap();
+        assert_eq!(table.indexed_column_ids(), [request_id]);
+        assert_eq!(table.index_column_ids(), [host_id]);
+
+        // the mark survives serialization of the catalog snapshot:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test").await.unwrap();
+        new_catalog.update_from_snapshot(snapshot);
+        let table = new_catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        assert_eq!(table.indexed_column_ids(), [request_id]);
+
+        assert_contains!(
+            catalog
+                .set_column_indexed("foo", "cpu", "host", true)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "only field columns can be marked as indexed"
+        );
+
+        catalog
+            .set_column_indexed("foo", "cpu", "request_id", false)
+            .await
+            .unwrap();
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        assert!(table.indexed_column_ids().is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_schema_digest() {
+        let catalog_a = Catalog::new_in_memory("a").await.unwrap();
+        catalog_a.create_database("foo").await.unwrap();
+        catalog_a.create_database("bar").await.unwrap();
+        catalog_a
+            .create_table(
+                "foo",
+                "cpu",
+                &["host", "region"],
+                &[
+                    ("usage", FieldDataType::Float),
+                    ("count", FieldDataType::Integer),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog_a
+            .create_table(
+                "foo",
+                "mem",
+                &["host"],
+                &[("free", FieldDataType::UInteger)],
+            )
+            .await
+            .unwrap();
+        catalog_a
+            .create_table("bar", "disk", &["path"], &[("used", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        // the same schema, created in a different order so that ids are assigned differently:
+        let catalog_b = Catalog::new_in_memory("b").await.unwrap();
+        catalog_b.create_database("bar").await.unwrap();
+        catalog_b
+            .create_table("bar", "disk", &["path"], &[("used", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog_b.create_database("foo").await.unwrap();
+        catalog_b
+            .create_table(
+                "foo",
+                "mem",
+                &["host"],
+                &[("free", FieldDataType::UInteger)],
+            )
+            .await
+            .unwrap();
+        catalog_b
+            .create_table(
+                "foo",
+                "cpu",
+                &["host", "region"],
+                &[
+                    ("count", FieldDataType::Integer),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_ne!(
+            catalog_a.db_name_to_id("foo"),
+            catalog_b.db_name_to_id("foo")
+        );
+        assert_eq!(catalog_a.schema_digest(), catalog_b.schema_digest());
+
+        // a different series key order is a different schema:
+        catalog_b
+            .create_table(
+                "foo",
+                "net",
+                &["host", "iface"],
+                &[("bytes", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        catalog_a
+            .create_table(
+                "foo",
+                "net",
+                &["iface", "host"],
+                &[("bytes", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        assert_ne!(catalog_a.schema_digest(), catalog_b.schema_digest());
+
+        // deleted tables are not included:
+        catalog_a
+            .soft_delete_table("foo", "net", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog_b
+            .soft_delete_table("foo", "net", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert_eq!(catalog_a.schema_digest(), catalog_b.schema_digest());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_get_token_checked_expiry() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        let (token_info, _) = catalog
+            .create_named_admin_token_with_permission("short_lived".to_string(), Some(1))
+            .await
+            .unwrap();
+        let (forever_info, _) = catalog
+            .create_named_admin_token_with_permission("forever".to_string(), None)
+            .await
+            .unwrap();
+        let hash = token_info.hash.clone();
+        assert!(catalog.get_token_checked(hash.clone()).is_some());
+
+        time_provider.set(Time::from_timestamp_millis(999).unwrap());
+        assert!(catalog.get_token_checked(hash.clone()).is_some());
+
+        time_provider.set(Time::from_timestamp_millis(1_000).unwrap());
+        assert!(catalog.get_token_checked(hash.clone()).is_none());
+        // the unchecked lookup still finds it, so the authenticator can report the expiry:
+        assert!(catalog.get_token(hash).is_some());
+
+        time_provider.set(Time::from_timestamp_nanos(i64::MAX / 2));
+        assert!(
+            catalog
+                .get_token_checked(forever_info.hash.clone())
+                .is_some()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_update_token_expiry() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        let (token_info, _) = catalog
+            .create_scoped_token("reader".to_string(), vec![], Some(60))
+            .await
+            .unwrap();
+        assert_eq!(token_info.maybe_expiry_millis(), Some(60_000));
+
+        time_provider.set(Time::from_timestamp_millis(30_000).unwrap());
+        catalog
+            .update_token_expiry("reader", Some(3_600))
+            .await
+            .unwrap();
+        let get_token = |catalog: &Catalog| {
+            catalog
+                .get_tokens()
+                .into_iter()
+                .find(|t| t.name.as_ref() == "reader")
+                .unwrap()
+        };
+        let updated = get_token(&catalog);
+        // the expiry is relative to when the token was created:
+        assert_eq!(updated.maybe_expiry_millis(), Some(3_600_000));
+        assert_eq!(updated.updated_at, Some(30_000));
+        assert_eq!(updated.hash, token_info.hash);
+
+        assert!(matches!(
+            catalog.update_token_expiry("reader", Some(3_600)).await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(matches!(
+            catalog.update_token_expiry("writer", None).await,
+            Err(CatalogError::NotFound)
+        ));
+
+        catalog.update_token_expiry("reader", None).await.unwrap();
+        assert_eq!(get_token(&catalog).maybe_expiry_millis(), None);
+        catalog
+            .update_token_expiry("reader", Some(120))
+            .await
+            .unwrap();
+
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test-2").await.unwrap();
+        new_catalog.update_from_snapshot(snapshot);
+        let restored = get_token(&new_catalog);
+        assert_eq!(restored.maybe_expiry_millis(), Some(120_000));
+        assert_eq!(restored.hash, token_info.hash);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_complexity_score() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        assert_eq!(catalog.complexity_score("foo"), 0);
+        catalog.create_database("foo").await.unwrap();
+        assert_eq!(catalog.complexity_score("foo"), 0);
+
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        // one table, with three columns including time, and a series key of one:
+        let one_table = catalog.complexity_score("foo");
+        assert_eq!(
+            one_table,
+            Catalog::COMPLEXITY_TABLE_WEIGHT
+                + 3 * Catalog::COMPLEXITY_COLUMN_WEIGHT
+                + Catalog::COMPLEXITY_SERIES_KEY_WEIGHT
+        );
+
+        let mut txn = catalog.begin("foo").unwrap();
+        txn.column_or_create("cpu", "idle", FieldDataType::Float)
+            .unwrap();
+        catalog.commit(txn).await.unwrap();
+        let more_columns = catalog.complexity_score("foo");
+        assert!(more_columns > one_table);
+
+        catalog
+            .create_table(
+                "foo",
+                "mem",
+                &["host", "region"],
+                &[("free", FieldDataType::UInteger)],
+            )
+            .await
+            .unwrap();
+        let two_tables = catalog.complexity_score("foo");
+        assert!(two_tables > more_columns);
+
+        catalog
+            .soft_delete_table("foo", "mem", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert_eq!(catalog.complexity_score("foo"), more_columns);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_min_retention_period() {
+        let hour = Duration::from_secs(60 * 60);
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_min_retention_period(hour),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        let err = catalog
+            .set_retention_period_for_database("foo", Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::RetentionPeriodTooShort { .. }));
+        assert_contains!(err.to_string(), "10s");
+        assert_contains!(err.to_string(), "1h");
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Indefinite
+        );
+        assert!(matches!(
+            catalog
+                .set_retention_period_for_table("foo", "cpu", Duration::from_secs(10))
+                .await,
+            Err(CatalogError::RetentionPeriodTooShort { .. })
+        ));
+
+        // the minimum itself is allowed, as is clearing the retention period:
+        catalog
+            .set_retention_period_for_database("foo", hour)
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.db_schema("foo").unwrap().retention_period,
+            RetentionPeriod::Duration(hour)
+        );
+        catalog
+            .clear_retention_period_for_database("foo")
+            .await
+            .unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_cache_coverage() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for table in ["both", "last", "distinct", "none"] {
+            catalog
+                .create_table("foo", table, &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        for table in ["both", "last"] {
+            catalog
+                .create_last_cache(
+                    "foo",
+                    table,
+                    None,
+                    Some(&["host"]),
+                    Some(&["usage"]),
+                    LastCacheSize::new(1).unwrap(),
+                    LastCacheTtl::from_secs(600),
+                )
+                .await
+                .unwrap();
+        }
+        for table in ["both", "distinct"] {
+            catalog
+                .create_distinct_cache(
+                    "foo",
+                    table,
+                    None,
+                    &["host"],
+                    MaxCardinality::from_usize_unchecked(100),
+                    MaxAge::from_secs(10),
+                )
+                .await
+                .unwrap();
+        }
+
+        let coverage = catalog.cache_coverage("foo");
+        assert_eq!(coverage.both, [Arc::from("both")]);
+        assert_eq!(coverage.last_only, [Arc::from("last")]);
+        assert_eq!(coverage.distinct_only, [Arc::from("distinct")]);
+        assert_eq!(coverage.neither, [Arc::from("none")]);
+
+        assert_eq!(catalog.cache_coverage("bar"), CacheCoverage::default());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_rename_token() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        let permissions = vec![Permission {
+            resource_type: ResourceType::Database,
+            resource_identifier: ResourceIdentifier::Database(vec![
+                catalog.db_name_to_id("foo").unwrap(),
+            ]),
+            actions: Actions::Database(DatabaseActions(2)),
+        }];
+        let (token_info, token) = catalog
+            .create_scoped_token("reader".to_string(), permissions.clone(), None)
+            .await
+            .unwrap();
+        catalog
+            .create_scoped_token("writer".to_string(), vec![], None)
+            .await
+            .unwrap();
+
+        catalog.rename_token("reader", "foo-reader").await.unwrap();
+        let tokens = catalog.inner.read().tokens.repo().clone();
+        assert!(!tokens.contains_name("reader"));
+        let renamed = tokens.get_by_name("foo-reader").unwrap();
+        assert_eq!(renamed.id, token_info.id);
+        assert_eq!(renamed.permissions, permissions);
+        // the secret still resolves to the token under its new name:
+        let by_secret = catalog
+            .get_token_checked(Sha512::digest(&token).to_vec())
+            .unwrap();
+        assert_eq!(by_secret.name.as_ref(), "foo-reader");
+
+        assert!(matches!(
+            catalog.rename_token("foo-reader", "writer").await,
+            Err(CatalogError::TokenNameAlreadyExists(_))
+        ));
+        assert!(matches!(
+            catalog.rename_token("reader", "other").await,
+            Err(CatalogError::NotFound)
+        ));
+        assert!(matches!(
+            catalog
+                .rename_token(DEFAULT_OPERATOR_TOKEN_NAME, "other")
+                .await,
+            Err(CatalogError::CannotModifyOperatorToken)
+        ));
+        // the writer token was not affected by the failed rename:
+        assert!(catalog.inner.read().tokens.repo().contains_name("writer"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_create_tables() {
+        let obj_store = Arc::new(RequestCountedObjectStore::new(Arc::new(InMemory::new())));
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new(
+            "test",
+            Arc::clone(&obj_store) as _,
+            Arc::clone(&time_provider) as _,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+
+        // creating tables one at a time writes a catalog file for each table:
+        let writes_before = obj_store.total_write_request_count();
+        for i in 0..5 {
+            catalog
+                .create_table(
+                    "foo",
+                    &format!("single_{i}"),
+                    &["t1"],
+                    &[("f1", FieldDataType::Float)],
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(5, obj_store.total_write_request_count() - writes_before);
+
+        // creating them in bulk writes a single catalog file:
+        let writes_before = obj_store.total_write_request_count();
+        let sequence_before = catalog.sequence_number();
+        let specs = (0..5)
+            .map(|i| {
+                TableSpec::new(
+                    format!("bulk_{i}"),
+                    &["t1"],
+                    &[("f1", FieldDataType::Float)],
+                )
+            })
+            .collect::<Vec<_>>();
+        catalog.create_tables("foo", specs).await.unwrap();
+        assert_eq!(1, obj_store.total_write_request_count() - writes_before);
+        assert_eq!(sequence_before.next(), catalog.sequence_number());
+        let db = catalog.db_schema("foo").unwrap();
+        for i in 0..5 {
+            let table = db.table_definition(format!("bulk_{i}")).unwrap();
+            assert_eq!(table.series_key_names(), [Arc::from("t1")]);
+        }
+
+        // none of the tables are created if one of them cannot be:
+        let specs = vec![
+            TableSpec::new("new_table", &["t1"], &[("f1", FieldDataType::Float)]),
+            TableSpec::new("bulk_0", &["t1"], &[("f1", FieldDataType::Float)]),
+        ];
+        assert!(matches!(
+            catalog.create_tables("foo", specs).await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("new_table")
+                .is_none()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_validate_batch() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+
+        let mut txn = catalog.begin("foo").unwrap();
+        txn.create_table("cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .unwrap();
+        let batch: CatalogBatch = txn.into();
+        let sequence = catalog.sequence_number();
+        let next_table_id = catalog.db_schema("foo").unwrap().tables.next_id();
+
+        catalog.validate_batch(&batch).unwrap();
+        // the live catalog is not changed:
+        let db = catalog.db_schema("foo").unwrap();
+        assert!(db.table_definition("cpu").is_none());
+        assert_eq!(next_table_id, db.tables.next_id());
+        assert_eq!(sequence, catalog.sequence_number());
+
+        // once the table exists with a different type for the field, the batch is invalid:
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[("usage", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog.validate_batch(&batch),
+            Err(CatalogError::FieldTypeMismatch { .. })
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_export_policies_yaml() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .set_retention_period_for_database("foo", Duration::from_secs(7 * 24 * 60 * 60))
+            .await
+            .unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .soft_delete_database(
+                "bar",
+                HardDeletionTime::Timestamp(Time::from_timestamp_nanos(0)),
+            )
+            .await
+            .unwrap();
+
+        let policies = catalog.policies();
+        assert_eq!(2, policies.databases.len());
+        assert_eq!(policies.databases[0].name.as_ref(), "foo");
+        assert_eq!(
+            policies.databases[0].retention_period.as_deref(),
+            Some("7days")
+        );
+        assert!(policies.databases[1].deleted);
+        assert!(policies.databases[1].hard_delete_time.is_some());
+
+        let yaml = catalog.export_policies_yaml().unwrap();
+        assert_contains!(&yaml, "name: foo");
+        assert_contains!(&yaml, "retention_period: 7days");
+        assert_contains!(&yaml, "deleted: true");
+        assert!(!yaml.contains(INTERNAL_DB_NAME));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_table_name_occurrences() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        for db in ["foo", "bar", "baz"] {
+            catalog.create_database(db).await.unwrap();
+        }
+        for db in ["foo", "bar"] {
+            catalog
+                .create_table(db, "cpu", &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        catalog
+            .create_table("baz", "mem", &["host"], &[("used", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        let table_id = |db: &str| {
+            let db_schema = catalog.db_schema(db).unwrap();
+            (db_schema.id, db_schema.table_name_to_id("cpu").unwrap())
+        };
+        assert_eq!(
+            catalog.table_name_occurrences("cpu"),
+            [table_id("foo"), table_id("bar")]
+        );
+        assert!(catalog.table_name_occurrences("disk").is_empty());
+
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert_eq!(catalog.table_name_occurrences("cpu"), [table_id("foo")]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_reorder_series_key() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host", "cpu"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        let column_id = |name: &str| {
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .column_name_to_id(name)
+                .unwrap()
+        };
+        let (region, host, cpu, usage) = (
+            column_id("region"),
+            column_id("host"),
+            column_id("cpu"),
+            column_id("usage"),
+        );
+
+        catalog
+            .reorder_series_key("foo", "cpu", vec![host, cpu, region])
+            .await
+            .unwrap();
+        let check = |catalog: &Catalog| {
+            let table = catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap();
+            assert_eq!(table.series_key, [host, cpu, region]);
+            assert_eq!(
+                table.series_key_names,
+                [Arc::from("host"), Arc::from("cpu"), Arc::from("region")]
+            );
+            assert_eq!(
+                table.sort_key,
+                SortKey::from_columns(["host", "cpu", "region", "time"])
+            );
+            assert_eq!(
+                table.schema.primary_key(),
+                &["host", "cpu", "region", TIME_COLUMN_NAME]
+            );
+        };
+        check(&catalog);
+
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.update_from_snapshot(snapshot);
+        check(&catalog);
+
+        // the new order must contain exactly the columns in the series key:
+        for new_order in [
+            vec![host, cpu],
+            vec![host, cpu, region, usage],
+            vec![host, cpu, cpu],
+        ] {
+            assert!(matches!(
+                catalog.reorder_series_key("foo", "cpu", new_order).await,
+                Err(CatalogError::InvalidConfiguration { .. })
+            ));
+        }
+        assert!(matches!(
+            catalog
+                .reorder_series_key("foo", "cpu", vec![host, cpu, region])
+                .await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        check(&catalog);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_set_column_description() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let table = |catalog: &Catalog| {
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+        };
+        let schema_before = table(&catalog).schema.clone();
+        catalog
+            .set_column_description("foo", "cpu", "usage", Some("percent of CPU time in use"))
+            .await
+            .unwrap();
+        assert_eq!(
+            table(&catalog)
+                .column_definition("usage")
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("percent of CPU time in use")
+        );
+        assert_eq!(schema_before, table(&catalog).schema);
+
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test").await.unwrap();
+        new_catalog.update_from_snapshot(snapshot);
+        let table_def = table(&new_catalog);
+        assert_eq!(
+            table_def
+                .column_definition("usage")
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("percent of CPU time in use")
+        );
+        assert!(
+            table_def
+                .column_definition("host")
+                .unwrap()
+                .description
+                .is_none()
+        );
+
+        catalog
+            .set_column_description("foo", "cpu", "usage", None)
+            .await
+            .unwrap();
+        assert!(
+            table(&catalog)
+                .column_definition("usage")
+                .unwrap()
+                .description
+                .is_none()
+        );
+        assert!(matches!(
+            catalog
+                .set_column_description("foo", "cpu", "missing", Some("text"))
+                .await,
+            Err(CatalogError::ColumnNotFound { .. })
+        ));
+        assert!(matches!(
+            catalog
+                .set_column_description("foo", "cpu", "usage", None)
+                .await,
+            Err(CatalogError::AlreadyExists)
+        ));
+
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog
+                .set_column_description("foo", "cpu", "usage", Some("text"))
+                .await,
+            Err(CatalogError::TableNotFound { .. })
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_restore_from_snapshot_checks_catalog_id() {
+        let source = Catalog::new_in_memory("cluster-a").await.unwrap();
+        source.create_database("foo").await.unwrap();
+        let snapshot = || {
+            let serialized = serialize_catalog_file(&source.snapshot()).unwrap();
+            verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap()
+        };
+
+        // matching catalog ids:
+        let same = Catalog::new_in_memory("cluster-a").await.unwrap();
+        same.restore_from_snapshot(snapshot(), false).unwrap();
+        assert!(same.db_schema("foo").is_some());
+
+        // mismatched catalog ids:
+        let other = Catalog::new_in_memory("cluster-b").await.unwrap();
+        assert_contains!(
+            other
+                .restore_from_snapshot(snapshot(), false)
+                .unwrap_err()
+                .to_string(),
+            "does not match this catalog 'cluster-b'"
+        );
+        assert!(other.db_schema("foo").is_none());
+
+        // forcing the restore of a mismatched snapshot:
+        other.restore_from_snapshot(snapshot(), true).unwrap();
+        assert!(other.db_schema("foo").is_some());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_all_influxql_retention_policies() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .set_retention_period_for_database("foo", Duration::from_secs(7 * 24 * 60 * 60))
+            .await
+            .unwrap();
+        catalog.create_database("bar/one_week").await.unwrap();
+        catalog
+            .set_retention_period_for_database(
+                "bar/one_week",
+                Duration::from_secs(7 * 24 * 60 * 60),
+            )
+            .await
+            .unwrap();
+        catalog.create_database("bar/forever").await.unwrap();
+        catalog.create_database("gone").await.unwrap();
+        catalog
+            .soft_delete_database("gone", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        let rp = |db: &str, name: &str, duration: Option<u64>| {
+            (
+                Arc::<str>::from(db),
+                InfluxqlRp {
+                    name: Arc::from(name),
+                    duration: duration.map(Duration::from_secs),
+                },
+            )
+        };
+        let policies = catalog
+            .all_influxql_retention_policies()
+            .into_iter()
+            .filter(|(db, _)| db.as_ref() != INTERNAL_DB_NAME)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            policies,
+            [
+                rp("bar", "forever", None),
+                rp("bar", "one_week", Some(7 * 24 * 60 * 60)),
+                rp("foo", AUTOGEN_RETENTION_POLICY, Some(7 * 24 * 60 * 60)),
+            ]
+        );
+
+        assert_eq!(
+            split_influxql_database_name("foo"),
+            ("foo", AUTOGEN_RETENTION_POLICY)
+        );
+        assert_eq!(split_influxql_database_name("foo/bar"), ("foo", "bar"));
+        assert_eq!(
+            split_influxql_database_name("foo/bar/baz"),
+            ("foo", "bar/baz")
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_table_exists_by_name() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        for table in ["cpu", "boo"] {
+            catalog
+                .create_table("foo", table, &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        catalog
+            .soft_delete_table("foo", "boo", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        let deleted_name = catalog
+            .db_schema("foo")
+            .unwrap()
+            .tables()
+            .find(|t| t.deleted)
+            .map(|t| Arc::clone(&t.table_name))
+            .unwrap();
+        assert_eq!(deleted_name.as_ref(), "boo-19700101T000000");
+
+        // live table:
+        assert!(catalog.table_exists_by_name("foo", "cpu"));
+        // soft-deleted table, under its original and its deleted name:
+        assert!(!catalog.table_exists_by_name("foo", "boo"));
+        assert!(!catalog.table_exists_by_name("foo", &deleted_name));
+        // nonexistent table and database:
+        assert!(!catalog.table_exists_by_name("foo", "mem"));
+        assert!(!catalog.table_exists_by_name("bar", "cpu"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_run_hard_delete_sweep() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test-catalog",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        for db in ["foo", "bar", "baz"] {
+            catalog.create_database(db).await.unwrap();
+            for table in ["cpu", "mem"] {
+                catalog
+                    .create_table(db, table, &["host"], &[("usage", FieldDataType::Float)])
+                    .await
+                    .unwrap();
+            }
+        }
+        let db_id = |name: &str| catalog.db_name_to_id(name).unwrap();
+        let (foo_id, bar_id, baz_id) = (db_id("foo"), db_id("bar"), db_id("baz"));
+        let foo_cpu_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+        let at = |secs| HardDeletionTime::Timestamp(Time::from_timestamp(secs, 0).unwrap());
+        catalog
+            .soft_delete_table("foo", "cpu", at(10))
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("foo", "mem", at(100))
+            .await
+            .unwrap();
+        catalog.soft_delete_database("bar", at(10)).await.unwrap();
+        catalog
+            .soft_delete_database("baz", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        // nothing is due yet:
+        assert!(catalog.run_hard_delete_sweep().await.unwrap().is_empty());
+
+        time_provider.set(Time::from_timestamp(10, 0).unwrap());
+        let sequence = catalog.sequence_number();
+        assert_eq!(
+            catalog.run_hard_delete_sweep().await.unwrap(),
+            [(bar_id, None), (foo_id, Some(foo_cpu_id))]
+        );
+        // everything was removed in a single batch:
+        assert_eq!(sequence.next(), catalog.sequence_number());
+        assert!(catalog.db_schema_by_id(&bar_id).is_none());
+        let foo = catalog.db_schema_by_id(&foo_id).unwrap();
+        assert!(foo.table_definition_by_id(&foo_cpu_id).is_none());
+        assert_eq!(foo.tables.len(), 1);
+        assert!(catalog.db_schema_by_id(&baz_id).is_some());
+
+        // running the sweep again does nothing:
+        assert!(catalog.run_hard_delete_sweep().await.unwrap().is_empty());
+        assert_eq!(sequence.next(), catalog.sequence_number());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_column_name_rules() {
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default().with_column_name_rules(ColumnNameRules {
+                min_length: 3,
+                allow_leading_digit: false,
+            }),
+        )
+        .await
+        .unwrap();
+        catalog.create_database("foo").await.unwrap();
+
+        // valid names pass, and the time column is exempt:
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let mut txn = catalog.begin("foo").unwrap();
+        txn.column_or_create("cpu", "region", FieldDataType::Tag)
+            .unwrap();
+        catalog.commit(txn).await.unwrap();
+
+        // too short:
+        assert!(matches!(
+            catalog
+                .create_table("foo", "mem", &["id"], &[("used", FieldDataType::Float)])
+                .await,
+            Err(CatalogError::InvalidColumnName { .. })
+        ));
+        // invalid characters:
+        let mut txn = catalog.begin("foo").unwrap();
+        for name in ["bad\u{7}name", "2xx_count"] {
+            assert!(matches!(
+                txn.column_or_create("cpu", name, FieldDataType::Integer),
+                Err(CatalogError::InvalidColumnName { .. })
+            ));
+        }
+        assert!(
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("mem")
+                .is_none()
+        );
+
+        // without rules the same names are allowed:
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "mem",
+                &["id"],
+                &[("2xx_count", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_partition_template_columns() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["region", "host", "cpu"],
+                &[("usage", FieldDataType::Float)],
+            )
+            .await
+            .unwrap();
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+
+        let time_id = table.column_name_to_id("time").unwrap();
+        let expected: Vec<ColumnId> = table
+            .series_key_ids()
+            .iter()
+            .copied()
+            .filter(|id| *id != time_id)
+            .collect();
+        let columns = table.partition_template_columns();
+        assert_eq!(expected, columns);
+
+        // tags in series key order, without time or fields:
+        let names: Vec<Arc<str>> = columns
+            .iter()
+            .map(|id| table.column_id_to_name(id).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![Arc::from("region"), Arc::from("host"), Arc::from("cpu")]
+        );
+        let usage_id = table.column_name_to_id("usage").unwrap();
+        assert!(!columns.contains(&usage_id));
+        assert!(!columns.contains(&time_id));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_set_checkpoint_interval() {
+        let obj_store = Arc::new(RequestCountedObjectStore::new(Arc::new(InMemory::new())));
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new(
+            "test",
+            Arc::clone(&obj_store) as _,
+            Arc::clone(&time_provider) as _,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(CATALOG_CHECKPOINT_INTERVAL, catalog.checkpoint_interval());
+        let checkpoint_path = CatalogFilePath::checkpoint(catalog.object_store_prefix().as_ref());
+        let checkpoint_puts = || obj_store.put_request_count(checkpoint_path.as_ref());
+
+        catalog.create_database("foo").await.unwrap();
+        let create_tables = async |range: std::ops::Range<usize>| {
+            for i in range {
+                catalog
+                    .create_table(
+                        "foo",
+                        format!("table_{i}").as_str(),
+                        &["t1"],
+                        &[("f1", FieldDataType::String)],
+                    )
+                    .await
+                    .unwrap();
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        };
+
+        // nowhere near the default interval, so no checkpoint is written in the background:
+        let before = checkpoint_puts();
+        create_tables(0..4).await;
+        assert_eq!(before, checkpoint_puts());
+
+        // zero is rejected and leaves the interval unchanged:
+        assert_contains!(
+            catalog.set_checkpoint_interval(0).unwrap_err().to_string(),
+            "checkpoint interval must be greater than 0"
+        );
+        assert_eq!(CATALOG_CHECKPOINT_INTERVAL, catalog.checkpoint_interval());
+
+        // checkpoint on every batch from now on:
+        catalog.set_checkpoint_interval(1).unwrap();
+        assert_eq!(1, catalog.checkpoint_interval());
+        create_tables(4..7).await;
+        assert_eq!(before + 3, checkpoint_puts());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_catalog_error_codes() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        let err = catalog.create_database("foo").await.unwrap_err();
+        assert!(matches!(err, CatalogError::AlreadyExists));
+        assert_eq!("catalog/already_exists", err.code());
+
+        let err = CatalogError::TooManyTables(2000);
+        assert_eq!("catalog/too_many_tables", err.code());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_provision() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let last_cache = ProvisionLastCacheSpec {
+            cache_name: None,
+            key_columns: None,
+            value_columns: None,
+            count: LastCacheSize::default(),
+            ttl: LastCacheTtl::default(),
+        };
+        let spec = TenantProvisionSpec {
+            database_name: "tenant".to_string(),
+            tables: vec![
+                ProvisionTableSpec {
+                    table: TableSpec::new("cpu", &["host"], &[("usage", FieldDataType::Float)]),
+                    last_caches: vec![last_cache.clone()],
+                    distinct_caches: vec![],
+                },
+                ProvisionTableSpec {
+                    table: TableSpec::new(
+                        "mem",
+                        &["host", "region"],
+                        &[("free", FieldDataType::Integer)],
+                    ),
+                    last_caches: vec![ProvisionLastCacheSpec {
+                        cache_name: Some("mem_cache".to_string()),
+                        key_columns: Some(vec!["region".to_string()]),
+                        ..last_cache.clone()
+                    }],
+                    distinct_caches: vec![ProvisionDistinctCacheSpec {
+                        cache_name: None,
+                        columns: vec!["region".to_string(), "host".to_string()],
+                        max_cardinality: MaxCardinality::default(),
+                        max_age_seconds: MaxAge::default(),
+                    }],
+                },
+            ],
+        };
+        // the spec can be accepted as JSON:
+        let spec: TenantProvisionSpec =
+            serde_json::from_str(&serde_json::to_string(&spec).unwrap()).unwrap();
+
+        let sequence = catalog.sequence_number();
+        let db_id = catalog.provision(spec.clone()).await.unwrap();
+        // everything was created in a single batch:
+        assert_eq!(sequence.next(), catalog.sequence_number());
+        let db = catalog.db_schema_by_id(&db_id).unwrap();
+        assert_eq!("tenant", db.name().as_ref());
+        let cpu = db.table_definition("cpu").unwrap();
+        assert!(cpu.last_caches.contains_name("cpu_host_last_cache"));
+        let mem = db.table_definition("mem").unwrap();
+        let mem_cache = mem.last_caches.get_by_name("mem_cache").unwrap();
+        assert_eq!(
+            vec![mem.column_name_to_id("region").unwrap()],
+            mem_cache.key_columns
+        );
+        assert!(
+            mem.distinct_caches
+                .contains_name("mem_region_host_distinct_cache")
+        );
+
+        // provisioning the same database again fails:
+        assert!(matches!(
+            catalog.provision(spec).await.unwrap_err(),
+            CatalogError::AlreadyExists
+        ));
+
+        // an invalid cache on the second table fails the whole spec, and commits nothing:
+        let sequence = catalog.sequence_number();
+        let err = catalog
+            .provision(TenantProvisionSpec {
+                database_name: "broken".to_string(),
+                tables: vec![
+                    ProvisionTableSpec {
+                        table: TableSpec::new("cpu", &["host"], &[("usage", FieldDataType::Float)]),
+                        last_caches: vec![last_cache.clone()],
+                        distinct_caches: vec![],
+                    },
+                    ProvisionTableSpec {
+                        table: TableSpec::new("mem", &["host"], &[("free", FieldDataType::Float)]),
+                        last_caches: vec![ProvisionLastCacheSpec {
+                            key_columns: Some(vec!["free".to_string()]),
+                            ..last_cache
+                        }],
+                        distinct_caches: vec![],
+                    },
+                ],
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::InvalidLastCacheKeyColumnType));
+        assert_eq!(sequence, catalog.sequence_number());
+        assert!(catalog.db_schema("broken").is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_hard_delete_schedule() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        assert!(catalog.hard_delete_schedule().is_empty());
+
+        for db in ["foo", "bar", "baz", "qux"] {
+            catalog.create_database(db).await.unwrap();
+        }
+        for table in ["cpu", "mem"] {
+            catalog
+                .create_table("foo", table, &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        let baz_id = catalog.db_name_to_id("baz").unwrap();
+        let cpu_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        let time = Time::from_timestamp_nanos;
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Timestamp(time(5_000_000_000)))
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("baz", HardDeletionTime::Timestamp(time(2_000_000_000)))
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("qux", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table(
+                "foo",
+                "cpu",
+                HardDeletionTime::Timestamp(time(3_000_000_000)),
+            )
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("foo", "mem", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        let schedule = catalog.hard_delete_schedule();
+        assert_eq!(
+            schedule
+                .iter()
+                .map(|d| (d.target, d.hard_delete_time))
+                .collect::<Vec<_>>(),
+            vec![
+                (DeletionTarget::Database(baz_id), time(2_000_000_000)),
+                (
+                    DeletionTarget::Table {
+                        db_id: foo_id,
+                        table_id: cpu_id
+                    },
+                    time(3_000_000_000)
+                ),
+                (DeletionTarget::Database(bar_id), time(5_000_000_000)),
+            ]
+        );
+        assert_eq!(schedule[0].name.as_ref(), "baz-19700101T000000");
+        assert_eq!(schedule[1].name.as_ref(), "cpu-19700101T000000");
+        assert_eq!(schedule[2].name.as_ref(), "bar-19700101T000000");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_alter_column_type() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "cpu",
+                &["host"],
+                &[
+                    ("count", FieldDataType::Integer),
+                    ("ok", FieldDataType::Boolean),
+                    ("usage", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        let column_type = |catalog: &Catalog, column: &str| {
+            catalog
+                .db_schema("foo")
+                .unwrap()
+                .table_definition("cpu")
+                .unwrap()
+                .column_definition(column)
+                .unwrap()
+                .data_type
+        };
+
+        // every change needs to be forced:
+        let err = catalog
+            .alter_column_type("foo", "cpu", "count", FieldDataType::Float, false)
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "requires force");
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::Integer),
+            column_type(&catalog, "count")
+        );
+        catalog
+            .alter_column_type("foo", "cpu", "count", FieldDataType::Float, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::Float),
+            column_type(&catalog, "count")
+        );
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition("cpu")
+            .unwrap();
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::Float),
+            table
+                .schema
+                .field(table.schema.find_index_of("count").unwrap())
+                .0
+        );
+
+        let err = catalog
+            .alter_column_type("foo", "cpu", "ok", FieldDataType::String, false)
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "requires force");
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::Boolean),
+            column_type(&catalog, "ok")
+        );
+        catalog
+            .alter_column_type("foo", "cpu", "ok", FieldDataType::String, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::String),
+            column_type(&catalog, "ok")
+        );
+
+        // narrowing, and changing tags, are not permitted, even when forced:
+        for (column, new_type) in [
+            ("usage", FieldDataType::Integer),
+            ("ok", FieldDataType::Boolean),
+            ("host", FieldDataType::String),
+        ] {
+            assert!(matches!(
+                catalog
+                    .alter_column_type("foo", "cpu", column, new_type, true)
+                    .await
+                    .unwrap_err(),
+                CatalogError::IncompatibleColumnTypeChange { .. }
+            ));
+        }
+        // the column already has the type:
+        assert!(matches!(
+            catalog
+                .alter_column_type("foo", "cpu", "usage", FieldDataType::Float, false)
+                .await
+                .unwrap_err(),
+            CatalogError::AlreadyExists
+        ));
+
+        // last cache key columns cannot be changed, as the cached key values are not converted:
+        catalog
+            .create_table("foo", "mem", &["host"], &[("code", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_last_cache(
+                "foo",
+                "mem",
+                None,
+                Some(&["code"]),
+                None::<&[&str]>,
+                LastCacheSize::default(),
+                LastCacheTtl::default(),
+            )
+            .await
+            .unwrap();
+        let err = catalog
+            .alter_column_type("foo", "mem", "code", FieldDataType::String, true)
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "key column of the last cache");
+
+        // the changes survive a snapshot round trip:
+        let snapshot = catalog.snapshot();
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let new_catalog = Catalog::new_in_memory("test").await.unwrap();
+        new_catalog.update_from_snapshot(snapshot);
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::Float),
+            column_type(&new_catalog, "count")
+        );
+        assert_eq!(
+            InfluxColumnType::Field(InfluxFieldType::String),
+            column_type(&new_catalog, "ok")
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_get_database_stats() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        assert!(catalog.get_database_stats("foo").is_none());
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "mem",
+                &["host", "region"],
+                &[("free", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "gone", &["host"], &[("val", FieldDataType::Float)])
+            .await
+            .unwrap();
+        for table in ["cpu", "mem", "gone"] {
+            catalog
+                .create_last_cache(
+                    "foo",
+                    table,
+                    None,
+                    Some(&["host"]),
+                    None::<&[&str]>,
+                    LastCacheSize::default(),
+                    LastCacheTtl::default(),
+                )
+                .await
+                .unwrap();
+        }
+        catalog
+            .create_distinct_cache(
+                "foo",
+                "mem",
+                None,
+                &["host", "region"],
+                MaxCardinality::default(),
+                MaxAge::default(),
+            )
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("foo", "gone", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .set_retention_period_for_database("foo", Duration::from_secs(3600))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            catalog.get_database_stats("foo").unwrap(),
+            DatabaseStats {
+                table_count: 2,
+                // cpu has host, usage, and time; mem has host, region, free, and time:
+                column_count: 7,
+                last_cache_count: 2,
+                distinct_cache_count: 1,
+                trigger_count: 0,
+                retention_period: RetentionPeriod::Duration(Duration::from_secs(3600)),
+                deleted: false,
+            }
+        );
+
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        catalog
+            .soft_delete_database("foo", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        let deleted_name = catalog.db_schema_by_id(&db_id).unwrap().name();
+        assert!(catalog.get_database_stats(&deleted_name).unwrap().deleted);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_token_effective_access() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        for db in ["foo", "bar", "baz"] {
+            catalog.create_database(db).await.unwrap();
+        }
+        let db_id = |name: &str| catalog.db_name_to_id(name).unwrap();
+        let db_permission = |db_ids: Vec<DbId>, actions: u16| Permission {
+            resource_type: ResourceType::Database,
+            resource_identifier: ResourceIdentifier::Database(db_ids),
+            actions: Actions::Database(DatabaseActions(actions)),
+        };
+        let (read, write) = (1, 2);
+        let (scoped, _) = catalog
+            .create_scoped_token(
+                "scoped".to_string(),
+                vec![
+                    db_permission(vec![db_id("foo"), db_id("bar")], read),
+                    db_permission(vec![db_id("bar")], write),
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+        let access = |actions: &[&'static str], name: &str| ResourceAccess {
+            name: Arc::from(name),
+            actions: actions.to_vec(),
+        };
+        assert_eq!(
+            catalog.token_effective_access(&scoped.id).unwrap(),
+            TokenAccessView::Scoped {
+                databases: vec![access(&["read", "write"], "bar"), access(&["read"], "foo"),],
+                tokens: vec![],
+            }
+        );
+
+        // a wildcard database permission covers all current databases:
+        let (all_dbs, _) = catalog
+            .create_scoped_token(
+                "all_dbs".to_string(),
+                vec![Permission {
+                    resource_type: ResourceType::Database,
+                    resource_identifier: ResourceIdentifier::Wildcard,
+                    actions: Actions::Database(DatabaseActions(read)),
+                }],
+                None,
+            )
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("baz", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        let mut expected = catalog
+            .list_db_schema()
+            .into_iter()
+            .filter(|db| !db.deleted)
+            .map(|db| access(&["read"], &db.name))
+            .collect::<Vec<_>>();
+        expected.sort_by(|a, b| a.name.cmp(&b.name));
+        assert!(expected.iter().all(|a| a.name.as_ref() != "baz"));
+        assert_eq!(
+            catalog.token_effective_access(&all_dbs.id).unwrap(),
+            TokenAccessView::Scoped {
+                databases: expected,
+                tokens: vec![],
+            }
+        );
+
+        // the admin token has access to everything:
+        let (admin, _) = catalog.create_admin_token(false).await.unwrap();
+        assert_eq!(
+            catalog.token_effective_access(&admin.id).unwrap(),
+            TokenAccessView::AllResources
+        );
+        assert!(
+            catalog
+                .token_effective_access(&TokenId::from(1000))
+                .is_none()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_tables_over_current_limits() {
+        let obj_store = Arc::new(InMemory::new());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let init = async |args: CatalogArgs| {
+            Catalog::new_with_args(
+                "test",
+                Arc::clone(&obj_store) as _,
+                Arc::clone(&time_provider) as _,
+                Default::default(),
+                args,
+            )
+            .await
+            .unwrap()
+        };
+
+        let catalog = init(CatalogArgs::default()).await;
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table(
+                "foo",
+                "wide",
+                &["host", "region"],
+                &[
+                    ("a", FieldDataType::Float),
+                    ("b", FieldDataType::Float),
+                    ("c", FieldDataType::Float),
+                    ("d", FieldDataType::Float),
+                ],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "narrow", &["host"], &[("a", FieldDataType::Float)])
+            .await
+            .unwrap();
+        assert!(catalog.tables_over_current_limits().is_empty());
+        drop(catalog);
+
+        // reload the catalog with a lower limit, which the wide table, with 7 columns including
+        // time, exceeds:
+        let catalog = init(CatalogArgs::default().with_num_columns_per_table_limit(5)).await;
+        let db = catalog.db_schema("foo").unwrap();
+        assert_eq!(
+            catalog.tables_over_current_limits(),
+            vec![LimitViolation {
+                db_id: db.id,
+                db_name: Arc::clone(&db.name),
+                table_id: db.table_name_to_id("wide").unwrap(),
+                table_name: Arc::from("wide"),
+                kind: LimitKind::ColumnsPerTable,
+                count: 7,
+                limit: 5,
+            }]
+        );
+
+        // the wide table is left as is, but cannot have more columns added:
+        let mut txn = catalog.begin("foo").unwrap();
+        assert!(matches!(
+            txn.column_or_create("wide", "e", FieldDataType::Float)
+                .unwrap_err(),
+            CatalogError::TooManyColumns(5)
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_read_only_catalog() {
+        let obj_store = Arc::new(InMemory::new());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let init = async |args: CatalogArgs| {
+            Catalog::new_with_args(
+                "test",
+                Arc::clone(&obj_store) as _,
+                Arc::clone(&time_provider) as _,
+                Default::default(),
+                args,
+            )
+            .await
+        };
+
+        // there is no catalog on object store for a replica to load yet:
+        let err = init(CatalogArgs::default().with_read_only(true))
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "read-only");
+
+        let writer = init(CatalogArgs::default()).await.unwrap();
+        writer.create_database("foo").await.unwrap();
+        writer
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+
+        let replica = init(CatalogArgs::default().with_read_only(true))
+            .await
+            .unwrap();
+        assert!(replica.is_read_only());
+        assert!(!writer.is_read_only());
+        assert!(replica.db_schema("foo").is_some());
+        assert!(replica.db_schema(INTERNAL_DB_NAME).is_some());
+
+        let sequence = replica.sequence_number();
+        assert!(matches!(
+            replica.create_database("bar").await,
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+        assert!(matches!(
+            replica
+                .create_table("foo", "mem", &["host"], &[("used", FieldDataType::Integer)])
+                .await,
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+        assert!(matches!(
+            replica.begin("bar"),
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+        assert!(matches!(
+            replica.force_checkpoint(None).await,
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+        // nothing was applied, and the database ids were not used up:
+        assert_eq!(sequence, replica.sequence_number());
+        assert_eq!(writer.next_db_id(), replica.next_db_id());
+
+        // the replica can still follow updates made by the writer:
+        writer.create_database("bar").await.unwrap();
+        replica
+            .update_to_sequence_number(writer.sequence_number())
+            .await
+            .unwrap();
+        assert!(replica.db_schema("bar").is_some());
+
+        // shutting down does not make the replica writable:
+        replica.set_state_shutdown();
+        assert!(replica.is_read_only());
+        assert!(matches!(
+            replica.create_database("baz").await,
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+        assert!(matches!(
+            replica.begin("baz"),
+            Err(CatalogError::ReadOnlyCatalog)
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_find_table_by_id() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_table("bar", "mem", &["host"], &[("used", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "bar",
+                "disk",
+                &["host"],
+                &[("free", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+
+        // table ids are assigned per database, so the first table in each has the same id:
+        let cpu_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+        let (db, table) = catalog.find_table_by_id(cpu_id).unwrap();
+        assert_eq!("foo", db.name.as_ref());
+        assert_eq!("cpu", table.table_name.as_ref());
+        let matches: Vec<(Arc<str>, Arc<str>)> = catalog
+            .find_tables_by_id(cpu_id)
+            .into_iter()
+            .map(|(db, table)| (db.name(), Arc::clone(&table.table_name)))
+            .collect();
+        assert_eq!(
+            vec![("foo".into(), "cpu".into()), ("bar".into(), "mem".into())],
+            matches
+        );
+
+        // the second table in "bar" is only found there:
+        let disk_id = catalog
+            .db_schema("bar")
+            .unwrap()
+            .table_name_to_id("disk")
+            .unwrap();
+        let (db, table) = catalog.find_table_by_id(disk_id).unwrap();
+        assert_eq!("bar", db.name.as_ref());
+        assert_eq!("disk", table.table_name.as_ref());
+        assert_eq!(1, catalog.find_tables_by_id(disk_id).len());
+
+        assert!(catalog.find_table_by_id(TableId::from(100)).is_none());
+        assert!(catalog.find_tables_by_id(TableId::from(100)).is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_create_database_with_tables() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        let sequence_before = catalog.sequence_number();
+        let specs = vec![
+            TableSpec::new("cpu", &["host"], &[("usage", FieldDataType::Float)]),
+            TableSpec::new("mem", &["host"], &[("used", FieldDataType::Integer)]),
+        ];
+        catalog
+            .create_database_with_tables(
+                "foo",
+                CreateDatabaseOptions {
+                    retention_period: Some(Duration::from_secs(3600)),
+                },
+                specs.clone(),
+            )
+            .await
+            .unwrap();
+        // the database and its tables are created in one batch:
+        assert_eq!(sequence_before.next(), catalog.sequence_number());
+        let db = catalog.db_schema("foo").unwrap();
+        assert_eq!(
+            RetentionPeriod::Duration(Duration::from_secs(3600)),
+            db.retention_period
+        );
+        assert_eq!(2, db.table_count());
+        assert!(db.table_definition("cpu").is_some());
+        assert!(db.table_definition("mem").is_some());
+
+        // the database cannot be created again:
+        assert!(matches!(
+            catalog
+                .create_database_with_tables("foo", Default::default(), specs)
+                .await,
+            Err(CatalogError::AlreadyExists)
+        ));
+
+        // if a table is invalid, neither the database nor any of its tables are created:
+        let sequence_before = catalog.sequence_number();
+        let fields = (0..Catalog::NUM_COLUMNS_PER_TABLE_LIMIT)
+            .map(|i| (format!("f{i}"), FieldDataType::Float))
+            .collect::<Vec<_>>();
+        let err = catalog
+            .create_database_with_tables(
+                "bar",
+                Default::default(),
+                vec![
+                    TableSpec::new("cpu", &["host"], &[("usage", FieldDataType::Float)]),
+                    TableSpec::new("wide", &["host"], &fields),
+                ],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::TooManyColumns(_)));
+        assert_eq!(sequence_before, catalog.sequence_number());
+        assert!(catalog.db_schema("bar").is_none());
+
+        // the retention period is checked against the configured minimum and maximum:
+        let hour = Duration::from_secs(60 * 60);
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+            CatalogArgs::default()
+                .with_min_retention_period(hour)
+                .with_max_retention(24 * hour, MaxRetentionMode::Clamp),
+        )
+        .await
+        .unwrap();
+        let specs = vec![TableSpec::new(
+            "cpu",
+            &["host"],
+            &[("usage", FieldDataType::Float)],
+        )];
+        assert!(matches!(
+            catalog
+                .create_database_with_tables(
+                    "foo",
+                    CreateDatabaseOptions {
+                        retention_period: Some(Duration::from_secs(10)),
+                    },
+                    specs.clone(),
+                )
+                .await,
+            Err(CatalogError::RetentionPeriodTooShort { .. })
+        ));
+        assert!(catalog.db_schema("foo").is_none());
+        catalog
+            .create_database_with_tables(
+                "foo",
+                CreateDatabaseOptions {
+                    retention_period: Some(48 * hour),
+                },
+                specs,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            RetentionPeriod::Duration(24 * hour),
+            catalog.db_schema("foo").unwrap().retention_period
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_undelete_table() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let table_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        // a table that is not deleted cannot be undeleted:
+        assert!(matches!(
+            catalog.undelete_table("foo", table_id).await,
+            Err(CatalogError::NotDeleted)
+        ));
+
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Default)
+            .await
+            .unwrap();
+        let table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_definition_by_id(&table_id)
+            .unwrap();
+        assert!(table.deleted);
+        assert_eq!("cpu-19700101T000000", table.table_name.as_ref());
+        assert!(table.hard_delete_time.is_some());
+
+        catalog.undelete_table("foo", table_id).await.unwrap();
+        let db = catalog.db_schema("foo").unwrap();
+        let table = db.table_definition_by_id(&table_id).unwrap();
+        assert!(!table.deleted);
+        assert_eq!("cpu", table.table_name.as_ref());
+        assert!(table.hard_delete_time.is_none());
+        assert_eq!(Some(table_id), db.table_name_to_id("cpu"));
+        assert!(db.table_definition("cpu-19700101T000000").is_none());
+        assert!(catalog.table_exists_by_name("foo", "cpu"));
+
+        // the restored table is in snapshots:
+        let serialized = serialize_catalog_file(&catalog.snapshot()).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let reloaded = Catalog::new_in_memory("test-2").await.unwrap();
+        reloaded.update_from_snapshot(snapshot);
+        assert!(reloaded.table_exists_by_name("foo", "cpu"));
+
+        // a table cannot be undeleted if another has since taken its name:
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog.undelete_table("foo", table_id).await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(matches!(
+            catalog.undelete_table("foo", TableId::from(100)).await,
+            Err(CatalogError::NotFound)
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_undelete_database() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+
+        // a database that is not deleted cannot be undeleted:
+        assert!(matches!(
+            catalog.undelete_database(db_id).await,
+            Err(CatalogError::NotDeleted)
+        ));
+
+        catalog
+            .soft_delete_database("foo", HardDeletionTime::Default)
+            .await
+            .unwrap();
+        let db = catalog.db_schema_by_id(&db_id).unwrap();
+        assert!(db.deleted);
+        assert_eq!("foo-19700101T000000", db.name.as_ref());
+        assert!(db.hard_delete_time.is_some());
+
+        catalog.undelete_database(db_id).await.unwrap();
+        let db = catalog.db_schema_by_id(&db_id).unwrap();
+        assert!(!db.deleted);
+        assert_eq!("foo", db.name.as_ref());
+        assert!(db.hard_delete_time.is_none());
+        assert_eq!(Some(db_id), catalog.db_name_to_id("foo"));
+        assert!(catalog.db_name_to_id("foo-19700101T000000").is_none());
+        assert!(catalog.table_exists_by_name("foo", "cpu"));
+
+        // the restored database is in snapshots:
+        let serialized = serialize_catalog_file(&catalog.snapshot()).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let reloaded = Catalog::new_in_memory("test-2").await.unwrap();
+        reloaded.update_from_snapshot(snapshot);
+        let db = reloaded.db_schema("foo").unwrap();
+        assert_eq!(db_id, db.id);
+        assert!(!db.deleted);
+        assert!(db.hard_delete_time.is_none());
+        assert!(reloaded.table_exists_by_name("foo", "cpu"));
+
+        // a database cannot be undeleted if another has since taken its name:
+        catalog
+            .soft_delete_database("foo", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog.create_database("foo").await.unwrap();
+        assert!(matches!(
+            catalog.undelete_database(db_id).await,
+            Err(CatalogError::AlreadyExists)
+        ));
+        assert!(matches!(
+            catalog.undelete_database(DbId::from(100)).await,
+            Err(CatalogError::NotFound)
+        ));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_soft_delete_records_original_name() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        let table_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+        assert!(catalog.db_schema("foo").unwrap().original_name.is_none());
+
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("foo", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        // deleting again to set a hard deletion time keeps the original name:
+        catalog
+            .soft_delete_database("foo-19700101T000000", HardDeletionTime::Default)
+            .await
+            .unwrap();
+        let db = catalog.db_schema_by_id(&db_id).unwrap();
+        assert_eq!("foo-19700101T000000", db.name.as_ref());
+        assert_eq!(Some("foo"), db.original_name.as_deref());
+        let table = db.table_definition_by_id(&table_id).unwrap();
+        assert_eq!("cpu-19700101T000000", table.table_name.as_ref());
+        assert_eq!(Some("cpu"), table.original_name.as_deref());
+
+        // the original names are in snapshots:
+        let serialized = serialize_catalog_file(&catalog.snapshot()).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let reloaded = Catalog::new_in_memory("test-2").await.unwrap();
+        reloaded.update_from_snapshot(snapshot);
+        let db = reloaded.db_schema_by_id(&db_id).unwrap();
+        assert_eq!(Some("foo"), db.original_name.as_deref());
+        assert_eq!(
+            Some("cpu"),
+            db.table_definition_by_id(&table_id)
+                .unwrap()
+                .original_name
+                .as_deref()
+        );
+
+        // snapshots taken before original names were recorded fall back to the deleted names:
+        let mut snapshot = catalog.snapshot();
+        let db_snapshot = snapshot.databases.repo.get_mut(&db_id).unwrap();
+        db_snapshot.original_name = None;
+        db_snapshot
+            .tables
+            .repo
+            .get_mut(&table_id)
+            .unwrap()
+            .original_name = None;
+        let serialized = serialize_catalog_file(&snapshot).unwrap();
+        let snapshot = verify_and_deserialize_catalog_checkpoint_file(serialized).unwrap();
+        let reloaded = Catalog::new_in_memory("test-3").await.unwrap();
+        reloaded.update_from_snapshot(snapshot);
+        let db = reloaded.db_schema_by_id(&db_id).unwrap();
+        assert_eq!(Some("foo"), db.original_name.as_deref());
+        assert_eq!(
+            Some("cpu"),
+            db.table_definition_by_id(&table_id)
+                .unwrap()
+                .original_name
+                .as_deref()
+        );
+
+        // the original name is cleared when the resource is undeleted:
+        catalog.undelete_database(db_id).await.unwrap();
+        catalog.undelete_table("foo", table_id).await.unwrap();
+        let db = catalog.db_schema("foo").unwrap();
+        assert!(db.original_name.is_none());
+        assert!(db.table_definition("cpu").unwrap().original_name.is_none());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_list_deleted_resources() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new_in_memory_with_args(
+            "test",
+            Arc::clone(&time_provider) as _,
+            CatalogArgs::default(),
+        )
+        .await
+        .unwrap();
+        assert!(catalog.list_deleted_resources().is_empty());
+
+        for db in ["foo", "bar", "baz"] {
+            catalog.create_database(db).await.unwrap();
+        }
+        for table in ["cpu", "mem"] {
+            catalog
+                .create_table("foo", table, &["host"], &[("usage", FieldDataType::Float)])
+                .await
+                .unwrap();
+        }
+        catalog
+            .create_table("baz", "disk", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let foo_id = catalog.db_name_to_id("foo").unwrap();
+        let bar_id = catalog.db_name_to_id("bar").unwrap();
+        let baz_id = catalog.db_name_to_id("baz").unwrap();
+        let cpu_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+
+        let time = Time::from_timestamp_nanos;
+        catalog
+            .soft_delete_database("bar", HardDeletionTime::Timestamp(time(1_000_000_000)))
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        // the table in the deleted database is not listed:
+        catalog
+            .soft_delete_table("baz", "disk", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        catalog
+            .soft_delete_database("baz", HardDeletionTime::Never)
+            .await
+            .unwrap();
+
+        let deleted = catalog.list_deleted_resources();
+        assert_eq!(
+            deleted,
+            vec![
+                DeletedResource {
+                    target: DeletionTarget::Table {
+                        db_id: foo_id,
+                        table_id: cpu_id
+                    },
+                    name: "cpu-19700101T000000".into(),
+                    original_name: Some("cpu".into()),
+                    hard_delete_time: None,
+                    status: DeletionStatus::Soft,
+                },
+                DeletedResource {
+                    target: DeletionTarget::Database(bar_id),
+                    name: "bar-19700101T000000".into(),
+                    original_name: Some("bar".into()),
+                    hard_delete_time: Some(time(1_000_000_000)),
+                    status: DeletionStatus::Soft,
+                },
+                DeletedResource {
+                    target: DeletionTarget::Database(baz_id),
+                    name: "baz-19700101T000000".into(),
+                    original_name: Some("baz".into()),
+                    hard_delete_time: None,
+                    status: DeletionStatus::Soft,
+                },
+            ]
+        );
+
+        // once its hard deletion time has passed, the database is reported as hard deleted:
+        time_provider.set(time(3_000_000_000));
+        let deleted = catalog.list_deleted_resources();
+        assert_eq!(
+            Some(DeletionStatus::Hard(Duration::from_secs(2))),
+            deleted
+                .iter()
+                .find(|d| d.target == DeletionTarget::Database(bar_id))
+                .map(|d| d.status)
+        );
+        assert_eq!(
+            DeletionStatus::Soft,
+            deleted
+                .iter()
+                .find(|d| d.target == DeletionTarget::Database(baz_id))
+                .unwrap()
+                .status
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_validate_trigger_specifications() {
+        let init = async |validate: bool| {
+            let catalog = Catalog::new_in_memory_with_args(
+                "test",
+                Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))) as _,
+                CatalogArgs::default().with_validate_trigger_specifications(validate),
+            )
+            .await
+            .unwrap();
+            catalog.create_database("foo").await.unwrap();
+            catalog.create_database("bar").await.unwrap();
+            catalog
+        };
+        let create = async |catalog: &Catalog, db: &str, trigger: &str, spec: &str| {
+            catalog
+                .create_processing_engine_trigger(
+                    db,
+                    trigger,
+                    "test".into(),
+                    ValidPluginFilename::from_validated_name("plugin.py"),
+                    spec,
+                    TriggerSettings::default(),
+                    &None,
+                    false,
+                )
+                .await
+        };
+
+        let catalog = init(true).await;
+        create(&catalog, "foo", "hello", "request:hello")
+            .await
+            .unwrap();
+        // the request path conflicts with the existing trigger, whichever database it is in:
+        for db in ["foo", "bar"] {
+            assert!(matches!(
+                create(&catalog, db, "hello-again", "request:hello").await,
+                Err(CatalogError::InvalidTriggerSpecification { .. })
+            ));
+        }
+        create(&catalog, "bar", "goodbye", "request:goodbye")
+            .await
+            .unwrap();
+        assert!(matches!(
+            create(&catalog, "foo", "never", "every:0s").await,
+            Err(CatalogError::InvalidTriggerSpecification { .. })
+        ));
+        assert!(matches!(
+            TriggerSpecificationDefinition::Schedule {
+                schedule: "not a schedule".to_string()
+            }
+            .validate(),
+            Err(CatalogError::InvalidTriggerSpecification { .. })
+        ));
+        assert!(
+            TriggerSpecificationDefinition::Schedule {
+                schedule: "* * * * * *".to_string()
+            }
+            .validate()
+            .is_ok()
+        );
+
+        // without validation, the specifications are accepted:
+        let catalog = init(false).await;
+        create(&catalog, "foo", "hello", "request:hello")
+            .await
+            .unwrap();
+        create(&catalog, "bar", "hello", "request:hello")
+            .await
+            .unwrap();
+        create(&catalog, "foo", "never", "every:0s").await.unwrap();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_disable_and_enable_all_triggers() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        for (db, trigger, disabled) in [
+            ("foo", "a", false),
+            ("foo", "b", false),
+            ("foo", "c", true),
+            ("bar", "d", false),
+        ] {
+            catalog
+                .create_processing_engine_trigger(
+                    db,
+                    trigger,
+                    "test".into(),
+                    ValidPluginFilename::from_validated_name("plugin.py"),
+                    "all_tables",
+                    TriggerSettings::default(),
+                    &None,
+                    disabled,
+                )
+                .await
+                .unwrap();
+        }
+        let active_in_foo = || {
+            catalog
+                .active_triggers()
+                .into_iter()
+                .filter(|(db, _)| db.as_ref() == "foo")
+                .count()
+        };
+        assert_eq!(2, active_in_foo());
+        let sequence = catalog.sequence_number();
+
+        assert_eq!(2, catalog.disable_all_triggers("foo").await.unwrap());
+        // the triggers were disabled in a single batch:
+        assert_eq!(sequence.next(), catalog.sequence_number());
+        assert_eq!(0, active_in_foo());
+        assert_eq!(
+            vec![(Arc::from("bar"), Arc::from("d"))],
+            catalog.active_triggers()
+        );
+        // disabling again changes nothing:
+        assert_eq!(0, catalog.disable_all_triggers("foo").await.unwrap());
+        assert_eq!(sequence.next(), catalog.sequence_number());
+
+        assert_eq!(3, catalog.enable_all_triggers("foo").await.unwrap());
+        assert_eq!(3, active_in_foo());
+        assert_eq!(0, catalog.enable_all_triggers("foo").await.unwrap());
+
+        assert!(matches!(
+            catalog.disable_all_triggers("baz").await,
+            Err(CatalogError::NotFound)
+        ));
+    }
 }
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
-use metric::{Attributes, Metric, Registry, U64Counter};
+use metric::{Attributes, Metric, Registry, U64Counter, U64Gauge};
 
+use super::{INTERNAL_DB_NAME, InnerCatalog};
 use crate::{
     channel::CatalogUpdateReceiver,
     log::{
@@ -19,10 +20,18 @@ pub(super) const CATALOG_OPERATIONS_METRIC_NAME: &str = "influxdb3_catalog_opera
 const CATALOG_OPERATIONS_METRIC_DESCRIPTION: &str =
     "counter of different catalog operations by their operation type";
 
+pub(super) const CATALOG_DATABASES_TOTAL_METRIC_NAME: &str = "influxdb3_catalog_databases_total";
+pub(super) const CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME: &str =
+    "influxdb3_catalog_databases_soft_deleted";
+pub(super) const CATALOG_TABLES_TOTAL_METRIC_NAME: &str = "influxdb3_catalog_tables_total";
+pub(super) const CATALOG_TOKENS_TOTAL_METRIC_NAME: &str = "influxdb3_catalog_tokens_total";
+pub(super) const CATALOG_TRIGGERS_TOTAL_METRIC_NAME: &str = "influxdb3_catalog_triggers_total";
+
 #[derive(Debug)]
 pub(super) struct CatalogMetrics {
     pub(super) catalog_operation_retries: U64Counter,
     catalog_operations: OperationMetrics,
+    catalog_size: SizeMetrics,
 }
 
 impl CatalogMetrics {
@@ -35,9 +44,15 @@ impl CatalogMetrics {
         Self {
             catalog_operation_retries,
             catalog_operations: OperationMetrics::new(metric_registry),
+            catalog_size: SizeMetrics::new(metric_registry),
         }
     }
 
+    /// Set the resource count gauges from the current state of the catalog
+    pub(super) fn record_catalog_size(&self, inner: &InnerCatalog) {
+        self.catalog_size.record(inner);
+    }
+
     pub(super) fn operation_observer(self: &Arc<Self>, mut recv: CatalogUpdateReceiver) {
         let metrics = Arc::clone(self);
         tokio::spawn(async move {
@@ -69,6 +84,11 @@ impl CatalogMetrics {
                                 metrics.catalog_operations.record(op);
                             }
                         }
+                        CatalogBatch::MultiDatabase(multi_batch) => {
+                            for op in multi_batch.batches.iter().flat_map(|b| &b.ops) {
+                                metrics.catalog_operations.record(op);
+                            }
+                        }
                     }
                 }
             }
@@ -76,6 +96,69 @@ impl CatalogMetrics {
     }
 }
 
+/// Gauges tracking the number of resources in the catalog
+#[derive(Debug)]
+struct SizeMetrics {
+    databases: U64Gauge,
+    databases_soft_deleted: U64Gauge,
+    tables: U64Gauge,
+    tokens: U64Gauge,
+    triggers: U64Gauge,
+}
+
+impl SizeMetrics {
+    fn new(metric_registry: &Registry) -> Self {
+        let gauge = |name: &'static str, description: &'static str| {
+            metric_registry
+                .register_metric::<U64Gauge>(name, description)
+                .recorder([])
+        };
+        Self {
+            databases: gauge(
+                CATALOG_DATABASES_TOTAL_METRIC_NAME,
+                "number of databases in the catalog, not including deleted databases",
+            ),
+            databases_soft_deleted: gauge(
+                CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME,
+                "number of databases that are deleted but have not yet been hard deleted",
+            ),
+            tables: gauge(
+                CATALOG_TABLES_TOTAL_METRIC_NAME,
+                "number of tables in the catalog's databases, not including deleted tables",
+            ),
+            tokens: gauge(
+                CATALOG_TOKENS_TOTAL_METRIC_NAME,
+                "number of tokens in the catalog",
+            ),
+            triggers: gauge(
+                CATALOG_TRIGGERS_TOTAL_METRIC_NAME,
+                "number of processing engine triggers in the catalog's databases",
+            ),
+        }
+    }
+
+    fn record(&self, inner: &InnerCatalog) {
+        let (mut databases, mut databases_soft_deleted, mut tables, mut triggers) = (0, 0, 0, 0);
+        for db in inner.databases.resource_iter() {
+            if db.deleted {
+                databases_soft_deleted += 1;
+                continue;
+            }
+            if db.name.as_ref() != INTERNAL_DB_NAME {
+                databases += 1;
+            }
+            tables += db.table_count() as u64;
+            triggers += db.processing_engine_triggers.len() as u64;
+        }
+        self.databases.set(databases);
+        self.databases_soft_deleted.set(databases_soft_deleted);
+        self.tables.set(tables);
+        self.tokens
+            .set(inner.tokens.repo().resource_iter().count() as u64);
+        self.triggers.set(triggers);
+    }
+}
+
 #[derive(Debug)]
 struct OperationMetrics {
     operations: Metric<U64Counter>,
@@ -116,6 +199,10 @@ impl AsMetricStr for TokenCatalogOp {
             TokenCatalogOp::CreateScopedToken(_) => "create_scoped_token",
             TokenCatalogOp::RegenerateAdminToken(_) => "regenerate_admin_token",
             TokenCatalogOp::DeleteToken(_) => "delete_token",
+            TokenCatalogOp::UpdateTokenPermissions(_) => "update_token_permissions",
+            TokenCatalogOp::SetTokenDisabled(_) => "set_token_disabled",
+            TokenCatalogOp::UpdateTokenExpiry(_) => "update_token_expiry",
+            TokenCatalogOp::RenameToken(_) => "rename_token",
         }
     }
 }
@@ -125,8 +212,11 @@ impl AsMetricStr for DatabaseCatalogOp {
         match self {
             DatabaseCatalogOp::CreateDatabase(_) => "create_database",
             DatabaseCatalogOp::SoftDeleteDatabase(_) => "soft_delete_database",
+            DatabaseCatalogOp::UndeleteDatabase(_) => "undelete_database",
             DatabaseCatalogOp::CreateTable(_) => "create_table",
             DatabaseCatalogOp::SoftDeleteTable(_) => "soft_delete_table",
+            DatabaseCatalogOp::UndeleteTable(_) => "undelete_table",
+            DatabaseCatalogOp::SetPrimaryPartitionTag(_) => "set_primary_partition_tag",
             DatabaseCatalogOp::AddFields(_) => "add_fields",
             DatabaseCatalogOp::CreateDistinctCache(_) => "create_distinct_cache",
             DatabaseCatalogOp::DeleteDistinctCache(_) => "delete_distinct_cache",
@@ -142,6 +232,20 @@ impl AsMetricStr for DatabaseCatalogOp {
             DatabaseCatalogOp::ClearRetentionPeriod(ClearRetentionPeriodLog { .. }) => {
                 "clear_retention_period_db"
             }
+            DatabaseCatalogOp::SetDatabaseDescription(_) => "set_database_description",
+            DatabaseCatalogOp::SetNullTagPlaceholder(_) => "set_null_tag_placeholder",
+            DatabaseCatalogOp::RenameDatabase(_) => "rename_database",
+            DatabaseCatalogOp::RenameTable(_) => "rename_table",
+            DatabaseCatalogOp::SetRetentionPeriodForTable(_) => "set_retention_period_for_table",
+            DatabaseCatalogOp::ClearRetentionPeriodForTable(_) => {
+                "clear_retention_period_for_table"
+            }
+            DatabaseCatalogOp::SetColumnAllowedValues(_) => "set_column_allowed_values",
+            DatabaseCatalogOp::SetColumnIndexed(_) => "set_column_indexed",
+            DatabaseCatalogOp::SetColumnDescription(_) => "set_column_description",
+            DatabaseCatalogOp::DropColumn(_) => "drop_column",
+            DatabaseCatalogOp::ReorderSeriesKey(_) => "reorder_series_key",
+            DatabaseCatalogOp::AlterColumnType(_) => "alter_column_type",
         }
     }
 }
@@ -169,15 +273,19 @@ mod tests {
 
     use influxdb3_process::{ProcessUuidGetter, ProcessUuidWrapper};
     use iox_time::{MockProvider, Time};
-    use metric::{Attributes, Metric, Registry, U64Counter};
+    use metric::{Attributes, Metric, Registry, U64Counter, U64Gauge};
     use object_store::memory::InMemory;
 
     use crate::{
-        catalog::{Catalog, Prompt, metrics::CATALOG_OPERATIONS_METRIC_NAME},
-        log::{FieldDataType, NodeMode},
+        catalog::{Catalog, HardDeletionTime, Prompt, metrics::CATALOG_OPERATIONS_METRIC_NAME},
+        log::{FieldDataType, NodeMode, TriggerSettings, ValidPluginFilename},
     };
 
-    use super::CATALOG_OPERATION_RETRIES_METRIC_NAME;
+    use super::{
+        CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME, CATALOG_DATABASES_TOTAL_METRIC_NAME,
+        CATALOG_OPERATION_RETRIES_METRIC_NAME, CATALOG_TABLES_TOTAL_METRIC_NAME,
+        CATALOG_TOKENS_TOTAL_METRIC_NAME, CATALOG_TRIGGERS_TOTAL_METRIC_NAME,
+    };
 
     #[tokio::test]
     async fn test_catalog_retry_metrics() {
@@ -289,6 +397,114 @@ mod tests {
         check_metric(&metrics, "create_admin_token", 1);
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_catalog_size_gauges() {
+        let metrics = Arc::new(Registry::new());
+        let os = Arc::new(InMemory::new());
+        let tp = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let catalog = Catalog::new(
+            "node",
+            Arc::clone(&os) as _,
+            Arc::clone(&tp) as _,
+            Arc::clone(&metrics),
+        )
+        .await
+        .unwrap();
+        // the internal database is not counted:
+        check_gauge(&metrics, CATALOG_DATABASES_TOTAL_METRIC_NAME, 0);
+        check_gauge(&metrics, CATALOG_TABLES_TOTAL_METRIC_NAME, 0);
+
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        catalog
+            .create_table("foo", "mem", &["host"], &[("used", FieldDataType::Integer)])
+            .await
+            .unwrap();
+        catalog
+            .create_table(
+                "bar",
+                "disk",
+                &["host"],
+                &[("free", FieldDataType::Integer)],
+            )
+            .await
+            .unwrap();
+        catalog
+            .create_processing_engine_trigger(
+                "foo",
+                "trigger",
+                "test".into(),
+                ValidPluginFilename::from_validated_name("plugin.py"),
+                "all_tables",
+                TriggerSettings::default(),
+                &None,
+                false,
+            )
+            .await
+            .unwrap();
+        catalog.create_admin_token(false).await.unwrap();
+        catalog
+            .create_scoped_token("scoped".to_string(), vec![], None)
+            .await
+            .unwrap();
+        check_gauge(&metrics, CATALOG_DATABASES_TOTAL_METRIC_NAME, 2);
+        check_gauge(&metrics, CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME, 0);
+        check_gauge(&metrics, CATALOG_TABLES_TOTAL_METRIC_NAME, 3);
+        check_gauge(&metrics, CATALOG_TOKENS_TOTAL_METRIC_NAME, 2);
+        check_gauge(&metrics, CATALOG_TRIGGERS_TOTAL_METRIC_NAME, 1);
+
+        // deleting a table removes it from the count:
+        catalog
+            .soft_delete_table("foo", "mem", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        check_gauge(&metrics, CATALOG_TABLES_TOTAL_METRIC_NAME, 2);
+
+        // a soft deleted database is counted separately, and its tables and triggers are no
+        // longer counted:
+        catalog
+            .soft_delete_database("foo", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        check_gauge(&metrics, CATALOG_DATABASES_TOTAL_METRIC_NAME, 1);
+        check_gauge(&metrics, CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME, 1);
+        check_gauge(&metrics, CATALOG_TABLES_TOTAL_METRIC_NAME, 1);
+        check_gauge(&metrics, CATALOG_TRIGGERS_TOTAL_METRIC_NAME, 0);
+
+        // a new catalog loaded from the object store starts with the same counts:
+        let reloaded_metrics = Arc::new(Registry::new());
+        let _reloaded = Catalog::new(
+            "node",
+            Arc::clone(&os) as _,
+            Arc::clone(&tp) as _,
+            Arc::clone(&reloaded_metrics),
+        )
+        .await
+        .unwrap();
+        check_gauge(&reloaded_metrics, CATALOG_DATABASES_TOTAL_METRIC_NAME, 1);
+        check_gauge(
+            &reloaded_metrics,
+            CATALOG_DATABASES_SOFT_DELETED_METRIC_NAME,
+            1,
+        );
+        check_gauge(&reloaded_metrics, CATALOG_TABLES_TOTAL_METRIC_NAME, 1);
+        check_gauge(&reloaded_metrics, CATALOG_TOKENS_TOTAL_METRIC_NAME, 2);
+    }
+
+    fn check_gauge(registry: &Arc<Registry>, name: &'static str, expected: u64) {
+        let observer = registry
+            .get_instrument::<Metric<U64Gauge>>(name)
+            .unwrap()
+            .get_observer(&Attributes::from([]))
+            .unwrap()
+            .fetch();
+        assert_eq!(expected, observer, "gauge check failed for {name}");
+    }
+
     fn check_metric_empty(registry: &Arc<Registry>, operation_type: &'static str) {
         let instrument = registry
             .get_instrument::<Metric<U64Counter>>(CATALOG_OPERATIONS_METRIC_NAME)
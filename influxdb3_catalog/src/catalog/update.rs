@@ -1,18 +1,23 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::ops::Add;
 use std::sync::Arc;
 
-use hashbrown::HashMap;
-use influxdb3_id::{ColumnId, DbId, TableId};
+use hashbrown::{HashMap, HashSet};
+use influxdb3_authz::{Permission, ResourceIdentifier, TokenInfo};
+use influxdb3_id::{ColumnId, DbId, TableId, TokenId};
 use influxdb3_process::ProcessUuidGetter;
 use iox_time::{Time, TimeProvider};
-use observability_deps::tracing::{debug, error, info, trace};
+use observability_deps::tracing::{debug, error, info, trace, warn};
 use schema::{InfluxColumnType, InfluxFieldType};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use uuid::Uuid;
 
 use super::{
     CATALOG_WRITE_PERMIT, Catalog, CatalogSequenceNumber, CatalogWritePermit, ColumnDefinition,
-    DatabaseSchema, NodeState, TIME_COLUMN_NAME, TableDefinition,
+    ColumnNameRules, DatabaseSchema, InnerCatalog, NodeState, TIME_COLUMN_NAME, TableDefinition,
+    UpdateDatabaseSchema, is_column_type_change, is_type_promotion, original_name_of_deleted,
 };
 use crate::{
     CatalogError, Result,
@@ -20,17 +25,24 @@ use crate::{
         DEFAULT_OPERATOR_TOKEN_NAME, INTERNAL_DB_NAME, NUM_TAG_COLUMNS_LIMIT, RetentionPeriod,
     },
     log::{
-        AddFieldsLog, CatalogBatch, ClearRetentionPeriodLog, CreateDatabaseLog, CreateTableLog,
-        DatabaseCatalogOp, DeleteDistinctCacheLog, DeleteLastCacheLog, DeleteOp,
-        DeleteTokenDetails, DeleteTriggerLog, DistinctCacheDefinition, FieldDataType,
-        FieldDefinition, GenerationOp, LastCacheDefinition, LastCacheSize, LastCacheTtl,
-        LastCacheValueColumnsDef, MaxAge, MaxCardinality, NodeCatalogOp, NodeMode,
-        OrderedCatalogBatch, RegisterNodeLog, SetGenerationDurationLog, SetRetentionPeriodLog,
+        AddFieldsLog, AlterColumnTypeLog, CatalogBatch, ClearRetentionPeriodForTableLog,
+        ClearRetentionPeriodLog, CreateDatabaseLog, CreateScopedTokenDetails, CreateTableLog,
+        DatabaseBatch, DatabaseCatalogOp, DeleteDistinctCacheLog, DeleteLastCacheLog, DeleteOp,
+        DeleteTokenDetails, DeleteTriggerLog, DistinctCacheDefinition, DropColumnLog,
+        FieldDataType, FieldDefinition, GenerationOp, LastCacheDefinition, LastCacheSize,
+        LastCacheTtl, LastCacheValueColumnsDef, MaxAge, MaxCardinality, NodeCatalogOp, NodeMode,
+        OrderedCatalogBatch, RegisterNodeLog, RenameDatabaseLog, RenameTableLog,
+        RenameTokenDetails, ReorderSeriesKeyLog, SetColumnAllowedValuesLog,
+        SetColumnDescriptionLog, SetColumnIndexedLog, SetDatabaseDescriptionLog,
+        SetGenerationDurationLog, SetNullTagPlaceholderLog, SetPrimaryPartitionTagLog,
+        SetRetentionPeriodForTableLog, SetRetentionPeriodLog, SetTokenDisabledDetails,
         SoftDeleteDatabaseLog, SoftDeleteTableLog, StopNodeLog, TokenBatch, TokenCatalogOp,
         TriggerDefinition, TriggerIdentifier, TriggerSettings, TriggerSpecificationDefinition,
-        ValidPluginFilename,
+        UndeleteDatabaseLog, UndeleteTableLog, UpdateTokenExpiryDetails,
+        UpdateTokenPermissionsDetails, ValidPluginFilename,
     },
     object_store::PersistCatalogResult,
+    snapshot::{CatalogSnapshot, versions::Snapshot},
 };
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -38,6 +50,120 @@ pub struct CreateDatabaseOptions {
     pub retention_period: Option<Duration>,
 }
 
+/// Specification of a table to be created, see [`Catalog::replace_table`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSpec {
+    pub table_name: String,
+    /// The tags of the table, in series key order
+    pub tags: Vec<String>,
+    pub fields: Vec<(String, FieldDataType)>,
+}
+
+impl TableSpec {
+    pub fn new(
+        table_name: impl Into<String>,
+        tags: &[impl AsRef<str>],
+        fields: &[(impl AsRef<str>, FieldDataType)],
+    ) -> Self {
+        Self {
+            table_name: table_name.into(),
+            tags: tags.iter().map(|t| t.as_ref().to_string()).collect(),
+            fields: fields
+                .iter()
+                .map(|(name, ty)| (name.as_ref().to_string(), *ty))
+                .collect(),
+        }
+    }
+}
+
+/// Specification of a database to be created along with its tables and their caches, see
+/// [`Catalog::provision`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantProvisionSpec {
+    pub database_name: String,
+    #[serde(default)]
+    pub tables: Vec<ProvisionTableSpec>,
+}
+
+/// A table to be created by [`Catalog::provision`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisionTableSpec {
+    #[serde(flatten)]
+    pub table: TableSpec,
+    #[serde(default)]
+    pub last_caches: Vec<ProvisionLastCacheSpec>,
+    #[serde(default)]
+    pub distinct_caches: Vec<ProvisionDistinctCacheSpec>,
+}
+
+/// A last cache to be created by [`Catalog::provision`], see [`Catalog::create_last_cache`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisionLastCacheSpec {
+    #[serde(default)]
+    pub cache_name: Option<String>,
+    /// Defaults to the series key of the table
+    #[serde(default)]
+    pub key_columns: Option<Vec<String>>,
+    /// Defaults to all non-key columns
+    #[serde(default)]
+    pub value_columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub count: LastCacheSize,
+    #[serde(default)]
+    pub ttl: LastCacheTtl,
+}
+
+/// A distinct cache to be created by [`Catalog::provision`], see
+/// [`Catalog::create_distinct_cache`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisionDistinctCacheSpec {
+    #[serde(default)]
+    pub cache_name: Option<String>,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub max_cardinality: MaxCardinality,
+    #[serde(default)]
+    pub max_age_seconds: MaxAge,
+}
+
+/// How to resolve a name conflict when merging another catalog, see [`Catalog::merge_from`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the existing resource and do not import the incoming one
+    SkipExisting,
+    /// Import the incoming resource under a new name, i.e., `<name>_<n>`
+    RenameIncoming,
+    /// Fail the merge without importing anything
+    Error,
+}
+
+/// A resource imported by [`Catalog::merge_from`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedResource<I> {
+    /// The name of the resource in the incoming catalog
+    pub incoming_name: Arc<str>,
+    /// The id of the resource in the incoming catalog
+    pub incoming_id: I,
+    /// The name of the resource in this catalog, which differs from `incoming_name` if the
+    /// resource was renamed
+    pub name: Arc<str>,
+    /// The id assigned to the resource in this catalog
+    pub id: I,
+}
+
+/// The outcome of [`Catalog::merge_from`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    pub databases_imported: Vec<MergedResource<DbId>>,
+    /// Names of incoming databases that were not imported due to a name conflict
+    pub databases_skipped: Vec<Arc<str>>,
+    pub tokens_imported: Vec<MergedResource<TokenId>>,
+    /// Names of incoming tokens that were not imported, either due to a name conflict, because
+    /// the token already exists in this catalog, or because it has a wildcard or admin permission
+    /// that was not opted into
+    pub tokens_skipped: Vec<Arc<str>>,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum HardDeletionTime {
     /// The object will never be hard deleted.
@@ -82,6 +208,16 @@ impl std::fmt::Display for HardDeletionTime {
 
 impl Catalog {
     pub fn begin(&self, db_name: &str) -> Result<DatabaseCatalogTransaction> {
+        self.begin_with_options(db_name, CreateDatabaseOptions::default())
+    }
+
+    /// Start a transaction as [`Catalog::begin`] does, creating the database with the given
+    /// `options` if it does not exist yet
+    fn begin_with_options(
+        &self,
+        db_name: &str,
+        options: CreateDatabaseOptions,
+    ) -> Result<DatabaseCatalogTransaction> {
         debug!(db_name, "starting catalog transaction");
         let inner = self.inner.read();
         match self.db_schema(db_name) {
@@ -93,21 +229,22 @@ impl Catalog {
                 database_schema: Arc::clone(&database_schema),
                 ops: vec![],
                 columns_per_table_limit: self.num_columns_per_table_limit(),
+                type_promotion: self.type_promotion(),
+                column_name_rules: self.column_name_rules(),
             }),
             None => {
+                self.check_writable()?;
+                Self::validate_database_name(db_name)?;
                 if inner.database_count() >= self.num_dbs_limit() {
                     return Err(CatalogError::TooManyDbs(self.num_dbs_limit()));
                 }
+                let retention_period = self.new_database_retention(options.retention_period)?;
                 drop(inner);
                 let mut inner = self.inner.write();
                 let database_id = inner.databases.get_and_increment_next_id();
                 let database_name = Arc::from(db_name);
                 let database_schema =
                     Arc::new(DatabaseSchema::new(database_id, Arc::clone(&database_name)));
-                let retention_period = match database_schema.retention_period {
-                    RetentionPeriod::Duration(duration) => Some(duration),
-                    RetentionPeriod::Indefinite => None,
-                };
                 let time_ns = self.time_provider.now().timestamp_nanos();
                 let ops = vec![DatabaseCatalogOp::CreateDatabase(CreateDatabaseLog {
                     database_id,
@@ -122,6 +259,8 @@ impl Catalog {
                     database_schema,
                     ops,
                     columns_per_table_limit: self.num_columns_per_table_limit(),
+                    type_promotion: self.type_promotion(),
+                    column_name_rules: self.column_name_rules(),
                 })
             }
         }
@@ -137,7 +276,7 @@ impl Catalog {
 
         match self
             .get_permit_and_verify_catalog_batch(txn.catalog_batch(), txn.sequence_number())
-            .await
+            .await?
         {
             Prompt::Success((ordered_batch, permit)) => {
                 match self
@@ -148,7 +287,7 @@ impl Catalog {
                     UpdatePrompt::Applied => {
                         self.apply_ordered_catalog_batch(&ordered_batch, &permit);
                         self.background_checkpoint(&ordered_batch);
-                        self.broadcast_update(ordered_batch.into_batch()).await?;
+                        self.broadcast_update(ordered_batch).await?;
                         Ok(Prompt::Success(self.sequence_number()))
                     }
                 }
@@ -356,6 +495,7 @@ impl Catalog {
             }
             let deletion_time = self.time_provider.now().timestamp_nanos();
             let database_id = db.id;
+            let original_name = db.original_name.as_ref().unwrap_or(&db.name);
             Ok(CatalogBatch::database(
                 deletion_time,
                 database_id,
@@ -366,6 +506,7 @@ impl Catalog {
                         database_name: db.name(),
                         deletion_time,
                         hard_deletion_time: resolved_hard_delete_time.map(|t|t.timestamp_nanos()),
+                        original_name: Some(Arc::clone(original_name)),
                     },
                 )],
             ))
@@ -385,6 +526,59 @@ impl Catalog {
         })
     }
 
+    /// Reverse the soft deletion of a database, before it is hard deleted
+    ///
+    /// The database is given back the name it had before it was deleted, and its hard deletion
+    /// is cancelled. Tables that were deleted separately, before or after the database, remain
+    /// deleted, see [`Catalog::undelete_table`].
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database doesn't exist, e.g., because it was already
+    ///   hard deleted
+    /// * `CatalogError::NotDeleted` - If the database is not deleted
+    /// * `CatalogError::AlreadyExists` - If a database has since been created with the original
+    ///   name
+    /// * `CatalogError::TooManyDbs` - If restoring the database would exceed the database limit
+    pub async fn undelete_database(&self, db_id: DbId) -> Result<OrderedCatalogBatch> {
+        info!(%db_id, "undelete database");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema_by_id(&db_id) else {
+                return Err(CatalogError::NotFound);
+            };
+            if !db.deleted {
+                return Err(CatalogError::NotDeleted);
+            }
+            let Some(database_name) = db
+                .original_name
+                .as_deref()
+                .or_else(|| original_name_of_deleted(&db.name))
+            else {
+                return Err(CatalogError::unexpected(format!(
+                    "deleted database '{}' does not have a deleted name",
+                    db.name
+                )));
+            };
+            if self.db_name_to_id(database_name).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if self.inner.read().database_count() >= self.num_dbs_limit() {
+                return Err(CatalogError::TooManyDbs(self.num_dbs_limit()));
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::UndeleteDatabase(UndeleteDatabaseLog {
+                    database_id: db.id,
+                    deleted_name: db.name(),
+                    database_name: database_name.into(),
+                })],
+            ))
+        })
+        .await
+    }
+
     pub async fn create_table(
         &self,
         db_name: &str,
@@ -401,6 +595,565 @@ impl Catalog {
         .await
     }
 
+    /// Create all of the tables described by `specs` in a single catalog batch
+    ///
+    /// This is cheaper than calling [`Catalog::create_table`] for each table, as the catalog is
+    /// only updated, and persisted to object store, once. Either all of the tables are created or
+    /// none of them are; the batch fails if any of the tables already exist, or if creating all of
+    /// them would exceed the table limit.
+    pub async fn create_tables(
+        &self,
+        db_name: &str,
+        specs: Vec<TableSpec>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, n_tables = specs.len(), "create tables");
+        if specs.is_empty() {
+            return Err(CatalogError::invalid_configuration(
+                "at least one table must be specified",
+            ));
+        }
+        self.catalog_update_with_retry(|| {
+            // the transaction checks the limit against the table count from before it was started,
+            // so check that there is room for the whole batch up front:
+            if self.inner.read().table_count() + specs.len() > self.num_tables_limit() {
+                return Err(CatalogError::TooManyTables(self.num_tables_limit()));
+            }
+            let mut txn = self.begin(db_name)?;
+            for spec in &specs {
+                txn.create_table(&spec.table_name, &spec.tags, &spec.fields)?;
+            }
+            Ok(txn.into())
+        })
+        .await
+    }
+
+    /// Create a database along with the given tables in a single catalog batch
+    ///
+    /// The tables are validated before anything is written, so if any of them cannot be created,
+    /// e.g., because it would exceed the column limit, neither the database nor any of the tables
+    /// are created. Fails if a database with the same name already exists, or if the retention
+    /// period in `options` is not allowed, see [`CatalogArgs::min_retention_period`] and
+    /// [`CatalogArgs::max_retention`].
+    ///
+    /// [`CatalogArgs::min_retention_period`]: crate::catalog::CatalogArgs::min_retention_period
+    /// [`CatalogArgs::max_retention`]: crate::catalog::CatalogArgs::max_retention
+    pub async fn create_database_with_tables(
+        &self,
+        db_name: &str,
+        options: CreateDatabaseOptions,
+        tables: Vec<TableSpec>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(
+            db_name,
+            n_tables = tables.len(),
+            "create database with tables"
+        );
+        let tables = tables
+            .into_iter()
+            .map(|table| ProvisionTableSpec {
+                table,
+                last_caches: vec![],
+                distinct_caches: vec![],
+            })
+            .collect::<Vec<_>>();
+        self.create_database_from_spec(db_name, options, &tables)
+            .await
+    }
+
+    /// Create a database along with all of its tables and their last and distinct caches in a
+    /// single catalog batch
+    ///
+    /// The whole spec is validated, including against the database, table, and column limits,
+    /// before anything is written, so either everything in the spec is created or nothing is.
+    /// Fails if a database with the same name already exists. Returns the id of the new database.
+    pub async fn provision(&self, spec: TenantProvisionSpec) -> Result<DbId> {
+        info!(
+            database_name = spec.database_name,
+            n_tables = spec.tables.len(),
+            "provision database"
+        );
+        self.create_database_from_spec(
+            &spec.database_name,
+            CreateDatabaseOptions::default(),
+            &spec.tables,
+        )
+        .await?;
+
+        Ok(self
+            .db_schema(&spec.database_name)
+            .map(|db| db.id)
+            .expect("database should exist after it was provisioned"))
+    }
+
+    /// Create a database with the given tables, and their caches, in a single catalog batch, see
+    /// [`Catalog::provision`]
+    async fn create_database_from_spec(
+        &self,
+        db_name: &str,
+        options: CreateDatabaseOptions,
+        tables: &[ProvisionTableSpec],
+    ) -> Result<OrderedCatalogBatch> {
+        self.catalog_update_with_retry(|| {
+            if self.db_schema(db_name).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            // as in create_tables, the transaction only checks the table limit against the count
+            // from before it was started:
+            if self.inner.read().table_count() + tables.len() > self.num_tables_limit() {
+                return Err(CatalogError::TooManyTables(self.num_tables_limit()));
+            }
+            let mut txn = self.begin_with_options(db_name, options)?;
+            for ProvisionTableSpec { table, .. } in tables {
+                txn.create_table(&table.table_name, &table.tags, &table.fields)?;
+            }
+
+            let mut cache_ops = Vec::new();
+            for table_spec in tables {
+                let mut tbl = txn
+                    .db_schema()
+                    .table_definition(&table_spec.table.table_name)
+                    .expect("table was created in the transaction");
+                // the caches are not applied to the transaction's schema, so names that clash
+                // within the spec need to be caught here:
+                let mut last_cache_names = HashSet::new();
+                for cache in &table_spec.last_caches {
+                    let definition = new_last_cache_definition(
+                        &mut tbl,
+                        cache.cache_name.as_deref(),
+                        cache.key_columns.as_deref(),
+                        cache.value_columns.as_deref(),
+                        cache.count,
+                        cache.ttl,
+                    )?;
+                    if !last_cache_names.insert(Arc::clone(&definition.name)) {
+                        return Err(CatalogError::AlreadyExists);
+                    }
+                    cache_ops.push(DatabaseCatalogOp::CreateLastCache(definition));
+                }
+                let mut distinct_cache_names = HashSet::new();
+                for cache in &table_spec.distinct_caches {
+                    let definition = new_distinct_cache_definition(
+                        &mut tbl,
+                        cache.cache_name.as_deref(),
+                        &cache.columns,
+                        cache.max_cardinality,
+                        cache.max_age_seconds,
+                    )?;
+                    if !distinct_cache_names.insert(Arc::clone(&definition.cache_name)) {
+                        return Err(CatalogError::AlreadyExists);
+                    }
+                    cache_ops.push(DatabaseCatalogOp::CreateDistinctCache(definition));
+                }
+            }
+            for op in cache_ops {
+                txn.push_op(op);
+            }
+            Ok(txn.into())
+        })
+        .await
+    }
+
+    /// Soft delete the table `table_name` and create the table described by `new_spec` in its
+    /// place, in a single catalog batch
+    ///
+    /// The new table is usually given the same name as the one it replaces; the replaced table is
+    /// renamed as it would be by [`Catalog::soft_delete_table`] and is scheduled for hard deletion
+    /// after the default duration. Returns the id of the new table.
+    pub async fn replace_table(
+        &self,
+        db_name: &str,
+        table_name: &str,
+        new_spec: TableSpec,
+    ) -> Result<TableId> {
+        info!(
+            db_name,
+            table_name,
+            new_table_name = new_spec.table_name,
+            "replace table"
+        );
+        self.catalog_update_with_retry(|| {
+            if self.db_schema(db_name).is_none() {
+                return Err(CatalogError::NotFound);
+            }
+            let mut txn = self.begin(db_name)?;
+            txn.soft_delete_table(
+                table_name,
+                HardDeletionTime::Default
+                    .as_time(&self.time_provider, self.default_hard_delete_duration()),
+            )?;
+            txn.create_table(&new_spec.table_name, &new_spec.tags, &new_spec.fields)?;
+            Ok(txn.into())
+        })
+        .await?;
+
+        Ok(self
+            .db_schema(db_name)
+            .and_then(|db| db.table_name_to_id(&new_spec.table_name))
+            .expect("table should exist after it was replaced"))
+    }
+
+    /// Create a new empty table named `dst_table` in the `dst_db` database that has the same
+    /// columns, series key, and caches as the `src_table` in `src_db`
+    ///
+    /// The new table and its columns and caches are assigned new identifiers and are created in a
+    /// single catalog batch. No data is copied. Fails if `dst_db` does not exist, or if it already
+    /// contains a table named `dst_table`.
+    pub async fn copy_table_schema(
+        &self,
+        src_db: &str,
+        src_table: &str,
+        dst_db: &str,
+        dst_table: &str,
+    ) -> Result<TableId> {
+        info!(src_db, src_table, dst_db, dst_table, "copy table schema");
+        self.catalog_update_with_retry(|| {
+            let Some(src) = self
+                .db_schema(src_db)
+                .and_then(|db| db.table_definition(src_table))
+            else {
+                return Err(CatalogError::NotFound);
+            };
+            if self.db_schema(dst_db).is_none() {
+                return Err(CatalogError::NotFound);
+            }
+            let mut txn = self.begin(dst_db)?;
+
+            // tags that are not part of the series key are added after the table is created so
+            // that the series key of the new table matches the source table
+            let fields = src
+                .columns
+                .resource_iter()
+                .filter(|def| matches!(def.data_type, InfluxColumnType::Field(_)))
+                .map(|def| (Arc::clone(&def.name), FieldDataType::from(&def.data_type)))
+                .collect::<Vec<_>>();
+            txn.create_table(dst_table, src.series_key_names(), &fields)?;
+            for def in src.columns.resource_iter() {
+                if matches!(def.data_type, InfluxColumnType::Tag)
+                    && !src.series_key_names.contains(&def.name)
+                {
+                    txn.column_or_create(dst_table, &def.name, FieldDataType::Tag)?;
+                }
+            }
+
+            let mut dst = txn
+                .db_schema()
+                .table_definition(dst_table)
+                .expect("table was created in the transaction");
+            let column_ids = src
+                .columns
+                .resource_iter()
+                .map(|def| {
+                    let dst_id = dst
+                        .column_name_to_id(&def.name)
+                        .expect("copied table should have all columns of the source table");
+                    (def.id, dst_id)
+                })
+                .collect::<HashMap<ColumnId, ColumnId>>();
+            let map_column_id = |id: &ColumnId| column_ids[id];
+            let mut cache_ops = Vec::new();
+            for cache in src.last_caches.resource_iter() {
+                cache_ops.push(DatabaseCatalogOp::CreateLastCache(LastCacheDefinition {
+                    table_id: dst.table_id,
+                    table: Arc::clone(&dst.table_name),
+                    id: Arc::make_mut(&mut dst)
+                        .last_caches
+                        .get_and_increment_next_id(),
+                    name: Arc::clone(&cache.name),
+                    key_columns: cache.key_columns.iter().map(map_column_id).collect(),
+                    value_columns: match &cache.value_columns {
+                        LastCacheValueColumnsDef::Explicit { columns } => {
+                            LastCacheValueColumnsDef::Explicit {
+                                columns: columns.iter().map(map_column_id).collect(),
+                            }
+                        }
+                        LastCacheValueColumnsDef::AllNonKeyColumns => {
+                            LastCacheValueColumnsDef::AllNonKeyColumns
+                        }
+                    },
+                    count: cache.count,
+                    ttl: cache.ttl,
+                }));
+            }
+            for cache in src.distinct_caches.resource_iter() {
+                cache_ops.push(DatabaseCatalogOp::CreateDistinctCache(
+                    DistinctCacheDefinition {
+                        table_id: dst.table_id,
+                        table_name: Arc::clone(&dst.table_name),
+                        cache_id: Arc::make_mut(&mut dst)
+                            .distinct_caches
+                            .get_and_increment_next_id(),
+                        cache_name: Arc::clone(&cache.cache_name),
+                        column_ids: cache.column_ids.iter().map(map_column_id).collect(),
+                        max_cardinality: cache.max_cardinality,
+                        max_age_seconds: cache.max_age_seconds,
+                    },
+                ));
+            }
+            for op in cache_ops {
+                txn.push_op(op);
+            }
+            Ok(txn.into())
+        })
+        .await?;
+
+        Ok(self
+            .db_schema(dst_db)
+            .and_then(|db| db.table_name_to_id(dst_table))
+            .expect("table should exist after it was copied"))
+    }
+
+    /// Import the databases and tokens from another catalog's snapshot into this catalog
+    ///
+    /// Database and token ids are remapped to avoid collisions with those in this catalog, and
+    /// database ids referenced by token permissions are remapped to match. Name conflicts are
+    /// resolved according to `conflict`; under [`MergePolicy::Error`] all conflicts are checked
+    /// before anything is imported.
+    ///
+    /// Each database is imported in its own batch, followed by a single batch for all tokens.
+    /// Deleted databases and tables, the internal database, processing engine triggers, and the
+    /// operator token are not imported. Permissions on databases that were not imported are
+    /// dropped rather than pointed at an existing database of the same name.
+    ///
+    /// Tokens with a permission that is not limited to the databases it names, such as wildcard
+    /// database tokens and named admin tokens, would be granted access to every database in this
+    /// catalog, so they are skipped unless `import_unscoped_tokens` is set.
+    pub async fn merge_from(
+        &self,
+        other: &CatalogSnapshot,
+        conflict: MergePolicy,
+        import_unscoped_tokens: bool,
+    ) -> Result<MergeReport> {
+        info!(
+            other_catalog_id = %other.catalog_id,
+            ?conflict,
+            import_unscoped_tokens,
+            "merge catalog"
+        );
+        let other = InnerCatalog::from_snapshot(other.clone());
+        let incoming_dbs = other
+            .databases
+            .resource_iter()
+            .filter(|db| !db.deleted && db.name.as_ref() != INTERNAL_DB_NAME)
+            .cloned()
+            .collect::<Vec<_>>();
+        let (incoming_tokens, unscoped_tokens): (Vec<_>, Vec<_>) = other
+            .tokens
+            .repo()
+            .resource_iter()
+            .filter(|token| token.name.as_ref() != DEFAULT_OPERATOR_TOKEN_NAME)
+            .cloned()
+            .partition(|token| import_unscoped_tokens || !has_unscoped_permission(token));
+
+        if conflict == MergePolicy::Error {
+            if incoming_dbs
+                .iter()
+                .any(|db| self.db_name_to_id(&db.name).is_some())
+            {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if let Some(token) = incoming_tokens
+                .iter()
+                .find(|token| self.inner.read().tokens.repo().contains_name(&token.name))
+            {
+                return Err(CatalogError::TokenNameAlreadyExists(token.name.to_string()));
+            }
+        }
+
+        let mut report = MergeReport {
+            tokens_skipped: unscoped_tokens
+                .iter()
+                .map(|token| Arc::clone(&token.name))
+                .collect(),
+            ..Default::default()
+        };
+        let mut db_ids = HashMap::new();
+        for db in incoming_dbs {
+            let name = if self.db_name_to_id(&db.name).is_none() {
+                Arc::clone(&db.name)
+            } else if conflict == MergePolicy::RenameIncoming {
+                merged_name(&db.name, |name| self.db_name_to_id(name).is_some())
+            } else {
+                report.databases_skipped.push(Arc::clone(&db.name));
+                continue;
+            };
+            let id = self.import_database(&db, &name).await?;
+            db_ids.insert(db.id, id);
+            report.databases_imported.push(MergedResource {
+                incoming_name: Arc::clone(&db.name),
+                incoming_id: db.id,
+                name,
+                id,
+            });
+        }
+
+        let mut tokens: Vec<(Arc<str>, Arc<TokenInfo>)> = Vec::new();
+        for token in incoming_tokens {
+            let (name_exists, hash_exists) = {
+                let inner = self.inner.read();
+                (
+                    inner.tokens.repo().contains_name(&token.name),
+                    inner.tokens.hash_to_info(token.hash.clone()).is_some(),
+                )
+            };
+            let name = if hash_exists {
+                report.tokens_skipped.push(Arc::clone(&token.name));
+                continue;
+            } else if !name_exists {
+                Arc::clone(&token.name)
+            } else if conflict == MergePolicy::RenameIncoming {
+                merged_name(&token.name, |name| {
+                    self.inner.read().tokens.repo().contains_name(name)
+                        || tokens.iter().any(|(n, _)| n.as_ref() == name)
+                })
+            } else {
+                report.tokens_skipped.push(Arc::clone(&token.name));
+                continue;
+            };
+            tokens.push((name, token));
+        }
+        if !tokens.is_empty() {
+            self.catalog_update_with_retry(|| {
+                let existing = self
+                    .inner
+                    .read()
+                    .tokens
+                    .repo()
+                    .resource_iter()
+                    .filter(|token| token.name.as_ref() != DEFAULT_OPERATOR_TOKEN_NAME)
+                    .count();
+                if existing + tokens.len() > self.num_tokens_limit() {
+                    return Err(CatalogError::TooManyTokens(self.num_tokens_limit()));
+                }
+                let mut ops = Vec::with_capacity(tokens.len());
+                for (name, token) in &tokens {
+                    let token_id = self.inner.write().tokens.get_and_increment_next_id();
+                    let permissions = token
+                        .permissions
+                        .iter()
+                        .filter_map(|permission| remap_permission(permission, &db_ids))
+                        .collect();
+                    ops.push(TokenCatalogOp::CreateScopedToken(
+                        CreateScopedTokenDetails {
+                            token_id,
+                            name: Arc::clone(name),
+                            hash: token.hash.clone(),
+                            created_at: token.created_at,
+                            updated_at: token.updated_at,
+                            expiry: token.maybe_expiry_millis(),
+                            permissions,
+                        },
+                    ));
+                }
+                Ok(CatalogBatch::Token(TokenBatch {
+                    time_ns: self.time_provider.now().timestamp_nanos(),
+                    ops,
+                }))
+            })
+            .await?;
+            let inner = self.inner.read();
+            report.tokens_imported = tokens
+                .into_iter()
+                .map(|(name, token)| MergedResource {
+                    incoming_name: Arc::clone(&token.name),
+                    incoming_id: token.id,
+                    id: inner
+                        .tokens
+                        .repo()
+                        .name_to_id(&name)
+                        .expect("token should exist after it was imported"),
+                    name,
+                })
+                .collect();
+        }
+
+        Ok(report)
+    }
+
+    /// Create a new database named `name` with the tables and caches of `db`, which is from
+    /// another catalog, and return the id it was assigned
+    async fn import_database(&self, db: &DatabaseSchema, name: &Arc<str>) -> Result<DbId> {
+        let tables = db
+            .tables()
+            .filter(|table_def| !table_def.deleted)
+            .collect::<Vec<_>>();
+        self.catalog_update_with_retry(|| {
+            if self.db_name_to_id(name).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if self.inner.read().table_count() + tables.len() > self.num_tables_limit() {
+                return Err(CatalogError::TooManyTables(self.num_tables_limit()));
+            }
+            let mut txn = self.begin(name)?;
+            let database_id = txn.db_schema().id;
+            if matches!(db.retention_period, RetentionPeriod::Duration(_)) {
+                txn.push_op(DatabaseCatalogOp::SetRetentionPeriod(
+                    SetRetentionPeriodLog {
+                        database_name: Arc::clone(name),
+                        database_id,
+                        retention_period: db.retention_period,
+                    },
+                ));
+            }
+            if let Some(description) = &db.description {
+                txn.push_op(DatabaseCatalogOp::SetDatabaseDescription(
+                    SetDatabaseDescriptionLog {
+                        database_name: Arc::clone(name),
+                        database_id,
+                        description: Some(Arc::clone(description)),
+                    },
+                ));
+            }
+            if let Some(placeholder) = &db.null_tag_placeholder {
+                txn.push_op(DatabaseCatalogOp::SetNullTagPlaceholder(
+                    SetNullTagPlaceholderLog {
+                        database_name: Arc::clone(name),
+                        database_id,
+                        placeholder: Some(Arc::clone(placeholder)),
+                    },
+                ));
+            }
+            for table_def in &tables {
+                // column and cache ids are scoped to the table, so only the table id needs to be
+                // assigned anew:
+                let table_id = txn.reserve_table_id();
+                txn.push_op(DatabaseCatalogOp::CreateTable(CreateTableLog {
+                    database_id,
+                    database_name: Arc::clone(name),
+                    table_name: Arc::clone(&table_def.table_name),
+                    table_id,
+                    field_definitions: table_def
+                        .columns
+                        .resource_iter()
+                        .map(|def| {
+                            FieldDefinition::new(def.id, Arc::clone(&def.name), &def.data_type)
+                        })
+                        .collect(),
+                    key: table_def.series_key.clone(),
+                }));
+                for cache in table_def.last_caches.resource_iter() {
+                    txn.push_op(DatabaseCatalogOp::CreateLastCache(LastCacheDefinition {
+                        table_id,
+                        ..cache.as_ref().clone()
+                    }));
+                }
+                for cache in table_def.distinct_caches.resource_iter() {
+                    txn.push_op(DatabaseCatalogOp::CreateDistinctCache(
+                        DistinctCacheDefinition {
+                            table_id,
+                            ..cache.as_ref().clone()
+                        },
+                    ));
+                }
+            }
+            Ok(txn.into())
+        })
+        .await?;
+
+        Ok(self
+            .db_name_to_id(name)
+            .expect("database should exist after it was imported"))
+    }
+
     pub async fn soft_delete_table(
         &self,
         db_name: &str,
@@ -432,6 +1185,10 @@ impl Catalog {
                 return Err(CatalogError::AlreadyDeleted);
             }
             let deletion_time = self.time_provider.now().timestamp_nanos();
+            let original_name = tbl_def
+                .original_name
+                .as_ref()
+                .unwrap_or(&tbl_def.table_name);
             Ok(CatalogBatch::database(
                 deletion_time,
                 db.id,
@@ -443,6 +1200,7 @@ impl Catalog {
                     table_name: Arc::clone(&tbl_def.table_name),
                     deletion_time,
                     hard_deletion_time: resolved_hard_delete_time.map(|t|t.timestamp_nanos()),
+                    original_name: Some(Arc::clone(original_name)),
                 })],
             ))
         })
@@ -461,37 +1219,103 @@ impl Catalog {
             })
     }
 
-    /// Permanently delete a table from the catalog.
+    /// Reverse the soft deletion of a table, before it is hard deleted
     ///
-    /// This function performs a hard deletion of a table, which means the table
-    /// will be completely removed from the catalog and marked for cleanup in the
-    /// deleted objects tracking system.
+    /// The table is given back the name it had before it was deleted, and its hard deletion is
+    /// cancelled. The table is identified by id, since it was renamed when it was deleted.
     ///
     /// # Errors
-    /// * `CatalogError::NotFound` - If the database or table doesn't exist
-    pub async fn hard_delete_table(
+    ///
+    /// * `CatalogError::NotFound` - If the database or table doesn't exist, e.g., because the
+    ///   table was already hard deleted
+    /// * `CatalogError::NotDeleted` - If the table is not deleted
+    /// * `CatalogError::AlreadyExists` - If a table has since been created with the original name
+    /// * `CatalogError::InvalidConfiguration` - If the database is deleted
+    /// * `CatalogError::TooManyTables` - If restoring the table would exceed the table limit
+    pub async fn undelete_table(
         &self,
-        db_id: &DbId,
-        table_id: &TableId,
+        db_name: &str,
+        table_id: TableId,
     ) -> Result<OrderedCatalogBatch> {
-        info!(?db_id, ?table_id, "Hard delete table.");
+        info!(db_name, %table_id, "undelete table");
         self.catalog_update_with_retry(|| {
-            let Some(db) = self.db_schema_by_id(db_id) else {
+            let Some(db) = self.db_schema(db_name) else {
                 return Err(CatalogError::NotFound);
             };
-            let Some(_table_def) = db.table_definition_by_id(table_id) else {
+            if db.deleted {
+                return Err(CatalogError::invalid_configuration(
+                    "cannot undelete a table in a deleted database",
+                ));
+            }
+            let Some(table) = db.table_definition_by_id(&table_id) else {
                 return Err(CatalogError::NotFound);
             };
-
-            let deletion_time = self.time_provider.now().timestamp_nanos();
-            Ok(CatalogBatch::delete(
-                deletion_time,
-                vec![DeleteOp::DeleteTable(*db_id, *table_id)],
-            ))
-        })
-        .await
-    }
-
+            if !table.deleted {
+                return Err(CatalogError::NotDeleted);
+            }
+            let Some(table_name) = table
+                .original_name
+                .as_deref()
+                .or_else(|| original_name_of_deleted(&table.table_name))
+            else {
+                return Err(CatalogError::unexpected(format!(
+                    "deleted table '{}' does not have a deleted name",
+                    table.table_name
+                )));
+            };
+            if db.table_definition(table_name).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if self.inner.read().table_count() >= self.num_tables_limit() {
+                return Err(CatalogError::TooManyTables(self.num_tables_limit()));
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::UndeleteTable(UndeleteTableLog {
+                    database_id: db.id,
+                    database_name: db.name(),
+                    table_id,
+                    deleted_name: Arc::clone(&table.table_name),
+                    table_name: table_name.into(),
+                })],
+            ))
+        })
+        .await
+    }
+
+    /// Permanently delete a table from the catalog.
+    ///
+    /// This function performs a hard deletion of a table, which means the table
+    /// will be completely removed from the catalog and marked for cleanup in the
+    /// deleted objects tracking system.
+    ///
+    /// # Errors
+    /// * `CatalogError::NotFound` - If the database or table doesn't exist
+    pub async fn hard_delete_table(
+        &self,
+        db_id: &DbId,
+        table_id: &TableId,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(?db_id, ?table_id, "Hard delete table.");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema_by_id(db_id) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(_table_def) = db.table_definition_by_id(table_id) else {
+                return Err(CatalogError::NotFound);
+            };
+
+            let deletion_time = self.time_provider.now().timestamp_nanos();
+            Ok(CatalogBatch::delete(
+                deletion_time,
+                vec![DeleteOp::DeleteTable(*db_id, *table_id)],
+            ))
+        })
+        .await
+    }
+
     /// Permanently delete a database from the catalog.
     ///
     /// This function performs a hard deletion of a database, which means the database
@@ -523,6 +1347,58 @@ impl Catalog {
         .await
     }
 
+    /// Permanently delete every soft-deleted database and table whose hard deletion time has
+    /// passed, in a single catalog batch
+    ///
+    /// This removes the same databases and tables that [`Catalog::overdue_hard_deletes`] reports
+    /// for the current time, and is intended to be called periodically, e.g., from a background
+    /// task. Returns the databases, and tables, that were removed from the catalog, so that the
+    /// caller can clean up their data; a `None` table id means the whole database was removed.
+    pub async fn run_hard_delete_sweep(&self) -> Result<Vec<(DbId, Option<TableId>)>> {
+        let result = self
+            .catalog_update_with_retry(|| {
+                let overdue = self.overdue_hard_deletes(self.time_provider.now());
+                if overdue.is_empty() {
+                    // nothing to do, or already removed by another update:
+                    return Err(CatalogError::NotFound);
+                }
+                let ops = overdue
+                    .databases
+                    .iter()
+                    .map(|(db_id, _)| DeleteOp::DeleteDatabase(*db_id))
+                    .chain(
+                        overdue
+                            .tables
+                            .iter()
+                            .map(|(db_id, table_id, _)| DeleteOp::DeleteTable(*db_id, *table_id)),
+                    )
+                    .collect();
+                Ok(CatalogBatch::delete(
+                    self.time_provider.now().timestamp_nanos(),
+                    ops,
+                ))
+            })
+            .await;
+        let batch = match result {
+            Ok(batch) => batch,
+            Err(CatalogError::NotFound) => return Ok(vec![]),
+            Err(error) => return Err(error),
+        };
+        let CatalogBatch::Delete(delete_batch) = batch.batch() else {
+            unreachable!("hard delete sweep should produce a delete batch");
+        };
+        let deleted = delete_batch
+            .ops
+            .iter()
+            .map(|op| match op {
+                DeleteOp::DeleteDatabase(db_id) => (*db_id, None),
+                DeleteOp::DeleteTable(db_id, table_id) => (*db_id, Some(*table_id)),
+            })
+            .collect::<Vec<_>>();
+        info!(n_deleted = deleted.len(), "hard delete sweep");
+        Ok(deleted)
+    }
+
     pub async fn create_distinct_cache(
         &self,
         db_name: &str,
@@ -540,67 +1416,18 @@ impl Catalog {
             let Some(mut tbl) = db.table_definition(table_name) else {
                 return Err(CatalogError::NotFound);
             };
-            if columns.is_empty() {
-                return Err(CatalogError::invalid_configuration(
-                    "no columns provided when creating distinct cache",
-                ));
-            }
-
-            fn is_valid_distinct_cache_type(def: &ColumnDefinition) -> bool {
-                matches!(
-                    def.data_type,
-                    InfluxColumnType::Tag | InfluxColumnType::Field(InfluxFieldType::String),
-                )
-            }
-
-            let (column_ids, col_names) = columns
-                .iter()
-                .map(|name| {
-                    tbl.column_definition(name.as_ref())
-                        .ok_or_else(|| {
-                            CatalogError::invalid_configuration(
-                                format!("invalid column provided: {name}", name = name.as_ref())
-                                    .as_str(),
-                            )
-                        })
-                        .and_then(|def| {
-                            if is_valid_distinct_cache_type(&def) {
-                                Ok((def.id, name.as_ref().to_string()))
-                            } else {
-                                Err(CatalogError::InvalidDistinctCacheColumnType)
-                            }
-                        })
-                })
-                .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?;
-            let cache_name = cache_name.map(Arc::from).unwrap_or_else(|| {
-                format!(
-                    "{table_name}_{cols}_distinct_cache",
-                    cols = col_names.join("_")
-                )
-                .as_str()
-                .into()
-            });
-            if tbl.distinct_caches.contains_name(&cache_name) {
-                return Err(CatalogError::AlreadyExists);
-            }
-            let cache_id = Arc::make_mut(&mut tbl)
-                .distinct_caches
-                .get_and_increment_next_id();
+            let definition = new_distinct_cache_definition(
+                &mut tbl,
+                cache_name,
+                columns,
+                max_cardinality,
+                max_age_seconds,
+            )?;
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::CreateDistinctCache(
-                    DistinctCacheDefinition {
-                        table_id: tbl.table_id,
-                        table_name: Arc::clone(&tbl.table_name),
-                        cache_id,
-                        cache_name,
-                        column_ids,
-                        max_cardinality,
-                        max_age_seconds,
-                    },
-                )],
+                vec![DatabaseCatalogOp::CreateDistinctCache(definition)],
             ))
         })
         .await
@@ -659,101 +1486,19 @@ impl Catalog {
             let Some(mut tbl) = db.table_definition(table_name) else {
                 return Err(CatalogError::NotFound);
             };
-
-            fn is_valid_last_cache_key_col(def: &ColumnDefinition) -> bool {
-                matches!(
-                    def.data_type,
-                    InfluxColumnType::Tag
-                        | InfluxColumnType::Field(
-                            InfluxFieldType::String
-                                | InfluxFieldType::Integer
-                                | InfluxFieldType::UInteger
-                                | InfluxFieldType::Boolean
-                        ),
-                )
-            }
-
-            let (key_ids, key_names) = if let Some(key_columns) = key_columns {
-                key_columns
-                    .iter()
-                    .map(|name| {
-                        tbl.column_definition(name.as_ref())
-                            .ok_or_else(|| {
-                                CatalogError::invalid_configuration(
-                                    format!(
-                                        "invalid key column provided: {name}",
-                                        name = name.as_ref()
-                                    )
-                                    .as_str(),
-                                )
-                            })
-                            .and_then(|def| {
-                                if is_valid_last_cache_key_col(&def) {
-                                    Ok((def.id, name.as_ref().to_string()))
-                                } else {
-                                    Err(CatalogError::InvalidLastCacheKeyColumnType)
-                                }
-                            })
-                    })
-                    .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?
-            } else {
-                tbl.series_key
-                    .iter()
-                    .map(|id| {
-                        tbl.column_definition_by_id(id)
-                            .expect("column id in series key should be valid")
-                    })
-                    .map(|def| Ok((def.id, def.name.to_string())))
-                    .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?
-            };
-
-            let value_columns = if let Some(value_columns) = value_columns {
-                let columns = value_columns
-                    .iter()
-                    .map(|name| {
-                        tbl.column_definition(name.as_ref())
-                            .map(|def| def.id)
-                            .ok_or_else(|| {
-                                CatalogError::invalid_configuration(
-                                    format!(
-                                        "invalid value column provided: {name}",
-                                        name = name.as_ref()
-                                    )
-                                    .as_str(),
-                                )
-                            })
-                    })
-                    .collect::<Result<Vec<ColumnId>>>()?;
-                LastCacheValueColumnsDef::Explicit { columns }
-            } else {
-                LastCacheValueColumnsDef::AllNonKeyColumns
-            };
-
-            let cache_name = cache_name.map(Arc::from).unwrap_or_else(|| {
-                format!("{table_name}_{cols}_last_cache", cols = key_names.join("_"))
-                    .as_str()
-                    .into()
-            });
-            if tbl.last_caches.contains_name(&cache_name) {
-                return Err(CatalogError::AlreadyExists);
-            }
-            let cache_id = Arc::make_mut(&mut tbl)
-                .last_caches
-                .get_and_increment_next_id();
+            let definition = new_last_cache_definition(
+                &mut tbl,
+                cache_name,
+                key_columns,
+                value_columns,
+                count,
+                ttl,
+            )?;
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::CreateLastCache(LastCacheDefinition {
-                    table_id: tbl.table_id,
-                    table: Arc::clone(&tbl.table_name),
-                    id: cache_id,
-                    name: cache_name,
-                    key_columns: key_ids,
-                    value_columns,
-                    count,
-                    ttl,
-                })],
+                vec![DatabaseCatalogOp::CreateLastCache(definition)],
             ))
         })
         .await
@@ -813,6 +1558,7 @@ impl Catalog {
             if db.processing_engine_triggers.contains_name(trigger_name) {
                 return Err(CatalogError::AlreadyExists);
             }
+            self.check_trigger_specification(&trigger)?;
             let trigger_id = Arc::make_mut(&mut db)
                 .processing_engine_triggers
                 .get_and_increment_next_id();
@@ -836,147 +1582,1135 @@ impl Catalog {
         .await
     }
 
-    pub async fn delete_processing_engine_trigger(
+    pub async fn delete_processing_engine_trigger(
+        &self,
+        db_name: &str,
+        trigger_name: &str,
+        force: bool,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, trigger_name, "delete processing engine trigger");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if !trigger.disabled && !force {
+                return Err(CatalogError::ProcessingEngineTriggerRunning {
+                    trigger_name: trigger.trigger_name.to_string(),
+                });
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::DeleteTrigger(DeleteTriggerLog {
+                    trigger_id: trigger.trigger_id,
+                    trigger_name: Arc::clone(&trigger.trigger_name),
+                    force,
+                })],
+            ))
+        })
+        .await
+    }
+
+    pub async fn enable_processing_engine_trigger(
+        &self,
+        db_name: &str,
+        trigger_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, trigger_name, "enable processing engine trigger");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if !trigger.disabled {
+                return Err(CatalogError::TriggerAlreadyEnabled);
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::EnableTrigger(TriggerIdentifier {
+                    db_id: db.id,
+                    db_name: Arc::clone(&db.name),
+                    trigger_id: trigger.trigger_id,
+                    trigger_name: Arc::clone(&trigger.trigger_name),
+                })],
+            ))
+        })
+        .await
+    }
+
+    pub async fn disable_processing_engine_trigger(
+        &self,
+        db_name: &str,
+        trigger_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, trigger_name, "disable processing engine trigger");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if trigger.disabled {
+                return Err(CatalogError::TriggerAlreadyDisabled);
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::DisableTrigger(TriggerIdentifier {
+                    db_id: db.id,
+                    db_name: Arc::clone(&db.name),
+                    trigger_id: trigger.trigger_id,
+                    trigger_name: Arc::clone(&trigger.trigger_name),
+                })],
+            ))
+        })
+        .await
+    }
+
+    /// Disable every trigger in a database that is not already disabled, in a single catalog
+    /// batch
+    ///
+    /// Returns the number of triggers that were disabled, which is zero if they all were already.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database doesn't exist
+    pub async fn disable_all_triggers(&self, db_name: &str) -> Result<usize> {
+        info!(db_name, "disable all processing engine triggers");
+        self.set_all_triggers_disabled(db_name, true).await
+    }
+
+    /// Enable every trigger in a database that is not already enabled, in a single catalog batch
+    ///
+    /// Returns the number of triggers that were enabled, which is zero if they all were already.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database doesn't exist
+    pub async fn enable_all_triggers(&self, db_name: &str) -> Result<usize> {
+        info!(db_name, "enable all processing engine triggers");
+        self.set_all_triggers_disabled(db_name, false).await
+    }
+
+    async fn set_all_triggers_disabled(&self, db_name: &str, disabled: bool) -> Result<usize> {
+        let already_set = || {
+            if disabled {
+                CatalogError::TriggerAlreadyDisabled
+            } else {
+                CatalogError::TriggerAlreadyEnabled
+            }
+        };
+        let result = self
+            .catalog_update_with_retry(|| {
+                let Some(db) = self.db_schema(db_name) else {
+                    return Err(CatalogError::NotFound);
+                };
+                let ops: Vec<_> = db
+                    .processing_engine_triggers
+                    .resource_iter()
+                    .filter(|trigger| trigger.disabled != disabled)
+                    .map(|trigger| {
+                        let identifier = TriggerIdentifier {
+                            db_id: db.id,
+                            db_name: Arc::clone(&db.name),
+                            trigger_id: trigger.trigger_id,
+                            trigger_name: Arc::clone(&trigger.trigger_name),
+                        };
+                        if disabled {
+                            DatabaseCatalogOp::DisableTrigger(identifier)
+                        } else {
+                            DatabaseCatalogOp::EnableTrigger(identifier)
+                        }
+                    })
+                    .collect();
+                if ops.is_empty() {
+                    // nothing to do, or already done by another update:
+                    return Err(already_set());
+                }
+                Ok(CatalogBatch::database(
+                    self.time_provider.now().timestamp_nanos(),
+                    db.id,
+                    db.name(),
+                    ops,
+                ))
+            })
+            .await;
+        match result {
+            Ok(batch) => Ok(batch
+                .catalog_batch
+                .as_database()
+                .map_or(0, |batch| batch.ops.len())),
+            Err(CatalogError::TriggerAlreadyDisabled | CatalogError::TriggerAlreadyEnabled) => {
+                Ok(0)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn delete_token(&self, token_name: &str) -> Result<OrderedCatalogBatch> {
+        info!(token_name, "delete token");
+
+        if token_name == DEFAULT_OPERATOR_TOKEN_NAME {
+            return Err(CatalogError::CannotDeleteOperatorToken);
+        }
+
+        self.catalog_update_with_retry(|| {
+            if !self.inner.read().tokens.repo().contains_name(token_name) {
+                // maybe deleted by another node or genuinely not present
+                return Err(CatalogError::NotFound);
+            }
+
+            Ok(CatalogBatch::Token(TokenBatch {
+                time_ns: self.time_provider.now().timestamp_nanos(),
+                ops: vec![TokenCatalogOp::DeleteToken(DeleteTokenDetails {
+                    token_name: token_name.to_owned(),
+                })],
+            }))
+        })
+        .await
+    }
+
+    /// Remove permissions from a token
+    ///
+    /// Each permission in `remove` strips its actions from any of the token's permissions for the
+    /// same resource; permissions for other resources are left as they are. Permissions left
+    /// without any actions are dropped. This never grants the token anything it did not already
+    /// have. The operator token cannot be restricted.
+    pub async fn restrict_token(
+        &self,
+        token_name: &str,
+        remove: Vec<Permission>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(token_name, ?remove, "restrict token");
+
+        if token_name == DEFAULT_OPERATOR_TOKEN_NAME {
+            return Err(CatalogError::CannotModifyOperatorToken);
+        }
+
+        self.catalog_update_with_retry(|| {
+            let Some(token_info) = self.inner.read().tokens.repo().get_by_name(token_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let permissions = token_info
+                .permissions
+                .iter()
+                .filter_map(|permission| {
+                    remove
+                        .iter()
+                        .try_fold(permission.clone(), |permission, removed| {
+                            permission.without(removed)
+                        })
+                })
+                .collect();
+
+            Ok(CatalogBatch::Token(TokenBatch {
+                time_ns: self.time_provider.now().timestamp_nanos(),
+                ops: vec![TokenCatalogOp::UpdateTokenPermissions(
+                    UpdateTokenPermissionsDetails {
+                        token_id: token_info.id,
+                        permissions,
+                        updated_at: self.time_provider.now().timestamp_millis(),
+                    },
+                )],
+            }))
+        })
+        .await
+    }
+
+    /// Disable a token, so that it can no longer be used to authenticate
+    ///
+    /// The token is kept in the catalog, along with its permissions, and can be re-enabled with
+    /// [`Catalog::enable_token`]. The operator token cannot be disabled.
+    pub async fn disable_token(&self, token_name: &str) -> Result<OrderedCatalogBatch> {
+        info!(token_name, "disable token");
+        self.set_token_disabled(token_name, true).await
+    }
+
+    /// Re-enable a token that was disabled with [`Catalog::disable_token`]
+    pub async fn enable_token(&self, token_name: &str) -> Result<OrderedCatalogBatch> {
+        info!(token_name, "enable token");
+        self.set_token_disabled(token_name, false).await
+    }
+
+    async fn set_token_disabled(
+        &self,
+        token_name: &str,
+        disabled: bool,
+    ) -> Result<OrderedCatalogBatch> {
+        if token_name == DEFAULT_OPERATOR_TOKEN_NAME {
+            return Err(CatalogError::CannotModifyOperatorToken);
+        }
+
+        self.catalog_update_with_retry(|| {
+            let Some(token_info) = self.inner.read().tokens.repo().get_by_name(token_name) else {
+                return Err(CatalogError::NotFound);
+            };
+
+            Ok(CatalogBatch::Token(TokenBatch {
+                time_ns: self.time_provider.now().timestamp_nanos(),
+                ops: vec![TokenCatalogOp::SetTokenDisabled(SetTokenDisabledDetails {
+                    token_id: token_info.id,
+                    disabled,
+                    updated_at: self.time_provider.now().timestamp_millis(),
+                })],
+            }))
+        })
+        .await
+    }
+
+    /// Change when a token expires, without changing the token itself
+    ///
+    /// The new expiry is `new_expiry_secs` after the token was created, which is consistent with
+    /// how the expiry is set when a token is created; pass `None` for the token to never expire.
+    /// The operator token cannot be modified.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If there is no token named `token_name`
+    /// * `CatalogError::AlreadyExists` - If the token already has the requested expiry, in which
+    ///   case the catalog is not updated
+    pub async fn update_token_expiry(
+        &self,
+        token_name: &str,
+        new_expiry_secs: Option<u64>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(token_name, ?new_expiry_secs, "update token expiry");
+
+        if token_name == DEFAULT_OPERATOR_TOKEN_NAME {
+            return Err(CatalogError::CannotModifyOperatorToken);
+        }
+
+        self.catalog_update_with_retry(|| {
+            let Some(token_info) = self.inner.read().tokens.repo().get_by_name(token_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let expiry = new_expiry_secs
+                .map(|secs| {
+                    secs.checked_mul(1_000)
+                        .and_then(|millis| i64::try_from(millis).ok())
+                        .and_then(|millis| token_info.created_at.checked_add(millis))
+                        .ok_or_else(|| {
+                            CatalogError::invalid_configuration("token expiry is too far away")
+                        })
+                })
+                .transpose()?;
+            if expiry == token_info.maybe_expiry_millis() {
+                return Err(CatalogError::AlreadyExists);
+            }
+
+            Ok(CatalogBatch::Token(TokenBatch {
+                time_ns: self.time_provider.now().timestamp_nanos(),
+                ops: vec![TokenCatalogOp::UpdateTokenExpiry(
+                    UpdateTokenExpiryDetails {
+                        token_id: token_info.id,
+                        expiry,
+                        updated_at: self.time_provider.now().timestamp_millis(),
+                    },
+                )],
+            }))
+        })
+        .await
+    }
+
+    /// Give a token a new name
+    ///
+    /// The token keeps its id, secret, permissions and expiry, so clients using the token are not
+    /// affected. The operator token cannot be renamed.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If there is no token named `old_name`
+    /// * `CatalogError::TokenNameAlreadyExists` - If a token named `new_name` already exists
+    pub async fn rename_token(
+        &self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(old_name, new_name, "rename token");
+
+        if old_name == DEFAULT_OPERATOR_TOKEN_NAME {
+            return Err(CatalogError::CannotModifyOperatorToken);
+        }
+
+        self.catalog_update_with_retry(|| {
+            let inner = self.inner.read();
+            let Some(token_id) = inner.tokens.repo().name_to_id(old_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if new_name == DEFAULT_OPERATOR_TOKEN_NAME
+                || inner.tokens.repo().contains_name(new_name)
+            {
+                return Err(CatalogError::TokenNameAlreadyExists(new_name.to_owned()));
+            }
+
+            Ok(CatalogBatch::Token(TokenBatch {
+                time_ns: self.time_provider.now().timestamp_nanos(),
+                ops: vec![TokenCatalogOp::RenameToken(RenameTokenDetails {
+                    token_id,
+                    new_name: Arc::from(new_name),
+                    updated_at: self.time_provider.now().timestamp_millis(),
+                })],
+            }))
+        })
+        .await
+    }
+
+    pub async fn set_retention_period_for_database(
+        &self,
+        db_name: &str,
+        duration: Duration,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(
+            db_name,
+            duration_ns = duration.as_nanos(),
+            "create new retention policy"
+        );
+        let Some(db) = self.db_schema(db_name) else {
+            return Err(CatalogError::NotFound);
+        };
+        self.check_min_retention(RetentionPeriod::Duration(duration))?;
+        let retention_period = self.enforce_max_retention(RetentionPeriod::Duration(duration))?;
+        self.catalog_update_with_retry(|| {
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::SetRetentionPeriod(
+                    SetRetentionPeriodLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                        retention_period,
+                    },
+                )],
+            ))
+        })
+        .await
+    }
+
+    pub async fn clear_retention_period_for_database(
+        &self,
+        db_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, "delete retention policy");
+        let Some(db) = self.db_schema(db_name) else {
+            return Err(CatalogError::NotFound);
+        };
+        self.enforce_max_retention(RetentionPeriod::Indefinite)?;
+        self.catalog_update_with_retry(|| {
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::ClearRetentionPeriod(
+                    ClearRetentionPeriodLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                    },
+                )],
+            ))
+        })
+        .await
+    }
+
+    /// Set the retention period for a table
+    ///
+    /// This applies in addition to the retention period of the table's database, so data is
+    /// removed according to whichever of the two is shorter.
+    pub async fn set_retention_period_for_table(
+        &self,
+        db_name: &str,
+        table_name: &str,
+        duration: Duration,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(
+            db_name,
+            table_name,
+            duration_ns = duration.as_nanos(),
+            "set table retention period"
+        );
+        self.check_min_retention(RetentionPeriod::Duration(duration))?;
+        let RetentionPeriod::Duration(duration) =
+            self.enforce_max_retention(RetentionPeriod::Duration(duration))?
+        else {
+            unreachable!("a duration is never made indefinite");
+        };
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(tbl) = db.table_definition(table_name) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::SetRetentionPeriodForTable(
+                    SetRetentionPeriodForTableLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                        table_name: Arc::clone(&tbl.table_name),
+                        table_id: tbl.table_id,
+                        retention_period: duration,
+                    },
+                )],
+            ))
+        })
+        .await
+    }
+
+    /// Clear the retention period for a table, so that only the retention period of its database
+    /// applies
+    pub async fn clear_retention_period_for_table(
+        &self,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, table_name, "clear table retention period");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(tbl) = db.table_definition(table_name) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::ClearRetentionPeriodForTable(
+                    ClearRetentionPeriodForTableLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                        table_name: Arc::clone(&tbl.table_name),
+                        table_id: tbl.table_id,
+                    },
+                )],
+            ))
+        })
+        .await
+    }
+
+    /// Apply the given retention period to each of the named databases in a single catalog batch
+    ///
+    /// The update is all-or-nothing: if the period is not permitted, or any database does not
+    /// exist, this fails and no retention periods are changed. A name given more than once is only
+    /// applied once, and databases that already have the given retention period are left as-is.
+    /// Returns the number of databases that were changed.
+    pub async fn set_retention_for_databases(
+        &self,
+        names: &[&str],
+        period: RetentionPeriod,
+    ) -> Result<usize> {
+        info!(?names, ?period, "set retention period for databases");
+        self.check_min_retention(period)?;
+        let period = self.enforce_max_retention(period)?;
+        let result = self
+            .catalog_update_with_retry(|| {
+                let time_ns = self.time_provider.now().timestamp_nanos();
+                let mut seen = HashSet::new();
+                let mut batches = Vec::new();
+                for name in names.iter().filter(|name| seen.insert(**name)) {
+                    let db = self.db_schema(name).ok_or(CatalogError::NotFound)?;
+                    if db.retention_period == period {
+                        continue;
+                    }
+                    let op = match period {
+                        RetentionPeriod::Duration(_) => {
+                            DatabaseCatalogOp::SetRetentionPeriod(SetRetentionPeriodLog {
+                                database_name: db.name(),
+                                database_id: db.id,
+                                retention_period: period,
+                            })
+                        }
+                        RetentionPeriod::Indefinite => {
+                            DatabaseCatalogOp::ClearRetentionPeriod(ClearRetentionPeriodLog {
+                                database_name: db.name(),
+                                database_id: db.id,
+                            })
+                        }
+                    };
+                    batches.push(DatabaseBatch {
+                        time_ns,
+                        database_id: db.id,
+                        database_name: db.name(),
+                        ops: vec![op],
+                    });
+                }
+                if batches.is_empty() {
+                    // an empty batch would not change the catalog, so nothing is written:
+                    return Err(CatalogError::AlreadyExists);
+                }
+                Ok(CatalogBatch::multi_database(time_ns, batches))
+            })
+            .await;
+        match result {
+            // each changed database has a single op in the batch:
+            Ok(ordered_batch) => Ok(ordered_batch.batch().n_ops()),
+            Err(CatalogError::AlreadyExists) => Ok(0),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Set or clear the description of a database
+    pub async fn set_database_description(
+        &self,
+        db_name: &str,
+        description: Option<&str>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, ?description, "set database description");
+        let Some(db) = self.db_schema(db_name) else {
+            return Err(CatalogError::NotFound);
+        };
+        self.catalog_update_with_retry(|| {
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::SetDatabaseDescription(
+                    SetDatabaseDescriptionLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                        description: description.map(Arc::from),
+                    },
+                )],
+            ))
+        })
+        .await
+    }
+
+    /// Rename a database, keeping its id, tables, and all other configuration
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If no database is named `old_name`
+    /// * `CatalogError::AlreadyExists` - If a database named `new_name` already exists
+    /// * `CatalogError::CannotRenameInternalDatabase` - If attempting to rename the internal
+    ///   database
+    /// * `CatalogError::InvalidDatabaseName` - If `new_name` is not a valid database name
+    /// * `CatalogError::CannotRenameDatabaseWithTriggers` - If the database has processing engine
+    ///   triggers, which refer to the database by name
+    pub async fn rename_database(
+        &self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(old_name, new_name, "rename database");
+        if old_name == INTERNAL_DB_NAME {
+            return Err(CatalogError::CannotRenameInternalDatabase);
+        }
+        Self::validate_database_name(new_name)?;
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(old_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if self.db_name_to_id(new_name).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if let Some(trigger) = db.processing_engine_triggers.resource_iter().next() {
+                return Err(CatalogError::CannotRenameDatabaseWithTriggers {
+                    database_name: db.name(),
+                    trigger_name: Arc::clone(&trigger.trigger_name),
+                });
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::RenameDatabase(RenameDatabaseLog {
+                    database_id: db.id,
+                    old_name: db.name(),
+                    new_name: Arc::from(new_name),
+                })],
+            ))
+        })
+        .await
+    }
+
+    /// Rename a table that has not been deleted, keeping its id, columns, series key, and caches
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database or table does not exist
+    /// * `CatalogError::AlreadyExists` - If the database already has a table named `new_table`
+    /// * `CatalogError::CannotRenameTableWithTriggers` - If a processing engine trigger is set to
+    ///   run on writes to the table, as the trigger refers to the table by name
+    pub async fn rename_table(
+        &self,
+        db_name: &str,
+        old_table: &str,
+        new_table: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, old_table, new_table, "rename table");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(table_def) = db.table_definition(old_table).filter(|t| !t.deleted) else {
+                return Err(CatalogError::NotFound);
+            };
+            if db.table_name_to_id(new_table).is_some() {
+                return Err(CatalogError::AlreadyExists);
+            }
+            if let Some(trigger) = db
+                .processing_engine_triggers
+                .resource_iter()
+                .find(|trigger| {
+                    matches!(
+                        &trigger.trigger,
+                        TriggerSpecificationDefinition::SingleTableWalWrite { table_name }
+                            if table_name.as_str() == table_def.table_name.as_ref()
+                    )
+                })
+            {
+                return Err(CatalogError::CannotRenameTableWithTriggers {
+                    table_name: Arc::clone(&table_def.table_name),
+                    trigger_name: Arc::clone(&trigger.trigger_name),
+                });
+            }
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::RenameTable(RenameTableLog {
+                    database_id: db.id,
+                    database_name: db.name(),
+                    table_id: table_def.table_id,
+                    old_name: Arc::clone(&table_def.table_name),
+                    new_name: Arc::from(new_table),
+                })],
+            ))
+        })
+        .await
+    }
+
+    /// Drop a column from a table that has not been deleted
+    ///
+    /// If the column is part of the table's series key it is removed from the series key, and the
+    /// sort key is recomputed. The id of a dropped column is not reused.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database, table, or column does not exist
+    /// * `CatalogError::CannotDropTimeColumn` - If attempting to drop the `time` column
+    /// * `CatalogError::InvalidConfiguration` - If the column is used by a last or distinct cache
+    pub async fn drop_column(
+        &self,
+        db_name: &str,
+        table_name: &str,
+        column_name: &str,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, table_name, column_name, "drop column");
+        self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(table_def) = db.table_definition(table_name).filter(|t| !t.deleted) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(column_id) = table_def.column_name_to_id(column_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            table_def.check_drop_column(column_id)?;
+            Ok(CatalogBatch::database(
+                self.time_provider.now().timestamp_nanos(),
+                db.id,
+                db.name(),
+                vec![DatabaseCatalogOp::DropColumn(DropColumnLog {
+                    database_id: db.id,
+                    database_name: db.name(),
+                    table_id: table_def.table_id,
+                    table_name: Arc::clone(&table_def.table_name),
+                    column_id,
+                    column_name: Arc::from(column_name),
+                })],
+            ))
+        })
+        .await
+    }
+
+    /// Change the order of the columns in a table's series key
+    ///
+    /// `new_order` must contain each of the columns currently in the series key exactly once. The
+    /// sort key is recomputed from the new series key order.
+    ///
+    /// This only affects files persisted after the change; parquet files that have already been
+    /// written keep the sort order they were written with, and are not rewritten.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database or table does not exist
+    /// * `CatalogError::InvalidConfiguration` - If `new_order` is not a reordering of the series
+    ///   key
+    /// * `CatalogError::AlreadyExists` - If the series key is already in the given order
+    pub async fn reorder_series_key(
+        &self,
+        db_name: &str,
+        table_name: &str,
+        new_order: Vec<ColumnId>,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(db_name, table_name, ?new_order, "reorder series key");
+        let batch = self
+            .catalog_update_with_retry(|| {
+                let Some(db) = self.db_schema(db_name) else {
+                    return Err(CatalogError::NotFound);
+                };
+                let Some(table_def) = db.table_definition(table_name).filter(|t| !t.deleted) else {
+                    return Err(CatalogError::NotFound);
+                };
+                table_def.check_series_key_order(&new_order)?;
+                if table_def.series_key == new_order {
+                    return Err(CatalogError::AlreadyExists);
+                }
+                Ok(CatalogBatch::database(
+                    self.time_provider.now().timestamp_nanos(),
+                    db.id,
+                    db.name(),
+                    vec![DatabaseCatalogOp::ReorderSeriesKey(ReorderSeriesKeyLog {
+                        database_id: db.id,
+                        database_name: db.name(),
+                        table_id: table_def.table_id,
+                        table_name: Arc::clone(&table_def.table_name),
+                        series_key: new_order.clone(),
+                    })],
+                ))
+            })
+            .await?;
+        warn!(
+            db_name,
+            table_name,
+            "series key reordered, this only applies to newly persisted files, existing parquet \
+            files keep their original sort order"
+        );
+        Ok(batch)
+    }
+
+    /// Change the type of a field column to a wider type
+    ///
+    /// Only the following changes are permitted:
+    ///
+    /// * integer or unsigned integer to float, as is done by type promotion on write
+    /// * integer, unsigned integer, float, or boolean to string
+    ///
+    /// The catalog does not know what data has been written to the table, so every change
+    /// requires `force`, which the caller should only give when it knows the table holds no data
+    /// for the column, or accepts that existing data is converted. Values for the column in the
+    /// write buffer and last caches are converted to the new type as new values are written, and
+    /// parquet files persisted before the change are cast to the new type when queried.
+    ///
+    /// # Errors
+    ///
+    /// * `CatalogError::NotFound` - If the database, table, or column does not exist
+    /// * `CatalogError::IncompatibleColumnTypeChange` - If the change is not one of the above
+    /// * `CatalogError::InvalidConfiguration` - If `force` was not given, or if the column is a
+    ///   last cache key column
+    /// * `CatalogError::AlreadyExists` - If the column already has the new type
+    pub async fn alter_column_type(
+        &self,
+        db_name: &str,
+        table_name: &str,
+        column_name: &str,
+        new_type: FieldDataType,
+        force: bool,
+    ) -> Result<OrderedCatalogBatch> {
+        info!(
+            db_name,
+            table_name,
+            column_name,
+            ?new_type,
+            force,
+            "alter column type"
+        );
+        let batch = self
+            .catalog_update_with_retry(|| {
+                let Some(db) = self.db_schema(db_name) else {
+                    return Err(CatalogError::NotFound);
+                };
+                let Some(table_def) = db.table_definition(table_name).filter(|t| !t.deleted) else {
+                    return Err(CatalogError::NotFound);
+                };
+                let Some(column) = table_def.column_definition(column_name) else {
+                    return Err(CatalogError::NotFound);
+                };
+                let attempted = InfluxColumnType::from(new_type);
+                if column.data_type == attempted {
+                    return Err(CatalogError::AlreadyExists);
+                }
+                if !is_column_type_change(column.data_type, attempted) {
+                    return Err(CatalogError::IncompatibleColumnTypeChange {
+                        column_name: column_name.to_string(),
+                        existing: column.data_type,
+                        attempted,
+                    });
+                }
+                if !force {
+                    return Err(CatalogError::invalid_configuration(format!(
+                        "changing column '{column_name}' from {} to {attempted} requires force, as \
+                        existing data for the column is converted to the new type",
+                        column.data_type
+                    )));
+                }
+                // the values of last cache keys are not converted:
+                if let Some(cache) = table_def
+                    .last_caches
+                    .resource_iter()
+                    .find(|cache| cache.key_columns.contains(&column.id))
+                {
+                    return Err(CatalogError::invalid_configuration(format!(
+                        "cannot change column '{column_name}' to {attempted} because it is a key \
+                        column of the last cache '{cache_name}'",
+                        cache_name = cache.name
+                    )));
+                }
+                Ok(CatalogBatch::database(
+                    self.time_provider.now().timestamp_nanos(),
+                    db.id,
+                    db.name(),
+                    vec![DatabaseCatalogOp::AlterColumnType(AlterColumnTypeLog {
+                        database_id: db.id,
+                        database_name: db.name(),
+                        table_id: table_def.table_id,
+                        table_name: Arc::clone(&table_def.table_name),
+                        column_id: column.id,
+                        column_type: new_type,
+                    })],
+                ))
+            })
+            .await?;
+        warn!(
+            db_name,
+            table_name,
+            column_name,
+            ?new_type,
+            "column type changed, existing data for the column is converted to the new type"
+        );
+        Ok(batch)
+    }
+
+    /// Set or clear the placeholder that queries render in place of missing tag values for a
+    /// database
+    pub async fn set_null_tag_placeholder(
         &self,
         db_name: &str,
-        trigger_name: &str,
-        force: bool,
+        placeholder: Option<&str>,
     ) -> Result<OrderedCatalogBatch> {
-        info!(db_name, trigger_name, "delete processing engine trigger");
+        info!(db_name, ?placeholder, "set null tag placeholder");
+        let Some(db) = self.db_schema(db_name) else {
+            return Err(CatalogError::NotFound);
+        };
         self.catalog_update_with_retry(|| {
-            let Some(db) = self.db_schema(db_name) else {
-                return Err(CatalogError::NotFound);
-            };
-            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
-                return Err(CatalogError::NotFound);
-            };
-            if !trigger.disabled && !force {
-                return Err(CatalogError::ProcessingEngineTriggerRunning {
-                    trigger_name: trigger.trigger_name.to_string(),
-                });
-            }
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::DeleteTrigger(DeleteTriggerLog {
-                    trigger_id: trigger.trigger_id,
-                    trigger_name: Arc::clone(&trigger.trigger_name),
-                    force,
-                })],
+                vec![DatabaseCatalogOp::SetNullTagPlaceholder(
+                    SetNullTagPlaceholderLog {
+                        database_name: db.name(),
+                        database_id: db.id,
+                        placeholder: placeholder.map(Arc::from),
+                    },
+                )],
             ))
         })
         .await
     }
 
-    pub async fn enable_processing_engine_trigger(
+    /// Set or clear the primary partition tag of a table
+    ///
+    /// The tag must be a column in the table's series key.
+    pub async fn set_primary_partition_tag(
         &self,
         db_name: &str,
-        trigger_name: &str,
+        table_name: &str,
+        tag_name: Option<&str>,
     ) -> Result<OrderedCatalogBatch> {
-        info!(db_name, trigger_name, "enable processing engine trigger");
+        info!(db_name, table_name, ?tag_name, "set primary partition tag");
         self.catalog_update_with_retry(|| {
             let Some(db) = self.db_schema(db_name) else {
                 return Err(CatalogError::NotFound);
             };
-            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
-                return Err(CatalogError::NotFound);
+            let Some(tbl) = db.table_definition(table_name) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            let column_id = match tag_name {
+                Some(tag_name) => {
+                    let Some(col) = tbl.column_definition(tag_name) else {
+                        return Err(CatalogError::NotFound);
+                    };
+                    if col.data_type != InfluxColumnType::Tag {
+                        return Err(CatalogError::InvalidColumnType {
+                            column_name: Arc::clone(&col.name),
+                            expected: InfluxColumnType::Tag,
+                            got: col.data_type,
+                        });
+                    }
+                    if !tbl.series_key.contains(&col.id) {
+                        return Err(CatalogError::invalid_configuration(format!(
+                            "column '{tag_name}' is not part of the series key for table \
+                            '{table_name}'"
+                        )));
+                    }
+                    Some(col.id)
+                }
+                None => None,
             };
-            if !trigger.disabled {
-                return Err(CatalogError::TriggerAlreadyEnabled);
-            }
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::EnableTrigger(TriggerIdentifier {
-                    db_id: db.id,
-                    db_name: Arc::clone(&db.name),
-                    trigger_id: trigger.trigger_id,
-                    trigger_name: Arc::clone(&trigger.trigger_name),
-                })],
+                vec![DatabaseCatalogOp::SetPrimaryPartitionTag(
+                    SetPrimaryPartitionTagLog {
+                        database_id: db.id,
+                        database_name: db.name(),
+                        table_id: tbl.table_id,
+                        table_name: Arc::clone(&tbl.table_name),
+                        column_id,
+                    },
+                )],
             ))
         })
         .await
     }
 
-    pub async fn disable_processing_engine_trigger(
+    /// Set or clear the set of values that a tag column is allowed to hold
+    ///
+    /// Only tag columns can have their values restricted. The set of values must not be empty;
+    /// pass `None` to lift the restriction.
+    pub async fn set_column_allowed_values(
         &self,
         db_name: &str,
-        trigger_name: &str,
+        table_name: &str,
+        column_name: &str,
+        allowed_values: Option<&[&str]>,
     ) -> Result<OrderedCatalogBatch> {
-        info!(db_name, trigger_name, "disable processing engine trigger");
+        info!(
+            db_name,
+            table_name,
+            column_name,
+            ?allowed_values,
+            "set column allowed values"
+        );
+        let allowed_values = allowed_values.map(|values| {
+            values
+                .iter()
+                .map(|v| Arc::from(*v))
+                .collect::<BTreeSet<_>>()
+        });
+        if allowed_values
+            .as_ref()
+            .is_some_and(|values| values.is_empty())
+        {
+            return Err(CatalogError::invalid_configuration(
+                "the set of allowed values cannot be empty",
+            ));
+        }
         self.catalog_update_with_retry(|| {
             let Some(db) = self.db_schema(db_name) else {
                 return Err(CatalogError::NotFound);
             };
-            let Some(trigger) = db.processing_engine_triggers.get_by_name(trigger_name) else {
+            let Some(tbl) = db.table_definition(table_name) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            let Some(col) = tbl.column_definition(column_name) else {
                 return Err(CatalogError::NotFound);
             };
-            if trigger.disabled {
-                return Err(CatalogError::TriggerAlreadyDisabled);
+            if col.data_type != InfluxColumnType::Tag {
+                return Err(CatalogError::InvalidColumnType {
+                    column_name: Arc::clone(&col.name),
+                    expected: InfluxColumnType::Tag,
+                    got: col.data_type,
+                });
             }
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::DisableTrigger(TriggerIdentifier {
-                    db_id: db.id,
-                    db_name: Arc::clone(&db.name),
-                    trigger_id: trigger.trigger_id,
-                    trigger_name: Arc::clone(&trigger.trigger_name),
-                })],
+                vec![DatabaseCatalogOp::SetColumnAllowedValues(
+                    SetColumnAllowedValuesLog {
+                        database_id: db.id,
+                        database_name: db.name(),
+                        table_id: tbl.table_id,
+                        table_name: Arc::clone(&tbl.table_name),
+                        column_id: col.id,
+                        allowed_values: allowed_values.clone(),
+                    },
+                )],
             ))
         })
         .await
     }
 
-    pub async fn delete_token(&self, token_name: &str) -> Result<OrderedCatalogBatch> {
-        info!(token_name, "delete token");
-
-        if token_name == DEFAULT_OPERATOR_TOKEN_NAME {
-            return Err(CatalogError::CannotDeleteOperatorToken);
-        }
-
-        self.catalog_update_with_retry(|| {
-            if !self.inner.read().tokens.repo().contains_name(token_name) {
-                // maybe deleted by another node or genuinely not present
-                return Err(CatalogError::NotFound);
-            }
-
-            Ok(CatalogBatch::Token(TokenBatch {
-                time_ns: self.time_provider.now().timestamp_nanos(),
-                ops: vec![TokenCatalogOp::DeleteToken(DeleteTokenDetails {
-                    token_name: token_name.to_owned(),
-                })],
-            }))
-        })
-        .await
-    }
-
-    pub async fn set_retention_period_for_database(
+    /// Set or clear the description of a column
+    ///
+    /// The description is for documentation only, and does not affect the table's schema.
+    pub async fn set_column_description(
         &self,
         db_name: &str,
-        duration: Duration,
+        table_name: &str,
+        column_name: &str,
+        description: Option<&str>,
     ) -> Result<OrderedCatalogBatch> {
         info!(
             db_name,
-            duration_ns = duration.as_nanos(),
-            "create new retention policy"
+            table_name,
+            column_name,
+            ?description,
+            "set column description"
         );
-        let Some(db) = self.db_schema(db_name) else {
-            return Err(CatalogError::NotFound);
-        };
         self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(tbl) = db.table_definition(table_name).filter(|t| !t.deleted) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            let Some(col) = tbl.column_definition(column_name) else {
+                return Err(CatalogError::ColumnNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::clone(&tbl.table_name),
+                    column_name: Arc::from(column_name),
+                });
+            };
+            if col.description.as_deref() == description {
+                return Err(CatalogError::AlreadyExists);
+            }
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::SetRetentionPeriod(
-                    SetRetentionPeriodLog {
-                        database_name: db.name(),
+                vec![DatabaseCatalogOp::SetColumnDescription(
+                    SetColumnDescriptionLog {
                         database_id: db.id,
-                        retention_period: RetentionPeriod::Duration(duration),
+                        database_name: db.name(),
+                        table_id: tbl.table_id,
+                        table_name: Arc::clone(&tbl.table_name),
+                        column_id: col.id,
+                        description: description.map(Arc::from),
                     },
                 )],
             ))
@@ -984,25 +2718,52 @@ impl Catalog {
         .await
     }
 
-    pub async fn clear_retention_period_for_database(
+    /// Mark a field column as indexed, or clear the mark
+    ///
+    /// This is a hint to the storage layer that the field benefits from indexing, without it
+    /// becoming a tag in the series key. Only field columns can be marked as indexed.
+    pub async fn set_column_indexed(
         &self,
         db_name: &str,
+        table_name: &str,
+        column_name: &str,
+        indexed: bool,
     ) -> Result<OrderedCatalogBatch> {
-        info!(db_name, "delete retention policy");
-        let Some(db) = self.db_schema(db_name) else {
-            return Err(CatalogError::NotFound);
-        };
+        info!(
+            db_name,
+            table_name, column_name, indexed, "set column indexed"
+        );
         self.catalog_update_with_retry(|| {
+            let Some(db) = self.db_schema(db_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            let Some(tbl) = db.table_definition(table_name) else {
+                return Err(CatalogError::TableNotFound {
+                    db_name: db.name(),
+                    table_name: Arc::from(table_name),
+                });
+            };
+            let Some(col) = tbl.column_definition(column_name) else {
+                return Err(CatalogError::NotFound);
+            };
+            if !matches!(col.data_type, InfluxColumnType::Field(_)) {
+                return Err(CatalogError::invalid_configuration(format!(
+                    "only field columns can be marked as indexed, '{}' is a {}",
+                    col.name, col.data_type
+                )));
+            }
             Ok(CatalogBatch::database(
                 self.time_provider.now().timestamp_nanos(),
                 db.id,
                 db.name(),
-                vec![DatabaseCatalogOp::ClearRetentionPeriod(
-                    ClearRetentionPeriodLog {
-                        database_name: db.name(),
-                        database_id: db.id,
-                    },
-                )],
+                vec![DatabaseCatalogOp::SetColumnIndexed(SetColumnIndexedLog {
+                    database_id: db.id,
+                    database_name: db.name(),
+                    table_id: tbl.table_id,
+                    table_name: Arc::clone(&tbl.table_name),
+                    column_id: col.id,
+                    indexed,
+                })],
             ))
         })
         .await
@@ -1017,6 +2778,15 @@ impl Catalog {
     where
         F: Fn() -> Result<CatalogBatch>,
     {
+        // check before composing the batch, as that can allocate ids from the catalog:
+        self.check_writable()?;
+        // waiters on the queue are served in FIFO order, and only one update composes and applies
+        // its batch at a time, so that updates do not retry due to one another:
+        let _queued = if self.args.fair_write_permit {
+            Some(self.fair_write_queue.lock().await)
+        } else {
+            None
+        };
         // NOTE(trevor/catalog-refactor): should there be a limit number of retries, or use a
         // timeout somewhere?
         loop {
@@ -1024,7 +2794,7 @@ impl Catalog {
             let batch = batch_creator_fn()?;
             match self
                 .get_permit_and_verify_catalog_batch(batch, sequence)
-                .await
+                .await?
             {
                 Prompt::Success((ordered_batch, permit)) => {
                     match self
@@ -1035,8 +2805,7 @@ impl Catalog {
                         UpdatePrompt::Applied => {
                             self.apply_ordered_catalog_batch(&ordered_batch, &permit);
                             self.background_checkpoint(&ordered_batch);
-                            self.broadcast_update(ordered_batch.clone().into_batch())
-                                .await?;
+                            self.broadcast_update(ordered_batch.clone()).await?;
                             return Ok(ordered_batch);
                         }
                     }
@@ -1130,10 +2899,28 @@ impl Catalog {
         Ok(())
     }
 
+    /// Persist the catalog as a checkpoint immediately, regardless of the checkpoint interval
+    ///
+    /// The optional `label` is stored in the checkpoint file to annotate why it was taken, e.g.,
+    /// `"pre-upgrade"`. It is purely informational and is available from
+    /// [`Catalog::checkpoint_label`], including after the catalog is reloaded from the checkpoint.
+    /// The label is kept on the checkpoints written afterwards at the checkpoint interval, until
+    /// the next forced checkpoint replaces it.
+    pub async fn force_checkpoint(&self, label: Option<String>) -> Result<()> {
+        self.check_writable()?;
+        // hold the permit so that no updates are applied while the checkpoint is written:
+        let _permit = CATALOG_WRITE_PERMIT.lock().await;
+        let mut snapshot = self.snapshot();
+        snapshot.label = label.clone();
+        self.store.overwrite_catalog_checkpoint(&snapshot).await?;
+        self.inner.write().checkpoint_label = label;
+        Ok(())
+    }
+
     /// Persist the catalog as a checkpoint in the background if we are at the _n_th sequence
     /// number.
     fn background_checkpoint(&self, ordered_batch: &OrderedCatalogBatch) {
-        if ordered_batch.sequence_number().get() % self.store.checkpoint_interval != 0 {
+        if ordered_batch.sequence_number().get() % self.store.checkpoint_interval() != 0 {
             return;
         }
         let snapshot = self.snapshot();
@@ -1148,14 +2935,182 @@ impl Catalog {
     }
 }
 
-impl From<Vec<CatalogBatch>> for CatalogUpdate {
-    fn from(batches: Vec<CatalogBatch>) -> Self {
+/// Validate the columns of a new distinct cache on `tbl` and build its definition
+///
+/// A new cache id is allocated on `tbl`, so that creating several caches on the same table
+/// definition gives each a unique id.
+fn new_distinct_cache_definition(
+    tbl: &mut Arc<TableDefinition>,
+    cache_name: Option<&str>,
+    columns: &[impl AsRef<str>],
+    max_cardinality: MaxCardinality,
+    max_age_seconds: MaxAge,
+) -> Result<DistinctCacheDefinition> {
+    let table_name = Arc::clone(&tbl.table_name);
+    if columns.is_empty() {
+        return Err(CatalogError::invalid_configuration(
+            "no columns provided when creating distinct cache",
+        ));
+    }
+
+    fn is_valid_distinct_cache_type(def: &ColumnDefinition) -> bool {
+        matches!(
+            def.data_type,
+            InfluxColumnType::Tag | InfluxColumnType::Field(InfluxFieldType::String),
+        )
+    }
+
+    let (column_ids, col_names) = columns
+        .iter()
+        .map(|name| {
+            tbl.column_definition(name.as_ref())
+                .ok_or_else(|| {
+                    CatalogError::invalid_configuration(
+                        format!("invalid column provided: {name}", name = name.as_ref()).as_str(),
+                    )
+                })
+                .and_then(|def| {
+                    if is_valid_distinct_cache_type(&def) {
+                        Ok((def.id, name.as_ref().to_string()))
+                    } else {
+                        Err(CatalogError::InvalidDistinctCacheColumnType)
+                    }
+                })
+        })
+        .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?;
+    let cache_name = cache_name.map(Arc::from).unwrap_or_else(|| {
+        format!(
+            "{table_name}_{cols}_distinct_cache",
+            cols = col_names.join("_")
+        )
+        .as_str()
+        .into()
+    });
+    if tbl.distinct_caches.contains_name(&cache_name) {
+        return Err(CatalogError::AlreadyExists);
+    }
+    let cache_id = Arc::make_mut(tbl)
+        .distinct_caches
+        .get_and_increment_next_id();
+    Ok(DistinctCacheDefinition {
+        table_id: tbl.table_id,
+        table_name,
+        cache_id,
+        cache_name,
+        column_ids,
+        max_cardinality,
+        max_age_seconds,
+    })
+}
+
+/// Validate the columns of a new last cache on `tbl` and build its definition
+///
+/// As with [`new_distinct_cache_definition`], a new cache id is allocated on `tbl`.
+fn new_last_cache_definition(
+    tbl: &mut Arc<TableDefinition>,
+    cache_name: Option<&str>,
+    key_columns: Option<&[impl AsRef<str>]>,
+    value_columns: Option<&[impl AsRef<str>]>,
+    count: LastCacheSize,
+    ttl: LastCacheTtl,
+) -> Result<LastCacheDefinition> {
+    let table_name = Arc::clone(&tbl.table_name);
+
+    fn is_valid_last_cache_key_col(def: &ColumnDefinition) -> bool {
+        matches!(
+            def.data_type,
+            InfluxColumnType::Tag
+                | InfluxColumnType::Field(
+                    InfluxFieldType::String
+                        | InfluxFieldType::Integer
+                        | InfluxFieldType::UInteger
+                        | InfluxFieldType::Boolean
+                ),
+        )
+    }
+
+    let (key_ids, key_names) = if let Some(key_columns) = key_columns {
+        key_columns
+            .iter()
+            .map(|name| {
+                tbl.column_definition(name.as_ref())
+                    .ok_or_else(|| {
+                        CatalogError::invalid_configuration(
+                            format!("invalid key column provided: {name}", name = name.as_ref())
+                                .as_str(),
+                        )
+                    })
+                    .and_then(|def| {
+                        if is_valid_last_cache_key_col(&def) {
+                            Ok((def.id, name.as_ref().to_string()))
+                        } else {
+                            Err(CatalogError::InvalidLastCacheKeyColumnType)
+                        }
+                    })
+            })
+            .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?
+    } else {
+        tbl.series_key
+            .iter()
+            .map(|id| {
+                tbl.column_definition_by_id(id)
+                    .expect("column id in series key should be valid")
+            })
+            .map(|def| Ok((def.id, def.name.to_string())))
+            .collect::<Result<(Vec<ColumnId>, Vec<String>)>>()?
+    };
+
+    let value_columns = if let Some(value_columns) = value_columns {
+        let columns = value_columns
+            .iter()
+            .map(|name| {
+                tbl.column_definition(name.as_ref())
+                    .map(|def| def.id)
+                    .ok_or_else(|| {
+                        CatalogError::invalid_configuration(
+                            format!(
+                                "invalid value column provided: {name}",
+                                name = name.as_ref()
+                            )
+                            .as_str(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<ColumnId>>>()?;
+        LastCacheValueColumnsDef::Explicit { columns }
+    } else {
+        LastCacheValueColumnsDef::AllNonKeyColumns
+    };
+
+    let cache_name = cache_name.map(Arc::from).unwrap_or_else(|| {
+        format!("{table_name}_{cols}_last_cache", cols = key_names.join("_"))
+            .as_str()
+            .into()
+    });
+    if tbl.last_caches.contains_name(&cache_name) {
+        return Err(CatalogError::AlreadyExists);
+    }
+    let cache_id = Arc::make_mut(tbl).last_caches.get_and_increment_next_id();
+    Ok(LastCacheDefinition {
+        table_id: tbl.table_id,
+        table: table_name,
+        id: cache_id,
+        name: cache_name,
+        key_columns: key_ids,
+        value_columns,
+        count,
+        ttl,
+    })
+}
+
+impl From<Vec<OrderedCatalogBatch>> for CatalogUpdate {
+    fn from(batches: Vec<OrderedCatalogBatch>) -> Self {
         Self { batches }
     }
 }
 
-impl From<CatalogBatch> for CatalogUpdate {
-    fn from(batch: CatalogBatch) -> Self {
+impl From<OrderedCatalogBatch> for CatalogUpdate {
+    fn from(batch: OrderedCatalogBatch) -> Self {
         Self {
             batches: vec![batch],
         }
@@ -1169,21 +3124,65 @@ enum UpdatePrompt {
 
 #[derive(Debug)]
 pub struct CatalogUpdate {
-    batches: Vec<CatalogBatch>,
+    batches: Vec<OrderedCatalogBatch>,
 }
 
 impl CatalogUpdate {
     pub(crate) fn batches(&self) -> impl Iterator<Item = &CatalogBatch> {
+        self.batches.iter().map(OrderedCatalogBatch::batch)
+    }
+
+    pub(crate) fn ordered_batches(&self) -> impl Iterator<Item = &OrderedCatalogBatch> {
         self.batches.iter()
     }
 }
 
+/// Produce a name of the form `<name>_<n>`, using the smallest `n` for which `exists` is false
+fn merged_name(name: &str, exists: impl Fn(&str) -> bool) -> Arc<str> {
+    (1..)
+        .map(|n| format!("{name}_{n}"))
+        .find(|candidate| !exists(candidate))
+        .expect("there should be an unused name")
+        .into()
+}
+
+/// Whether a token from an incoming catalog has a permission that is not limited to the databases
+/// it names, i.e., a wildcard or admin permission
+fn has_unscoped_permission(token: &TokenInfo) -> bool {
+    token.permissions.iter().any(|permission| {
+        !matches!(
+            permission.resource_identifier,
+            ResourceIdentifier::Database(_)
+        )
+    })
+}
+
+/// Remap the database ids in a permission from an incoming catalog to the ids of the databases
+/// they were imported as, dropping any that were not imported
+///
+/// Returns `None` if the permission no longer applies to any database.
+fn remap_permission(permission: &Permission, db_ids: &HashMap<DbId, DbId>) -> Option<Permission> {
+    let ResourceIdentifier::Database(ids) = &permission.resource_identifier else {
+        return Some(permission.clone());
+    };
+    let ids = ids
+        .iter()
+        .filter_map(|id| db_ids.get(id).copied())
+        .collect::<Vec<_>>();
+    (!ids.is_empty()).then(|| Permission {
+        resource_identifier: ResourceIdentifier::Database(ids),
+        ..permission.clone()
+    })
+}
+
 #[derive(Debug)]
 pub struct DatabaseCatalogTransaction {
     catalog_sequence: CatalogSequenceNumber,
     current_table_count: usize,
     table_limit: usize,
     columns_per_table_limit: usize,
+    type_promotion: bool,
+    column_name_rules: Option<ColumnNameRules>,
     time_ns: i64,
     database_schema: Arc<DatabaseSchema>,
     ops: Vec<DatabaseCatalogOp>,
@@ -1202,6 +3201,67 @@ impl DatabaseCatalogTransaction {
         self.ops.push(op);
     }
 
+    /// Soft delete a table in this transaction
+    ///
+    /// The table is renamed in the transaction's schema, so a new table with the same name can
+    /// be created in the same transaction.
+    pub fn soft_delete_table(
+        &mut self,
+        table_name: &str,
+        hard_delete_time: Option<Time>,
+    ) -> Result<TableId> {
+        let Some(table_def) = self.database_schema.table_definition(table_name) else {
+            return Err(CatalogError::NotFound);
+        };
+        if table_def.deleted {
+            return Err(CatalogError::AlreadyDeleted);
+        }
+        let op = SoftDeleteTableLog {
+            database_id: self.database_schema.id,
+            database_name: Arc::clone(&self.database_schema.name),
+            table_id: table_def.table_id,
+            table_name: Arc::clone(&table_def.table_name),
+            deletion_time: self.time_ns,
+            hard_deletion_time: hard_delete_time.map(|t| t.timestamp_nanos()),
+            original_name: Some(Arc::clone(&table_def.table_name)),
+        };
+        let db_schema = op
+            .update_schema(Cow::Borrowed(self.database_schema.as_ref()))?
+            .into_owned();
+        self.database_schema = Arc::new(db_schema);
+        self.ops.push(DatabaseCatalogOp::SoftDeleteTable(op));
+        Ok(table_def.table_id)
+    }
+
+    /// Reserve a [`TableId`] for a table that will be created in this transaction
+    ///
+    /// The id will not be handed out again by this transaction, so it can be used in a
+    /// [`DatabaseCatalogOp::CreateTable`] pushed via [`push_op`][Self::push_op]. The reservation
+    /// only holds for this transaction; if the commit needs to be retried then ids must be
+    /// reserved again on the new transaction.
+    pub fn reserve_table_id(&mut self) -> TableId {
+        Arc::make_mut(&mut self.database_schema)
+            .tables
+            .get_and_increment_next_id()
+    }
+
+    /// Reserve a [`ColumnId`] for a new column on an existing table in this transaction
+    ///
+    /// The id can be used in a [`DatabaseCatalogOp::AddFields`] pushed via
+    /// [`push_op`][Self::push_op]. As with [`reserve_table_id`][Self::reserve_table_id], the
+    /// reservation only holds for this transaction.
+    pub fn reserve_column_id(&mut self, table_name: &str) -> Result<ColumnId> {
+        let Some(mut table_def) = self.database_schema.table_definition(table_name) else {
+            return Err(CatalogError::NotFound);
+        };
+        let column_id = Arc::make_mut(&mut table_def)
+            .columns
+            .get_and_increment_next_id();
+        let table_id = table_def.table_id;
+        Arc::make_mut(&mut self.database_schema).update_table(table_id, table_def)?;
+        Ok(column_id)
+    }
+
     fn catalog_batch(&mut self) -> CatalogBatch {
         CatalogBatch::database(
             self.time_ns,
@@ -1252,6 +3312,54 @@ impl DatabaseCatalogTransaction {
         };
         match table_def.column_definition(column_name) {
             Some(def) if def.data_type == column_type.into() => Ok(def.id),
+            // floats cannot be used as last cache keys, so those columns are not promoted:
+            Some(def)
+                if self.type_promotion
+                    && is_type_promotion(def.data_type, column_type.into())
+                    && !table_def
+                        .last_caches
+                        .resource_iter()
+                        .any(|cache| cache.key_columns.contains(&def.id)) =>
+            {
+                let field_definitions = vec![FieldDefinition {
+                    name: Arc::clone(&def.name),
+                    id: def.id,
+                    data_type: column_type,
+                }];
+                let table_id = table_def.table_id;
+                let table_name = Arc::clone(&table_def.table_name);
+                let promoted = TableDefinition::add_fields(
+                    Cow::Borrowed(table_def.as_ref()),
+                    &field_definitions,
+                    true,
+                )?
+                .into_owned();
+                debug!(
+                    table_name = table_name.as_ref(),
+                    column_name,
+                    column_id = def.id.get(),
+                    from = ?def.data_type,
+                    to = ?column_type,
+                    "promote column type"
+                );
+                self.ops.push(DatabaseCatalogOp::AddFields(AddFieldsLog {
+                    database_name: Arc::clone(&self.database_schema.name),
+                    database_id: self.database_schema.id,
+                    table_name,
+                    table_id,
+                    field_definitions,
+                }));
+                Arc::make_mut(&mut self.database_schema)
+                    .update_table(table_id, Arc::new(promoted))?;
+                Ok(def.id)
+            }
+            // writes of the type a column was promoted from are accepted, the write buffer
+            // converts the values to the column's type
+            Some(def)
+                if self.type_promotion && is_type_promotion(column_type.into(), def.data_type) =>
+            {
+                Ok(def.id)
+            }
             Some(def) => Err(CatalogError::InvalidColumnType {
                 column_name: Arc::clone(&def.name),
                 expected: def.data_type,
@@ -1266,6 +3374,9 @@ impl DatabaseCatalogTransaction {
                 {
                     return Err(CatalogError::TooManyTagColumns);
                 }
+                if let Some(rules) = &self.column_name_rules {
+                    rules.validate(column_name)?;
+                }
                 let database_id = self.database_schema.id;
                 let database_name = Arc::clone(&self.database_schema.name);
                 let db_schema = Arc::make_mut(&mut self.database_schema);
@@ -1315,6 +3426,15 @@ impl DatabaseCatalogTransaction {
         if tags.len() + fields.len() > self.columns_per_table_limit - 1 {
             return Err(CatalogError::TooManyColumns(self.columns_per_table_limit));
         }
+        if let Some(rules) = &self.column_name_rules {
+            for name in tags
+                .iter()
+                .map(AsRef::as_ref)
+                .chain(fields.iter().map(|(name, _)| name.as_ref()))
+            {
+                rules.validate(name)?;
+            }
+        }
         let db_schema = Arc::make_mut(&mut self.database_schema);
         let mut table_def_arc = db_schema.create_new_empty_table(table_name)?;
         let table_def = Arc::make_mut(&mut table_def_arc);
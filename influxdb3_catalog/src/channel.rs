@@ -1,11 +1,19 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use futures::future::try_join_all;
-use observability_deps::tracing::warn;
-use tokio::sync::{mpsc, oneshot};
+use metric::{Metric, Registry, U64Counter};
+use observability_deps::tracing::{info, warn};
+use parking_lot::Mutex;
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
 
-use crate::{catalog::CatalogUpdate, log::CatalogBatch};
+use crate::{
+    catalog::CatalogUpdate,
+    log::{CatalogBatch, OrderedCatalogBatch},
+};
 
 #[derive(Debug, thiserror::Error)]
 #[error("error in catalog update subscribers: {0:?}")]
@@ -13,6 +21,19 @@ pub struct SubscriptionError(#[from] anyhow::Error);
 
 const CATALOG_SUBSCRIPTION_BUFFER_SIZE: usize = 10_000;
 
+/// How long a catalog update waits for a subscriber to handle it before moving on without it
+const CATALOG_SUBSCRIPTION_ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) const CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_NAME: &str =
+    "influxdb3_catalog_subscription_held_back_updates";
+const CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_DESCRIPTION: &str = "catalog updates held back from a subscriber because its buffer of pending updates was full, \
+    to be delivered once it catches up";
+
+pub(crate) const CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_NAME: &str =
+    "influxdb3_catalog_subscription_ack_timeouts";
+const CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_DESCRIPTION: &str =
+    "catalog updates that were not handled by a subscriber before the catalog stopped waiting";
+
 type CatalogUpdateSender = mpsc::Sender<CatalogUpdateMessage>;
 pub type CatalogUpdateReceiver = mpsc::Receiver<CatalogUpdateMessage>;
 
@@ -22,7 +43,8 @@ pub type CatalogUpdateReceiver = mpsc::Receiver<CatalogUpdateMessage>;
 /// The response is sent in the `Drop` implementation of this type, so that the consumer of these
 /// messages does not need to worry about sending the response back to the catalog on broadcast.
 pub struct CatalogUpdateMessage {
-    update: Arc<CatalogUpdate>,
+    updates: Vec<Arc<CatalogUpdate>>,
+    lagged: bool,
     tx: Option<oneshot::Sender<()>>,
 }
 
@@ -30,14 +52,43 @@ impl CatalogUpdateMessage {
     /// Create a new `CatalogUpdateMessage`
     fn new(update: Arc<CatalogUpdate>, tx: oneshot::Sender<()>) -> Self {
         Self {
-            update,
+            updates: vec![update],
+            lagged: false,
             tx: Some(tx),
         }
     }
 
+    /// Create a message for updates that were held back from a subscriber that had fallen behind
+    ///
+    /// The catalog no longer waits on these, so there is no response to send.
+    fn new_lagged(updates: Vec<Arc<CatalogUpdate>>) -> Self {
+        Self {
+            updates,
+            lagged: true,
+            tx: None,
+        }
+    }
+
     /// Iterate over the `CatalogBatch`s in the update
     pub fn batches(&self) -> impl Iterator<Item = &CatalogBatch> {
-        self.update.batches()
+        self.updates.iter().flat_map(|update| update.batches())
+    }
+
+    /// Iterate over the batches in the update, along with the sequence number that each was
+    /// applied to the catalog at
+    pub fn ordered_batches(&self) -> impl Iterator<Item = &OrderedCatalogBatch> {
+        self.updates
+            .iter()
+            .flat_map(|update| update.ordered_batches())
+    }
+
+    /// Whether this message carries updates that were held back because the subscriber's buffer
+    /// of pending updates was full
+    ///
+    /// The updates are still delivered in order and none are lost, but the catalog operations
+    /// that made them completed without waiting for this subscriber to handle them.
+    pub fn is_lagged(&self) -> bool {
+        self.lagged
     }
 }
 
@@ -55,17 +106,68 @@ impl Drop for CatalogUpdateMessage {
 impl std::fmt::Debug for CatalogUpdateMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CatalogUpdateMessage")
-            .field("update", &self.update)
+            .field("updates", &self.updates)
+            .field("lagged", &self.lagged)
             .finish()
     }
 }
 
+/// A subscriber to catalog updates
+#[derive(Debug)]
+struct Subscription {
+    tx: CatalogUpdateSender,
+    /// Updates held back while the subscriber's buffer of pending updates was full
+    backlog: Arc<Mutex<Backlog>>,
+}
+
 #[derive(Debug, Default)]
+struct Backlog {
+    updates: Vec<Arc<CatalogUpdate>>,
+    /// Whether a task is forwarding the backlog to the subscriber, in which case new updates
+    /// must be added to the backlog so that they are not delivered out of order
+    forwarding: bool,
+}
+
+/// The subscribers to catalog updates
+///
+/// Each update is sent to every subscriber, and the catalog waits for each to handle it, so that
+/// when a catalog operation completes its effects are visible to the rest of the system. A slow
+/// subscriber does not stall catalog writes indefinitely, however:
+///
+/// * if a subscriber does not handle an update within [`CATALOG_SUBSCRIPTION_ACK_TIMEOUT`] the
+///   catalog stops waiting for it, and the subscriber lags behind the catalog until it catches up
+///   on the updates pending in its buffer;
+/// * if a subscriber's buffer of pending updates is full, which can only happen if it has fallen
+///   well behind, the update is held back and the catalog does not wait for it. Held back updates
+///   are delivered in order by a background task as the subscriber makes room in its buffer, in
+///   messages marked as [lagged](CatalogUpdateMessage::is_lagged), so that the subscriber does
+///   not miss any update.
+///
+/// Both cases are logged as warnings and counted in metrics, per subscriber.
+#[derive(Debug)]
 pub(crate) struct CatalogSubscriptions {
-    subscriptions: hashbrown::HashMap<Arc<str>, CatalogUpdateSender>,
+    subscriptions: hashbrown::HashMap<Arc<str>, Subscription>,
+    ack_timeout: Duration,
+    held_back_updates: Metric<U64Counter>,
+    ack_timeouts: Metric<U64Counter>,
 }
 
 impl CatalogSubscriptions {
+    pub(crate) fn new(metric_registry: &Registry) -> Self {
+        Self {
+            subscriptions: Default::default(),
+            ack_timeout: CATALOG_SUBSCRIPTION_ACK_TIMEOUT,
+            held_back_updates: metric_registry.register_metric(
+                CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_NAME,
+                CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_DESCRIPTION,
+            ),
+            ack_timeouts: metric_registry.register_metric(
+                CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_NAME,
+                CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_DESCRIPTION,
+            ),
+        }
+    }
+
     /// Subscribe to the catalog for updates.
     ///
     /// This allows components in the system to listen for updates made to the catalog
@@ -76,10 +178,22 @@ impl CatalogSubscriptions {
     ///
     /// If the provided `subscription_name` has already been used, this will panic.
     pub(crate) fn subscribe(&mut self, subscription_name: &'static str) -> CatalogUpdateReceiver {
-        let (tx, rx) = mpsc::channel(CATALOG_SUBSCRIPTION_BUFFER_SIZE);
+        self.subscribe_with_buffer_size(subscription_name, CATALOG_SUBSCRIPTION_BUFFER_SIZE)
+    }
+
+    fn subscribe_with_buffer_size(
+        &mut self,
+        subscription_name: &'static str,
+        buffer_size: usize,
+    ) -> CatalogUpdateReceiver {
+        let (tx, rx) = mpsc::channel(buffer_size);
+        let subscription = Subscription {
+            tx,
+            backlog: Default::default(),
+        };
         assert!(
             self.subscriptions
-                .insert(Arc::from(subscription_name), tx)
+                .insert(Arc::from(subscription_name), subscription)
                 .is_none(),
             "attempted to subscribe to catalog with same component name more than once, \
             name: {subscription_name}"
@@ -92,19 +206,65 @@ impl CatalogSubscriptions {
         update: Arc<CatalogUpdate>,
     ) -> Result<(), SubscriptionError> {
         let mut responses = vec![];
-        for (name, sub) in self
-            .subscriptions
-            .iter()
-            .map(|(n, s)| (Arc::clone(n), s.clone()))
-        {
-            let update_cloned = Arc::clone(&update);
+        for (name, sub) in self.subscriptions.iter() {
+            let subscriber: Cow<'static, str> = Cow::from(name.to_string());
+            let (tx, rx) = oneshot::channel();
+            {
+                let mut backlog = sub.backlog.lock();
+                if sub.tx.is_closed() {
+                    return Err(anyhow::anyhow!("failed to send update to {name}").into());
+                }
+                if backlog.forwarding {
+                    // the subscriber has not caught up on the updates held back for it yet:
+                    backlog.updates.push(Arc::clone(&update));
+                    self.held_back_updates
+                        .recorder([("subscriber", subscriber)])
+                        .inc(1);
+                    continue;
+                }
+                match sub
+                    .tx
+                    .try_send(CatalogUpdateMessage::new(Arc::clone(&update), tx))
+                {
+                    Ok(()) => (),
+                    Err(TrySendError::Full(_)) => {
+                        warn!(
+                            subscriber = name.as_ref(),
+                            "catalog subscriber has too many pending updates, holding back \
+                            updates until it catches up"
+                        );
+                        self.held_back_updates
+                            .recorder([("subscriber", subscriber)])
+                            .inc(1);
+                        backlog.updates.push(Arc::clone(&update));
+                        backlog.forwarding = true;
+                        forward_backlog(Arc::clone(name), sub.tx.clone(), Arc::clone(&sub.backlog));
+                        continue;
+                    }
+                    Err(TrySendError::Closed(_)) => {
+                        return Err(anyhow::anyhow!("failed to send update to {name}").into());
+                    }
+                }
+            }
+            let name = Arc::clone(name);
+            let ack_timeout = self.ack_timeout;
+            let ack_timeouts = self.ack_timeouts.recorder([("subscriber", subscriber)]);
             responses.push(tokio::spawn(async move {
-                let (tx, rx) = oneshot::channel();
-                sub.send(CatalogUpdateMessage::new(update_cloned, tx))
-                    .await
-                    .with_context(|| format!("failed to send update to {name}"))?;
-                rx.await
-                    .with_context(|| format!("failed to receive response from {name}"))?;
+                match tokio::time::timeout(ack_timeout, rx).await {
+                    Ok(response) => {
+                        response
+                            .with_context(|| format!("failed to receive response from {name}"))?;
+                    }
+                    Err(_) => {
+                        warn!(
+                            subscriber = name.as_ref(),
+                            timeout = ?ack_timeout,
+                            "catalog subscriber did not handle update in time, it will lag \
+                            behind the catalog"
+                        );
+                        ack_timeouts.inc(1);
+                    }
+                }
                 Ok(())
             }));
         }
@@ -118,11 +278,76 @@ impl CatalogSubscriptions {
     }
 }
 
+/// Deliver the updates held back for a subscriber, in order, as it makes room in its buffer
+///
+/// The task stops once the backlog is empty, after which updates are sent to the subscriber
+/// directly again.
+fn forward_backlog(name: Arc<str>, tx: CatalogUpdateSender, backlog: Arc<Mutex<Backlog>>) {
+    tokio::spawn(async move {
+        loop {
+            let updates = {
+                let mut backlog = backlog.lock();
+                if backlog.updates.is_empty() {
+                    backlog.forwarding = false;
+                    info!(
+                        subscriber = name.as_ref(),
+                        "catalog subscriber caught up on held back updates"
+                    );
+                    return;
+                }
+                std::mem::take(&mut backlog.updates)
+            };
+            if tx
+                .send(CatalogUpdateMessage::new_lagged(updates))
+                .await
+                .is_err()
+            {
+                warn!(
+                    subscriber = name.as_ref(),
+                    "catalog subscriber closed before it caught up on held back updates"
+                );
+                return;
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use observability_deps::tracing::debug;
 
-    use crate::{catalog::Catalog, log::FieldDataType};
+    use std::{sync::Arc, time::Duration};
+
+    use influxdb3_id::DbId;
+    use metric::{Attributes, Metric, Registry, U64Counter};
+
+    use super::{
+        CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_NAME, CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_NAME,
+        CatalogSubscriptions,
+    };
+    use crate::{
+        catalog::{Catalog, CatalogSequenceNumber, CatalogUpdate},
+        log::{CatalogBatch, DatabaseCatalogOp, FieldDataType, OrderedCatalogBatch},
+    };
+
+    fn update(sequence: u64) -> Arc<CatalogUpdate> {
+        Arc::new(
+            OrderedCatalogBatch::new(
+                CatalogBatch::database(0, DbId::new(0), "foo", vec![]),
+                CatalogSequenceNumber::new(sequence),
+            )
+            .into(),
+        )
+    }
+
+    fn subscriber_metric(registry: &Registry, name: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>(name)
+            .unwrap()
+            .get_observer(&Attributes::from(&[("subscriber", "test_sub")]))
+            .unwrap()
+            .fetch()
+    }
 
     #[test_log::test(tokio::test)]
     async fn test_catalog_update_sub() {
@@ -149,4 +374,113 @@ mod tests {
         let n_updates = handle.await.unwrap();
         assert_eq!(2, n_updates);
     }
+
+    #[test_log::test(tokio::test)]
+    async fn test_catalog_update_sub_ordered_batches() {
+        let catalog = Catalog::new_in_memory("cats").await.unwrap();
+        let mut sub = catalog.subscribe_to_updates("test_sub").await;
+        let handle = tokio::spawn(async move {
+            let mut updates = vec![];
+            while let Some(update) = sub.recv().await {
+                for batch in update.ordered_batches() {
+                    let tables_created = batch
+                        .batch()
+                        .as_database()
+                        .map(|db| {
+                            db.ops
+                                .iter()
+                                .filter(|op| matches!(op, DatabaseCatalogOp::CreateTable(_)))
+                                .count()
+                        })
+                        .unwrap_or_default();
+                    updates.push((batch.sequence_number(), tables_created));
+                }
+            }
+            updates
+        });
+
+        catalog.create_database("foo").await.unwrap();
+        let create_db_sequence = catalog.sequence_number();
+        catalog
+            .create_table("foo", "bar", &["tag"], &[("field", FieldDataType::String)])
+            .await
+            .unwrap();
+        let create_table_sequence = catalog.sequence_number();
+
+        drop(catalog);
+
+        let updates = handle.await.unwrap();
+        assert_eq!(
+            updates,
+            [(create_db_sequence, 0), (create_table_sequence, 1)]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_subscriber_ack_timeout() {
+        let registry = Registry::new();
+        let mut subscriptions = CatalogSubscriptions::new(&registry);
+        subscriptions.ack_timeout = Duration::from_millis(10);
+        let mut sub = subscriptions.subscribe("test_sub");
+
+        // nothing is handling the update, so the catalog stops waiting for it after the timeout:
+        subscriptions.send_update(update(1)).await.unwrap();
+        assert_eq!(
+            1,
+            subscriber_metric(&registry, CATALOG_SUBSCRIPTION_ACK_TIMEOUTS_METRIC_NAME)
+        );
+
+        // the update is still delivered once the subscriber gets to it:
+        let message = sub.recv().await.unwrap();
+        assert!(!message.is_lagged());
+        assert_eq!(
+            vec![CatalogSequenceNumber::new(1)],
+            message
+                .ordered_batches()
+                .map(|batch| batch.sequence_number())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_full_subscriber_catches_up_on_held_back_updates() {
+        let registry = Registry::new();
+        let mut subscriptions = CatalogSubscriptions::new(&registry);
+        subscriptions.ack_timeout = Duration::from_millis(10);
+        let mut sub = subscriptions.subscribe_with_buffer_size("test_sub", 1);
+
+        // the first update fills the subscriber's buffer, so the rest are held back:
+        for sequence in 1..=4 {
+            subscriptions.send_update(update(sequence)).await.unwrap();
+        }
+        assert_eq!(
+            3,
+            subscriber_metric(&registry, CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_NAME)
+        );
+
+        // every update is delivered, in order, with the held back ones marked as lagged:
+        let mut received = vec![];
+        while received.len() < 4 {
+            let message = sub.recv().await.unwrap();
+            assert_eq!(!received.is_empty(), message.is_lagged());
+            received.extend(
+                message
+                    .ordered_batches()
+                    .map(|batch| batch.sequence_number()),
+            );
+        }
+        assert_eq!(
+            (1..=4).map(CatalogSequenceNumber::new).collect::<Vec<_>>(),
+            received
+        );
+
+        // once it has caught up, updates are sent to the subscriber directly again:
+        subscriptions.send_update(update(5)).await.unwrap();
+        let message = sub.recv().await.unwrap();
+        assert!(!message.is_lagged());
+        assert_eq!(
+            3,
+            subscriber_metric(&registry, CATALOG_SUBSCRIPTION_HELD_BACK_METRIC_NAME)
+        );
+    }
 }
@@ -5,7 +5,8 @@ use humantime::Duration;
 use schema::InfluxColumnType;
 
 use crate::{
-    catalog::NUM_TAG_COLUMNS_LIMIT, channel::SubscriptionError,
+    catalog::{NUM_TAG_COLUMNS_LIMIT, ValidateDbNameError},
+    channel::SubscriptionError,
     object_store::ObjectStoreCatalogError,
 };
 
@@ -23,6 +24,9 @@ pub enum CatalogError {
     #[error("attempted to delete resource that was already deleted")]
     AlreadyDeleted,
 
+    #[error("attempted to undelete resource that is not deleted")]
+    NotDeleted,
+
     #[error("invalid configuration provided: {message}")]
     InvalidConfiguration { message: Box<str> },
 
@@ -63,12 +67,22 @@ pub enum CatalogError {
     #[error("Adding a new database would exceed limit of {0} databases")]
     TooManyDbs(usize),
 
+    #[error("Adding a new token would exceed limit of {0} tokens")]
+    TooManyTokens(usize),
+
     #[error("Table {} not in DB schema for {}", table_name, db_name)]
     TableNotFound {
         db_name: Arc<str>,
         table_name: Arc<str>,
     },
 
+    #[error("Column {} not in table {} of DB {}", column_name, table_name, db_name)]
+    ColumnNotFound {
+        db_name: Arc<str>,
+        table_name: Arc<str>,
+        column_name: Arc<str>,
+    },
+
     #[error(
         "Field type mismatch on table {} column {}. Existing column is {} but attempted to add {}",
         table_name,
@@ -162,6 +176,12 @@ pub enum CatalogError {
         context: Option<String>,
     },
 
+    #[error("invalid trigger specification {trigger_spec}: {reason}")]
+    InvalidTriggerSpecification {
+        trigger_spec: String,
+        reason: String,
+    },
+
     #[error("invalid error behavior {0}")]
     InvalidErrorBehavior(String),
 
@@ -174,12 +194,39 @@ pub enum CatalogError {
     #[error("cannot delete internal db")]
     CannotDeleteInternalDatabase,
 
+    #[error("cannot rename internal db")]
+    CannotRenameInternalDatabase,
+
+    #[error(
+        "cannot rename database {database_name} because it has the processing engine trigger \
+        {trigger_name}, which must be deleted first"
+    )]
+    CannotRenameDatabaseWithTriggers {
+        database_name: Arc<str>,
+        trigger_name: Arc<str>,
+    },
+
+    #[error(
+        "cannot rename table {table_name} because the processing engine trigger {trigger_name} \
+        refers to it, which must be deleted first"
+    )]
+    CannotRenameTableWithTriggers {
+        table_name: Arc<str>,
+        trigger_name: Arc<str>,
+    },
+
     #[error("tried to stop a node ({node_id}) that is already stopped")]
     NodeAlreadyStopped { node_id: Arc<str> },
 
     #[error("cannot delete operator token")]
     CannotDeleteOperatorToken,
 
+    #[error("cannot modify operator token")]
+    CannotModifyOperatorToken,
+
+    #[error("cannot drop the time column")]
+    CannotDropTimeColumn,
+
     #[error(
         "cannot change the configured generation duration for level {level}; \
         attempted to set to {attempted:#} but its already set to {existing:#}"
@@ -189,6 +236,28 @@ pub enum CatalogError {
         existing: Duration,
         attempted: Duration,
     },
+
+    #[error("retention period of {attempted} is shorter than the minimum of {min}")]
+    RetentionPeriodTooShort { min: Duration, attempted: Duration },
+
+    #[error("invalid database name '{name}': {reason}")]
+    InvalidDatabaseName {
+        name: String,
+        reason: ValidateDbNameError,
+    },
+
+    #[error("invalid column name '{name}': {reason}")]
+    InvalidColumnName { name: String, reason: String },
+
+    #[error("the catalog is read-only and cannot be updated")]
+    ReadOnlyCatalog,
+
+    #[error("cannot change the type of column '{column_name}' from {existing} to {attempted}")]
+    IncompatibleColumnTypeChange {
+        column_name: String,
+        existing: InfluxColumnType,
+        attempted: InfluxColumnType,
+    },
 }
 
 impl CatalogError {
@@ -201,4 +270,69 @@ impl CatalogError {
     pub fn unexpected(message: impl Into<String>) -> Self {
         Self::Other(anyhow!(message.into()))
     }
+
+    /// A stable, machine-readable code identifying the kind of error
+    ///
+    /// Unlike the error message, which may change between releases, this can be matched on by
+    /// clients, so existing codes should not be changed.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ObjectStore(_) => "catalog/object_store",
+            Self::AlreadyExists => "catalog/already_exists",
+            Self::NotFound => "catalog/not_found",
+            Self::AlreadyDeleted => "catalog/already_deleted",
+            Self::NotDeleted => "catalog/not_deleted",
+            Self::InvalidConfiguration { .. } => "catalog/invalid_configuration",
+            Self::InvalidDistinctCacheColumnType => "catalog/invalid_distinct_cache_column_type",
+            Self::InvalidLastCacheKeyColumnType => "catalog/invalid_last_cache_key_column_type",
+            Self::TriggerAlreadyEnabled => "catalog/trigger_already_enabled",
+            Self::TriggerAlreadyDisabled => "catalog/trigger_already_disabled",
+            Self::InvalidColumnType { .. } => "catalog/invalid_column_type",
+            Self::InvalidNodeRegistration => "catalog/invalid_node_registration",
+            Self::TooManyColumns(_) => "catalog/too_many_columns",
+            Self::TooManyTagColumns => "catalog/too_many_tag_columns",
+            Self::TooManyTables(_) => "catalog/too_many_tables",
+            Self::TooManyDbs(_) => "catalog/too_many_databases",
+            Self::TooManyTokens(_) => "catalog/too_many_tokens",
+            Self::TableNotFound { .. } => "catalog/table_not_found",
+            Self::ColumnNotFound { .. } => "catalog/column_not_found",
+            Self::FieldTypeMismatch { .. } => "catalog/field_type_mismatch",
+            Self::SeriesKeyMismatch { .. } => "catalog/series_key_mismatch",
+            Self::Subscription(_) => "catalog/subscription",
+            Self::Other(_) => "catalog/other",
+            Self::ProcessingEngineTriggerExists { .. } => "catalog/trigger_exists",
+            Self::ProcessingEngineTriggerRunning { .. } => "catalog/trigger_running",
+            Self::ProcessingEnginePluginInUse { .. } => "catalog/plugin_in_use",
+            Self::ProcessingEnginePluginNotFound { .. } => "catalog/plugin_not_found",
+            Self::ProcessingEngineUnimplemented { .. } => "catalog/processing_engine_unimplemented",
+            Self::ProcessingEngineTriggerNotFound { .. } => "catalog/trigger_not_found",
+            Self::ProcessingEngineTriggerSpecParseError { .. }
+            | Self::TriggerSpecificationParseError { .. }
+            | Self::InvalidTriggerSpecification { .. } => "catalog/invalid_trigger_spec",
+            Self::InvalidLastCacheSize => "catalog/invalid_last_cache_size",
+            Self::InvalidErrorBehavior(_) => "catalog/invalid_error_behavior",
+            Self::TokenNameAlreadyExists(_) => "catalog/token_name_already_exists",
+            Self::MissingAdminTokenToUpdate => "catalog/missing_admin_token",
+            Self::CannotDeleteInternalDatabase => "catalog/cannot_delete_internal_database",
+            Self::CannotRenameInternalDatabase => "catalog/cannot_rename_internal_database",
+            Self::CannotRenameDatabaseWithTriggers { .. } => {
+                "catalog/cannot_rename_database_with_triggers"
+            }
+            Self::CannotRenameTableWithTriggers { .. } => {
+                "catalog/cannot_rename_table_with_triggers"
+            }
+            Self::NodeAlreadyStopped { .. } => "catalog/node_already_stopped",
+            Self::CannotDeleteOperatorToken => "catalog/cannot_delete_operator_token",
+            Self::CannotModifyOperatorToken => "catalog/cannot_modify_operator_token",
+            Self::CannotDropTimeColumn => "catalog/cannot_drop_time_column",
+            Self::CannotChangeGenerationDuration { .. } => {
+                "catalog/cannot_change_generation_duration"
+            }
+            Self::RetentionPeriodTooShort { .. } => "catalog/retention_period_too_short",
+            Self::InvalidDatabaseName { .. } => "catalog/invalid_database_name",
+            Self::InvalidColumnName { .. } => "catalog/invalid_column_name",
+            Self::IncompatibleColumnTypeChange { .. } => "catalog/incompatible_column_type_change",
+            Self::ReadOnlyCatalog => "catalog/read_only",
+        }
+    }
 }
@@ -223,6 +223,7 @@ impl From<v2::SoftDeleteDatabaseLog> for v3::SoftDeleteDatabaseLog {
             database_name: value.database_name,
             deletion_time: value.deletion_time,
             hard_deletion_time: None,
+            original_name: None,
         }
     }
 }
@@ -277,6 +278,7 @@ impl From<v2::SoftDeleteTableLog> for v3::SoftDeleteTableLog {
             table_name: value.table_name,
             deletion_time: value.deletion_time,
             hard_deletion_time: None,
+            original_name: None,
         }
     }
 }
@@ -3,6 +3,7 @@
 /// log files.
 use std::{
     cmp::{Ord, PartialOrd},
+    collections::BTreeSet,
     num::NonZeroUsize,
     ops::Deref,
     str::FromStr,
@@ -38,6 +39,9 @@ pub enum CatalogBatch {
     Delete(DeleteBatch),
     /// A batch for modifying catalog generation configuration
     Generation(GenerationBatch),
+    /// A batch of database batches that are applied together, so that either all or none of
+    /// them take effect
+    MultiDatabase(MultiDatabaseBatch),
 }
 
 impl CatalogBatch {
@@ -77,6 +81,10 @@ impl CatalogBatch {
         Self::Generation(GenerationBatch { time_ns, ops })
     }
 
+    pub fn multi_database(time_ns: i64, batches: Vec<DatabaseBatch>) -> Self {
+        Self::MultiDatabase(MultiDatabaseBatch { time_ns, batches })
+    }
+
     pub fn n_ops(&self) -> usize {
         match self {
             CatalogBatch::Node(node_batch) => node_batch.ops.len(),
@@ -84,6 +92,11 @@ impl CatalogBatch {
             CatalogBatch::Token(token_batch) => token_batch.ops.len(),
             CatalogBatch::Delete(delete_batch) => delete_batch.ops.len(),
             CatalogBatch::Generation(generation_batch) => generation_batch.ops.len(),
+            CatalogBatch::MultiDatabase(multi_batch) => multi_batch
+                .batches
+                .iter()
+                .map(|database_batch| database_batch.ops.len())
+                .sum(),
         }
     }
 
@@ -94,6 +107,7 @@ impl CatalogBatch {
             CatalogBatch::Token(_) => None,
             CatalogBatch::Delete(_) => None,
             CatalogBatch::Generation(_) => None,
+            CatalogBatch::MultiDatabase(_) => None,
         }
     }
 
@@ -111,6 +125,7 @@ impl CatalogBatch {
             CatalogBatch::Token(_) => None,
             CatalogBatch::Delete(_) => None,
             CatalogBatch::Generation(_) => None,
+            CatalogBatch::MultiDatabase(_) => None,
         }
     }
 
@@ -121,6 +136,7 @@ impl CatalogBatch {
             CatalogBatch::Token(_) => None,
             CatalogBatch::Delete(_) => None,
             CatalogBatch::Generation(_) => None,
+            CatalogBatch::MultiDatabase(_) => None,
         }
     }
 
@@ -131,8 +147,63 @@ impl CatalogBatch {
             CatalogBatch::Token(_) => None,
             CatalogBatch::Delete(_) => None,
             CatalogBatch::Generation(generation_batch) => Some(generation_batch),
+            CatalogBatch::MultiDatabase(_) => None,
         }
     }
+
+    /// Get the databases and tables that the ops in this batch touch
+    ///
+    /// Any database that has one of its tables touched is also listed as an affected database.
+    /// Node, token, and generation batches do not affect any databases or tables.
+    pub fn affected_resources(&self) -> AffectedResources {
+        let mut affected = AffectedResources::default();
+        match self {
+            CatalogBatch::Database(database_batch) => {
+                affected.add_database_batch(database_batch);
+            }
+            CatalogBatch::MultiDatabase(multi_batch) => {
+                for database_batch in &multi_batch.batches {
+                    affected.add_database_batch(database_batch);
+                }
+            }
+            CatalogBatch::Delete(delete_batch) => {
+                for op in &delete_batch.ops {
+                    match op {
+                        DeleteOp::DeleteDatabase(db_id) => {
+                            affected.databases.insert(*db_id);
+                        }
+                        DeleteOp::DeleteTable(db_id, table_id) => {
+                            affected.databases.insert(*db_id);
+                            affected.tables.insert((*db_id, *table_id));
+                        }
+                    }
+                }
+            }
+            CatalogBatch::Node(_) | CatalogBatch::Token(_) | CatalogBatch::Generation(_) => (),
+        }
+        affected
+    }
+}
+
+/// The databases and tables touched by a [`CatalogBatch`], as produced by
+/// [`CatalogBatch::affected_resources`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AffectedResources {
+    pub databases: BTreeSet<DbId>,
+    pub tables: BTreeSet<(DbId, TableId)>,
+}
+
+impl AffectedResources {
+    fn add_database_batch(&mut self, database_batch: &DatabaseBatch) {
+        self.databases.insert(database_batch.database_id);
+        self.tables.extend(
+            database_batch
+                .ops
+                .iter()
+                .filter_map(DatabaseCatalogOp::table_id)
+                .map(|table_id| (database_batch.database_id, table_id)),
+        );
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
@@ -169,6 +240,12 @@ pub struct GenerationBatch {
     pub ops: Vec<GenerationOp>,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
+pub struct MultiDatabaseBatch {
+    pub time_ns: i64,
+    pub batches: Vec<DatabaseBatch>,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum GenerationOp {
     SetGenerationDuration(SetGenerationDurationLog),
@@ -235,10 +312,20 @@ pub enum DatabaseCatalogOp {
     // Database ops:
     CreateDatabase(CreateDatabaseLog),
     SoftDeleteDatabase(SoftDeleteDatabaseLog),
+    UndeleteDatabase(UndeleteDatabaseLog),
     // Table ops:
     CreateTable(CreateTableLog),
     SoftDeleteTable(SoftDeleteTableLog),
+    UndeleteTable(UndeleteTableLog),
     AddFields(AddFieldsLog),
+    SetPrimaryPartitionTag(SetPrimaryPartitionTagLog),
+    RenameTable(RenameTableLog),
+    SetColumnAllowedValues(SetColumnAllowedValuesLog),
+    SetColumnIndexed(SetColumnIndexedLog),
+    SetColumnDescription(SetColumnDescriptionLog),
+    DropColumn(DropColumnLog),
+    ReorderSeriesKey(ReorderSeriesKeyLog),
+    AlterColumnType(AlterColumnTypeLog),
     // Distinct cache ops:
     CreateDistinctCache(DistinctCacheDefinition),
     DeleteDistinctCache(DeleteDistinctCacheLog),
@@ -253,6 +340,12 @@ pub enum DatabaseCatalogOp {
     // Retention period ops:
     SetRetentionPeriod(SetRetentionPeriodLog),
     ClearRetentionPeriod(ClearRetentionPeriodLog),
+    SetRetentionPeriodForTable(SetRetentionPeriodForTableLog),
+    ClearRetentionPeriodForTable(ClearRetentionPeriodForTableLog),
+    // Database metadata ops:
+    SetDatabaseDescription(SetDatabaseDescriptionLog),
+    SetNullTagPlaceholder(SetNullTagPlaceholderLog),
+    RenameDatabase(RenameDatabaseLog),
 }
 
 impl DatabaseCatalogOp {
@@ -276,6 +369,43 @@ impl DatabaseCatalogOp {
             _ => None,
         }
     }
+
+    /// Get the id of the table this op applies to, or `None` if it applies to the database as a
+    /// whole
+    pub fn table_id(&self) -> Option<TableId> {
+        match self {
+            Self::CreateTable(log) => Some(log.table_id),
+            Self::SoftDeleteTable(log) => Some(log.table_id),
+            Self::UndeleteTable(log) => Some(log.table_id),
+            Self::AddFields(log) => Some(log.table_id),
+            Self::SetPrimaryPartitionTag(log) => Some(log.table_id),
+            Self::RenameTable(log) => Some(log.table_id),
+            Self::SetColumnAllowedValues(log) => Some(log.table_id),
+            Self::SetColumnIndexed(log) => Some(log.table_id),
+            Self::SetColumnDescription(log) => Some(log.table_id),
+            Self::DropColumn(log) => Some(log.table_id),
+            Self::ReorderSeriesKey(log) => Some(log.table_id),
+            Self::AlterColumnType(log) => Some(log.table_id),
+            Self::CreateDistinctCache(def) => Some(def.table_id),
+            Self::DeleteDistinctCache(log) => Some(log.table_id),
+            Self::CreateLastCache(def) => Some(def.table_id),
+            Self::DeleteLastCache(log) => Some(log.table_id),
+            Self::SetRetentionPeriodForTable(log) => Some(log.table_id),
+            Self::ClearRetentionPeriodForTable(log) => Some(log.table_id),
+            Self::CreateDatabase(_)
+            | Self::SoftDeleteDatabase(_)
+            | Self::UndeleteDatabase(_)
+            | Self::CreateTrigger(_)
+            | Self::DeleteTrigger(_)
+            | Self::EnableTrigger(_)
+            | Self::DisableTrigger(_)
+            | Self::SetRetentionPeriod(_)
+            | Self::ClearRetentionPeriod(_)
+            | Self::SetDatabaseDescription(_)
+            | Self::SetNullTagPlaceholder(_)
+            | Self::RenameDatabase(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -321,6 +451,20 @@ pub struct SoftDeleteDatabaseLog {
     pub database_name: Arc<str>,
     pub deletion_time: i64,
     pub hard_deletion_time: Option<i64>,
+    /// The name the database had before it was deleted
+    ///
+    /// This is `None` for logs written before original names were recorded.
+    #[serde(default)]
+    pub original_name: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UndeleteDatabaseLog {
+    pub database_id: DbId,
+    /// The name the database was given when it was soft deleted
+    pub deleted_name: Arc<str>,
+    /// The name the database is restored to
+    pub database_name: Arc<str>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -331,6 +475,102 @@ pub struct SoftDeleteTableLog {
     pub table_name: Arc<str>,
     pub deletion_time: i64,
     pub hard_deletion_time: Option<i64>,
+    /// The name the table had before it was deleted
+    ///
+    /// This is `None` for logs written before original names were recorded.
+    #[serde(default)]
+    pub original_name: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UndeleteTableLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    /// The name the table was given when it was soft deleted
+    pub deleted_name: Arc<str>,
+    /// The name the table is restored to
+    pub table_name: Arc<str>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetPrimaryPartitionTagLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: Option<ColumnId>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetColumnAllowedValuesLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    pub allowed_values: Option<BTreeSet<Arc<str>>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetColumnIndexedLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    pub indexed: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetColumnDescriptionLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    /// The new description, or `None` to clear it
+    pub description: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DropColumnLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    pub column_name: Arc<str>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReorderSeriesKeyLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    /// The columns of the series key, in their new order
+    pub series_key: Vec<ColumnId>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AlterColumnTypeLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub table_name: Arc<str>,
+    pub column_id: ColumnId,
+    /// The type the column is changed to
+    pub column_type: FieldDataType,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RenameTableLog {
+    pub database_id: DbId,
+    pub database_name: Arc<str>,
+    pub table_id: TableId,
+    pub old_name: Arc<str>,
+    pub new_name: Arc<str>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -356,6 +596,44 @@ pub struct ClearRetentionPeriodLog {
     pub database_id: DbId,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetRetentionPeriodForTableLog {
+    pub database_name: Arc<str>,
+    pub database_id: DbId,
+    pub table_name: Arc<str>,
+    pub table_id: TableId,
+    pub retention_period: Duration,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ClearRetentionPeriodForTableLog {
+    pub database_name: Arc<str>,
+    pub database_id: DbId,
+    pub table_name: Arc<str>,
+    pub table_id: TableId,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetDatabaseDescriptionLog {
+    pub database_name: Arc<str>,
+    pub database_id: DbId,
+    pub description: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetNullTagPlaceholderLog {
+    pub database_name: Arc<str>,
+    pub database_id: DbId,
+    pub placeholder: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RenameDatabaseLog {
+    pub database_id: DbId,
+    pub old_name: Arc<str>,
+    pub new_name: Arc<str>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AddFieldsLog {
     pub database_name: Arc<str>,
@@ -895,6 +1173,39 @@ impl TriggerSpecificationDefinition {
         }
     }
 
+    /// Check that a schedule is a valid cron expression, that an interval is greater than zero and
+    /// at most one year, and that a request path is not empty
+    ///
+    /// Specifications parsed with [`Self::from_string_rep`] have already had their schedule and
+    /// path checked, but not that an interval is greater than zero.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| {
+            Err(CatalogError::InvalidTriggerSpecification {
+                trigger_spec: self.string_rep(),
+                reason: reason.to_string(),
+            })
+        };
+        match self {
+            TriggerSpecificationDefinition::Schedule { schedule }
+                if Schedule::from_str(schedule).is_err() =>
+            {
+                invalid("schedule is not a valid cron expression")
+            }
+            TriggerSpecificationDefinition::Every { duration } if duration.is_zero() => {
+                invalid("interval must be greater than zero")
+            }
+            TriggerSpecificationDefinition::Every { duration }
+                if *duration > parse_duration("1 year").unwrap() =>
+            {
+                invalid("don't support every schedules of over 1 year")
+            }
+            TriggerSpecificationDefinition::RequestPath { path } if path.trim().is_empty() => {
+                invalid("request path is empty")
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn string_rep(&self) -> String {
         match self {
             TriggerSpecificationDefinition::SingleTableWalWrite { table_name } => {
@@ -940,6 +1251,10 @@ pub enum TokenCatalogOp {
     CreateScopedToken(CreateScopedTokenDetails),
     RegenerateAdminToken(RegenerateAdminTokenDetails),
     DeleteToken(DeleteTokenDetails),
+    UpdateTokenPermissions(UpdateTokenPermissionsDetails),
+    SetTokenDisabled(SetTokenDisabledDetails),
+    UpdateTokenExpiry(UpdateTokenExpiryDetails),
+    RenameToken(RenameTokenDetails),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -974,3 +1289,33 @@ pub struct RegenerateAdminTokenDetails {
 pub struct DeleteTokenDetails {
     pub token_name: String,
 }
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UpdateTokenPermissionsDetails {
+    pub token_id: TokenId,
+    /// The full set of permissions the token has after the update
+    pub permissions: Vec<influxdb3_authz::Permission>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SetTokenDisabledDetails {
+    pub token_id: TokenId,
+    pub disabled: bool,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UpdateTokenExpiryDetails {
+    pub token_id: TokenId,
+    /// The new expiry time in milliseconds, or `None` if the token no longer expires
+    pub expiry: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RenameTokenDetails {
+    pub token_id: TokenId,
+    pub new_name: Arc<str>,
+    pub updated_at: i64,
+}
@@ -1,5 +1,6 @@
 use std::ops::Deref;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use anyhow::Context;
 use bytes::Bytes;
@@ -10,7 +11,10 @@ use observability_deps::tracing::{debug, error, info, trace, warn};
 use uuid::Uuid;
 
 use crate::catalog::InnerCatalog;
-use crate::serialize::verify_and_deserialize_catalog_checkpoint_file;
+use crate::serialize::{
+    verify_and_deserialize_catalog_checkpoint_file,
+    verify_and_deserialize_catalog_checkpoint_sequence,
+};
 use crate::snapshot::CatalogSnapshot;
 use crate::snapshot::versions::Snapshot;
 use crate::{
@@ -34,7 +38,9 @@ type Result<T, E = ObjectStoreCatalogError> = std::result::Result<T, E>;
 pub struct ObjectStoreCatalog {
     pub(crate) prefix: Arc<str>,
     /// PUT a checkpoint file to the object store every `checkpoint_interval` sequenced log files
-    pub(crate) checkpoint_interval: u64,
+    ///
+    /// This is shared between clones so that it can be changed on a running catalog.
+    checkpoint_interval: Arc<AtomicU64>,
     store: Arc<dyn ObjectStore>,
 }
 
@@ -46,11 +52,20 @@ impl ObjectStoreCatalog {
     ) -> Self {
         Self {
             prefix: prefix.into(),
-            checkpoint_interval,
+            checkpoint_interval: Arc::new(AtomicU64::new(checkpoint_interval)),
             store,
         }
     }
 
+    pub(crate) fn checkpoint_interval(&self) -> u64 {
+        self.checkpoint_interval.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_checkpoint_interval(&self, checkpoint_interval: u64) {
+        self.checkpoint_interval
+            .store(checkpoint_interval, Ordering::Relaxed);
+    }
+
     pub fn object_store(&self) -> Arc<dyn ObjectStore> {
         Arc::clone(&self.store)
     }
@@ -81,6 +96,26 @@ impl ObjectStoreCatalog {
         }
     }
 
+    /// Get the sequence number covered by the checkpoint on object store, if there is one
+    pub(crate) async fn load_checkpoint_sequence(&self) -> Result<Option<CatalogSequenceNumber>> {
+        match self
+            .store
+            .get(&CatalogFilePath::checkpoint(&self.prefix))
+            .await
+        {
+            Ok(get_result) => {
+                let bytes = get_result.bytes().await?;
+                let sequence = verify_and_deserialize_catalog_checkpoint_sequence(bytes).context(
+                    "there was a catalog checkpoint file on object store, but its \
+                        sequence could not be read",
+                )?;
+                Ok(Some(sequence))
+            }
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
     /// Loads all catalog files from object store to build the catalog
     pub async fn load_catalog(&self) -> Result<Option<InnerCatalog>> {
         // get the checkpoint to initialize the catalog:
@@ -290,6 +325,32 @@ impl ObjectStoreCatalog {
         Ok(())
     }
 
+    /// Persist the `CatalogSnapshot` as a checkpoint, overwriting any existing checkpoint, and wait
+    /// for the operation to complete.
+    pub(crate) async fn overwrite_catalog_checkpoint(
+        &self,
+        snapshot: &CatalogSnapshot,
+    ) -> Result<()> {
+        let sequence = snapshot.sequence_number().get();
+        let catalog_path = CatalogFilePath::checkpoint(&self.prefix);
+
+        let content =
+            serialize_catalog_file(snapshot).context("failed to serialize catalog snapshot")?;
+
+        let put_result = self
+            .store
+            .put(&catalog_path, content.into())
+            .await
+            .inspect_err(|err| error!(error = ?err, "failed to persist catalog checkpoint file"))?;
+        info!(
+            sequence,
+            label = snapshot.label.as_deref(),
+            "persisted catalog checkpoint file"
+        );
+        debug!(put_result = ?put_result, "object store PUT result");
+        Ok(())
+    }
+
     async fn catalog_update_if_not_exists(
         &self,
         path: CatalogFilePath,
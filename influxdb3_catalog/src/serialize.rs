@@ -3,10 +3,11 @@ use std::io::Cursor;
 use anyhow::Context;
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Bytes, BytesMut};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     CatalogError, Result,
+    catalog::CatalogSequenceNumber,
     log::{self},
     snapshot::{self, CatalogSnapshot},
 };
@@ -109,6 +110,37 @@ pub fn verify_and_deserialize_catalog_checkpoint_file(bytes: Bytes) -> Result<Ca
     }
 }
 
+/// Verify a catalog checkpoint file and read only the sequence number that it covers
+///
+/// For JSON-encoded checkpoints only the sequence is deserialized, the rest of the snapshot is
+/// skipped over rather than built. Older `bitcode`-encoded checkpoints are fully deserialized.
+pub fn verify_and_deserialize_catalog_checkpoint_sequence(
+    bytes: Bytes,
+) -> Result<CatalogSequenceNumber> {
+    #[derive(Deserialize)]
+    struct SequenceOnly {
+        sequence: CatalogSequenceNumber,
+    }
+
+    let version_id: &[u8; 10] = bytes.first_chunk().ok_or(CatalogError::unexpected(
+        "file must contain at least 10 bytes",
+    ))?;
+
+    match *version_id {
+        snapshot::versions::v2::CatalogSnapshot::VERSION_ID
+        | snapshot::versions::v3::CatalogSnapshot::VERSION_ID => {
+            let checksum = bytes.slice(10..10 + CHECKSUM_LEN);
+            let data = bytes.slice(10 + CHECKSUM_LEN..);
+            verify_checksum(&checksum, &data)?;
+            let SequenceOnly { sequence } = serde_json::from_slice(&data)
+                .context("failed to deserialize sequence from catalog snapshot file contents")?;
+            Ok(sequence)
+        }
+        _ => verify_and_deserialize_catalog_checkpoint_file(bytes)
+            .map(|snapshot| snapshot.sequence_number()),
+    }
+}
+
 fn verify_checksum(checksum: &[u8], data: &[u8]) -> Result<()> {
     let mut cursor = Cursor::new(checksum);
     let crc32_checksum = cursor
@@ -850,6 +882,7 @@ mod v1_tests {
                             database_name: "test-db".into(),
                             deletion_time: 0,
                             hard_deletion_time: None,
+                            original_name: None,
                         },
                     ),
                     crate::log::DatabaseCatalogOp::CreateTable(crate::log::CreateTableLog {
@@ -904,6 +937,7 @@ mod v1_tests {
                             table_name: "test-table".into(),
                             deletion_time: 10,
                             hard_deletion_time: None,
+                            original_name: None,
                         },
                     ),
                     crate::log::DatabaseCatalogOp::AddFields(crate::log::AddFieldsLog {
@@ -1,2 +1,4 @@
+mod diff;
 pub(crate) mod versions;
+pub use diff::{CatalogDiff, ChangeKind, ColumnDiff, DatabaseDiff, TableDiff, diff};
 pub(crate) use versions::v3::*;
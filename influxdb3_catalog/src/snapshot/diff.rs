@@ -0,0 +1,274 @@
+//! Comparison of two [`CatalogSnapshot`]s, e.g., to see what a lagging node has yet to apply
+//!
+//! Resources are matched by their id rather than their name, since names can change, e.g., when a
+//! database or table is soft deleted.
+
+use std::sync::Arc;
+
+use influxdb3_id::{CatalogId, ColumnId, DbId, TableId};
+use serde::Serialize;
+
+use super::{CatalogSnapshot, DatabaseSnapshot, RepositorySnapshot, TableSnapshot};
+use crate::catalog::CatalogSequenceNumber;
+
+/// The databases, tables, and columns that differ between two catalog snapshots, see [`diff`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogDiff {
+    pub old_sequence: CatalogSequenceNumber,
+    pub new_sequence: CatalogSequenceNumber,
+    pub databases: Vec<DatabaseDiff>,
+}
+
+impl CatalogDiff {
+    /// Whether the snapshots hold the same databases, tables, and columns
+    pub fn is_empty(&self) -> bool {
+        self.databases.is_empty()
+    }
+}
+
+/// How a resource differs between two catalog snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The resource is only in the new snapshot
+    Added,
+    /// The resource is only in the old snapshot, i.e., it was hard deleted
+    Removed,
+    /// The resource is in both snapshots, but it, or one of the resources it contains, differs
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatabaseDiff {
+    pub id: DbId,
+    /// The name of the database in the new snapshot, or in the old one if it was removed
+    pub name: Arc<str>,
+    /// The name of the database in the old snapshot, if it was changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_name: Option<Arc<str>>,
+    pub change: ChangeKind,
+    pub tables: Vec<TableDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TableDiff {
+    pub id: TableId,
+    /// The name of the table in the new snapshot, or in the old one if it was removed
+    pub name: Arc<str>,
+    /// The name of the table in the old snapshot, if it was changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_name: Option<Arc<str>>,
+    pub change: ChangeKind,
+    pub columns: Vec<ColumnDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ColumnDiff {
+    pub id: ColumnId,
+    pub name: Arc<str>,
+    pub change: ChangeKind,
+}
+
+/// Compare two catalog snapshots
+///
+/// A database or table that was added or removed is reported along with all of its tables or
+/// columns. One that is in both snapshots is reported as changed if any of its own properties
+/// differ, e.g., it was soft deleted or its retention period was set, or if any of the tables or
+/// columns it contains were added, removed, or changed. Only the resources that differ are
+/// reported, ordered by id.
+pub fn diff(old: &CatalogSnapshot, new: &CatalogSnapshot) -> CatalogDiff {
+    CatalogDiff {
+        old_sequence: old.sequence,
+        new_sequence: new.sequence,
+        databases: diff_repositories(Some(&old.databases), Some(&new.databases), diff_database),
+    }
+}
+
+fn diff_database(
+    id: DbId,
+    old: Option<&DatabaseSnapshot>,
+    new: Option<&DatabaseSnapshot>,
+) -> Option<DatabaseDiff> {
+    let tables = diff_repositories(
+        old.map(|db| &db.tables),
+        new.map(|db| &db.tables),
+        diff_table,
+    );
+    // the last modified sequence changes along with the tables, which are compared separately:
+    let change = change_kind(
+        old,
+        new,
+        !tables.is_empty(),
+        &["tables", "last_modified_sequence"],
+    )?;
+    let (name, old_name) = names(old.map(|db| &db.name), new.map(|db| &db.name))?;
+    Some(DatabaseDiff {
+        id,
+        name,
+        old_name,
+        change,
+        tables,
+    })
+}
+
+fn diff_table(
+    id: TableId,
+    old: Option<&TableSnapshot>,
+    new: Option<&TableSnapshot>,
+) -> Option<TableDiff> {
+    let columns = diff_repositories(
+        old.map(|table| &table.columns),
+        new.map(|table| &table.columns),
+        |id, old, new| {
+            Some(ColumnDiff {
+                id,
+                change: change_kind(old, new, false, &[])?,
+                name: Arc::clone(&new.or(old)?.name),
+            })
+        },
+    );
+    let change = change_kind(old, new, !columns.is_empty(), &["columns"])?;
+    let (name, old_name) = names(
+        old.map(|table| &table.table_name),
+        new.map(|table| &table.table_name),
+    )?;
+    Some(TableDiff {
+        id,
+        name,
+        old_name,
+        change,
+        columns,
+    })
+}
+
+/// Compare the resources in two repositories by id, where a missing repository is treated as
+/// empty
+fn diff_repositories<I, R, D>(
+    old: Option<&RepositorySnapshot<I, R>>,
+    new: Option<&RepositorySnapshot<I, R>>,
+    diff_resource: impl Fn(I, Option<&R>, Option<&R>) -> Option<D>,
+) -> Vec<D>
+where
+    I: CatalogId,
+{
+    let mut ids: Vec<I> = old
+        .into_iter()
+        .chain(new)
+        .flat_map(|repo| repo.repo.keys().copied())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids.into_iter()
+        .filter_map(|id| {
+            diff_resource(
+                id,
+                old.and_then(|repo| repo.repo.get(&id)),
+                new.and_then(|repo| repo.repo.get(&id)),
+            )
+        })
+        .collect()
+}
+
+/// Determine how a resource differs between snapshots, if at all, comparing its serialized form
+/// without the `ignored_fields`, i.e., those holding resources that are compared separately
+fn change_kind<R: Serialize>(
+    old: Option<&R>,
+    new: Option<&R>,
+    contents_changed: bool,
+    ignored_fields: &[&str],
+) -> Option<ChangeKind> {
+    match (old, new) {
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (Some(old), Some(new)) => (contents_changed
+            || properties(old, ignored_fields) != properties(new, ignored_fields))
+        .then_some(ChangeKind::Changed),
+        (None, None) => None,
+    }
+}
+
+fn properties(resource: &impl Serialize, ignored_fields: &[&str]) -> serde_json::Value {
+    let mut value =
+        serde_json::to_value(resource).expect("catalog snapshot resources can be serialized");
+    if let Some(fields) = value.as_object_mut() {
+        for field in ignored_fields {
+            fields.remove(*field);
+        }
+    }
+    value
+}
+
+/// The name to report a resource by, and its old name, if it changed
+fn names(old: Option<&Arc<str>>, new: Option<&Arc<str>>) -> Option<(Arc<str>, Option<Arc<str>>)> {
+    let name = Arc::clone(new.or(old)?);
+    let old_name = old.filter(|old| **old != name).map(Arc::clone);
+    Some((name, old_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        catalog::{Catalog, HardDeletionTime},
+        log::FieldDataType,
+    };
+
+    use super::*;
+
+    #[test_log::test(tokio::test)]
+    async fn test_diff_added_table() {
+        let catalog = Catalog::new_in_memory("test").await.unwrap();
+        catalog.create_database("foo").await.unwrap();
+        let old = catalog.snapshot();
+        catalog
+            .create_table("foo", "cpu", &["host"], &[("usage", FieldDataType::Float)])
+            .await
+            .unwrap();
+        let new = catalog.snapshot();
+
+        let db_id = catalog.db_name_to_id("foo").unwrap();
+        let table_id = catalog
+            .db_schema("foo")
+            .unwrap()
+            .table_name_to_id("cpu")
+            .unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(old.sequence, changes.old_sequence);
+        assert_eq!(new.sequence, changes.new_sequence);
+        assert_eq!(1, changes.databases.len());
+        let db = &changes.databases[0];
+        assert_eq!(db_id, db.id);
+        assert_eq!(ChangeKind::Changed, db.change);
+        assert_eq!(1, db.tables.len());
+        let table = &db.tables[0];
+        assert_eq!(table_id, table.id);
+        assert_eq!("cpu", table.name.as_ref());
+        assert_eq!(ChangeKind::Added, table.change);
+        // the host tag, usage field, and time column:
+        assert_eq!(3, table.columns.len());
+        assert!(
+            table
+                .columns
+                .iter()
+                .all(|column| column.change == ChangeKind::Added)
+        );
+
+        // the diff is reversed when the snapshots are swapped:
+        let changes = diff(&new, &old);
+        assert_eq!(ChangeKind::Removed, changes.databases[0].tables[0].change);
+
+        // soft deleting the table renames it, which is reported as a change by its id:
+        catalog
+            .soft_delete_table("foo", "cpu", HardDeletionTime::Never)
+            .await
+            .unwrap();
+        let changes = diff(&new, &catalog.snapshot());
+        let table = &changes.databases[0].tables[0];
+        assert_eq!(table_id, table.id);
+        assert_eq!(ChangeKind::Changed, table.change);
+        assert_eq!(Some("cpu"), table.old_name.as_deref());
+        assert!(table.columns.is_empty());
+
+        // a snapshot does not differ from itself:
+        assert!(diff(&new, &new).is_empty());
+    }
+}
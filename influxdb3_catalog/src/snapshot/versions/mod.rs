@@ -19,6 +19,7 @@ use crate::{
     catalog::{
         ColumnDefinition, DatabaseSchema, GenerationConfig, InnerCatalog, NodeDefinition,
         NodeState, Repository, RetentionPeriod, TableDefinition, TokenRepository,
+        original_name_of_deleted,
     },
     log::{
         DistinctCacheDefinition, LastCacheDefinition, LastCacheTtl, LastCacheValueColumnsDef,
@@ -72,6 +73,8 @@ impl Snapshot for InnerCatalog {
             tokens: self.tokens.repo().snapshot(),
             catalog_uuid: self.catalog_uuid,
             generation_config: self.generation_config.snapshot(),
+            // carry the label forward so that interval checkpoints do not drop it:
+            label: self.checkpoint_label.clone(),
         }
     }
 
@@ -92,6 +95,7 @@ impl Snapshot for InnerCatalog {
             databases: Repository::from_snapshot(snap.databases),
             tokens: token_info_repo,
             generation_config: GenerationConfig::from_snapshot(snap.generation_config),
+            checkpoint_label: snap.label,
         }
     }
 }
@@ -135,6 +139,7 @@ impl Snapshot for TokenInfo {
                 .iter()
                 .map(|perm| perm.snapshot())
                 .collect(),
+            disabled: self.disabled,
         }
     }
 
@@ -154,6 +159,7 @@ impl Snapshot for TokenInfo {
                 .map(Permission::from_snapshot)
                 .collect(),
             description: snap.description,
+            disabled: snap.disabled,
         }
     }
 }
@@ -252,7 +258,7 @@ impl Snapshot for DatabaseActions {
     type Serialized = DatabaseActionsSnapshot;
 
     fn snapshot(&self) -> Self::Serialized {
-        DatabaseActionsSnapshot(u16::MAX)
+        DatabaseActionsSnapshot(self.0)
     }
 
     fn from_snapshot(snap: Self::Serialized) -> Self {
@@ -264,7 +270,7 @@ impl Snapshot for CrudActions {
     type Serialized = CrudActionsSnapshot;
 
     fn snapshot(&self) -> Self::Serialized {
-        CrudActionsSnapshot(u16::MAX)
+        CrudActionsSnapshot(self.0)
     }
 
     fn from_snapshot(snap: Self::Serialized) -> Self {
@@ -351,10 +357,16 @@ impl Snapshot for DatabaseSchema {
             processing_engine_triggers: self.processing_engine_triggers.snapshot(),
             deleted: self.deleted,
             hard_delete_time: self.hard_delete_time.as_ref().map(Time::timestamp_nanos),
+            original_name: self.original_name.as_ref().map(Arc::clone),
+            description: self.description.as_ref().map(Arc::clone),
+            created_time_ns: self.created_time_ns,
+            null_tag_placeholder: self.null_tag_placeholder.as_ref().map(Arc::clone),
+            last_modified_sequence: self.last_modified_sequence,
         }
     }
 
     fn from_snapshot(snap: Self::Serialized) -> Self {
+        let original_name = deleted_original_name(snap.deleted, snap.original_name, &snap.name);
         Self {
             id: snap.id,
             name: snap.name,
@@ -366,6 +378,11 @@ impl Snapshot for DatabaseSchema {
             processing_engine_triggers: Repository::from_snapshot(snap.processing_engine_triggers),
             deleted: snap.deleted,
             hard_delete_time: snap.hard_delete_time.map(Time::from_timestamp_nanos),
+            original_name,
+            description: snap.description,
+            created_time_ns: snap.created_time_ns,
+            null_tag_placeholder: snap.null_tag_placeholder,
+            last_modified_sequence: snap.last_modified_sequence,
         }
     }
 }
@@ -389,11 +406,20 @@ impl Snapshot for TableDefinition {
             distinct_caches: self.distinct_caches.snapshot(),
             deleted: self.deleted,
             hard_delete_time: self.hard_delete_time.as_ref().map(Time::timestamp_nanos),
+            original_name: self.original_name.as_ref().map(Arc::clone),
+            primary_partition_tag: self.primary_partition_tag,
+            // tables only record a retention period when one is set:
+            retention_period: match self.retention_period {
+                RetentionPeriod::Duration(_) => Some(self.retention_period.snapshot()),
+                RetentionPeriod::Indefinite => None,
+            },
         }
     }
 
     fn from_snapshot(snap: Self::Serialized) -> Self {
         let table_id = snap.table_id;
+        let original_name =
+            deleted_original_name(snap.deleted, snap.original_name, &snap.table_name);
         // use the TableDefinition constructor here since it handles
         // Schema construction:
         let mut table_def = Self::new(
@@ -434,10 +460,31 @@ impl Snapshot for TableDefinition {
             distinct_caches: Repository::from_snapshot(snap.distinct_caches),
             deleted: snap.deleted,
             hard_delete_time: snap.hard_delete_time.map(Time::from_timestamp_nanos),
+            original_name,
+            primary_partition_tag: snap.primary_partition_tag,
+            retention_period: snap
+                .retention_period
+                .map(Snapshot::from_snapshot)
+                .unwrap_or(RetentionPeriod::Indefinite),
         }
     }
 }
 
+/// The original name of a deleted database or table, which snapshots taken before original names
+/// were recorded do not have, so it is recovered from the name given to the resource on deletion
+fn deleted_original_name(
+    deleted: bool,
+    original_name: Option<Arc<str>>,
+    name: &Arc<str>,
+) -> Option<Arc<str>> {
+    if !deleted {
+        return None;
+    }
+    original_name.or_else(|| {
+        Some(original_name_of_deleted(name).map_or_else(|| Arc::clone(name), Arc::from))
+    })
+}
+
 impl Snapshot for TriggerDefinition {
     type Serialized = ProcessingEngineTriggerSnapshot;
 
@@ -480,6 +527,9 @@ impl Snapshot for ColumnDefinition {
             r#type: self.data_type.into(),
             influx_type: self.data_type.into(),
             nullable: self.nullable,
+            allowed_values: self.allowed_values.clone(),
+            indexed: self.indexed,
+            description: self.description.clone(),
         }
     }
 
@@ -493,6 +543,9 @@ impl Snapshot for ColumnDefinition {
                 InfluxType::Time => InfluxColumnType::Timestamp,
             },
             nullable: snap.nullable,
+            allowed_values: snap.allowed_values,
+            indexed: snap.indexed,
+            description: snap.description,
         }
     }
 }
@@ -12,6 +12,7 @@ impl From<super::CatalogSnapshot> for v3::CatalogSnapshot {
             catalog_uuid: value.catalog_uuid,
             tokens: value.tokens.into(),
             generation_config: Default::default(),
+            label: None,
         }
     }
 }
@@ -55,6 +56,7 @@ impl From<super::TokenInfoSnapshot> for v3::TokenInfoSnapshot {
             updated_by: value.updated_by,
             updated_at: value.updated_at,
             permissions: value.permissions.into_iter().map(Into::into).collect(),
+            disabled: false,
         }
     }
 }
@@ -142,6 +144,11 @@ impl From<super::DatabaseSnapshot> for v3::DatabaseSnapshot {
             processing_engine_triggers: value.processing_engine_triggers.into(),
             deleted: value.deleted,
             hard_delete_time: None,
+            original_name: None,
+            description: None,
+            created_time_ns: None,
+            null_tag_placeholder: None,
+            last_modified_sequence: None,
         }
     }
 }
@@ -157,6 +164,9 @@ impl From<super::TableSnapshot> for v3::TableSnapshot {
             distinct_caches: value.distinct_caches.into(),
             deleted: value.deleted,
             hard_delete_time: None,
+            original_name: None,
+            primary_partition_tag: None,
+            retention_period: None,
         }
     }
 }
@@ -169,6 +179,9 @@ impl From<super::ColumnDefinitionSnapshot> for v3::ColumnDefinitionSnapshot {
             r#type: value.r#type.into(),
             influx_type: value.influx_type.into(),
             nullable: value.nullable,
+            allowed_values: None,
+            indexed: false,
+            description: None,
         }
     }
 }
@@ -14,11 +14,12 @@ use influxdb3_id::{
 };
 use schema::{InfluxColumnType, InfluxFieldType, TIME_DATA_TIMEZONE};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CatalogSnapshot {
     // NOTE(tjh): added as part of https://github.com/influxdata/influxdb_pro/issues/911
     #[serde(default)]
@@ -30,6 +31,9 @@ pub struct CatalogSnapshot {
     pub(crate) tokens: RepositorySnapshot<TokenId, TokenInfoSnapshot>,
     pub(crate) catalog_id: Arc<str>,
     pub(crate) catalog_uuid: Uuid,
+    /// Optional operator-supplied label describing why the checkpoint was taken
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) label: Option<String>,
 }
 
 impl VersionedFileType for CatalogSnapshot {
@@ -42,12 +46,12 @@ impl CatalogSnapshot {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct GenerationConfigSnapshot {
     pub(crate) generation_durations: SerdeVecMap<u8, Duration>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct TokenInfoSnapshot {
     pub id: TokenId,
     pub name: Arc<str>,
@@ -59,9 +63,11 @@ pub(crate) struct TokenInfoSnapshot {
     pub updated_by: Option<TokenId>,
     pub updated_at: Option<i64>,
     pub permissions: Vec<PermissionSnapshot>,
+    #[serde(default)]
+    pub disabled: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct PermissionSnapshot {
     pub resource_type: ResourceTypeSnapshot,
     pub resource_identifier: ResourceIdentifierSnapshot,
@@ -98,7 +104,7 @@ pub(crate) struct CrudActionsSnapshot(pub u16);
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct SystemActionsSnapshot(pub u16);
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct NodeSnapshot {
     pub(crate) node_id: Arc<str>,
     pub(crate) node_catalog_id: NodeId,
@@ -108,13 +114,13 @@ pub(crate) struct NodeSnapshot {
     pub(crate) core_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum RetentionPeriodSnapshot {
     Indefinite,
     Duration(Duration),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct DatabaseSnapshot {
     pub(crate) id: DbId,
     pub(crate) name: Arc<str>,
@@ -124,9 +130,19 @@ pub(crate) struct DatabaseSnapshot {
         RepositorySnapshot<TriggerId, ProcessingEngineTriggerSnapshot>,
     pub(crate) deleted: bool,
     pub(crate) hard_delete_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) original_name: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) description: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) created_time_ns: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) null_tag_placeholder: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) last_modified_sequence: Option<CatalogSequenceNumber>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct TableSnapshot {
     pub(crate) table_id: TableId,
     pub(crate) table_name: Arc<str>,
@@ -138,9 +154,15 @@ pub(crate) struct TableSnapshot {
     // TODO(sgc): Remove `skip_serializing_if` when implementation is complete
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub(crate) hard_delete_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) original_name: Option<Arc<str>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) primary_partition_tag: Option<ColumnId>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) retention_period: Option<RetentionPeriodSnapshot>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ProcessingEngineTriggerSnapshot {
     pub trigger_id: TriggerId,
     pub trigger_name: Arc<str>,
@@ -154,7 +176,7 @@ pub(crate) struct ProcessingEngineTriggerSnapshot {
 }
 
 /// The inner column definition for a [`TableSnapshot`]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ColumnDefinitionSnapshot {
     pub(crate) name: Arc<str>,
     /// The id of the column
@@ -165,9 +187,18 @@ pub(crate) struct ColumnDefinitionSnapshot {
     pub(crate) influx_type: InfluxType,
     /// Whether the column can hold NULL values
     pub(crate) nullable: bool,
+    /// The values the column is restricted to, if any
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) allowed_values: Option<BTreeSet<Arc<str>>>,
+    /// Whether the column has been marked as indexed
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub(crate) indexed: bool,
+    /// A description of the column, if one has been set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub(crate) description: Option<Arc<str>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct LastCacheSnapshot {
     pub(crate) table_id: TableId,
     pub(crate) table: Arc<str>,
@@ -179,7 +210,7 @@ pub(crate) struct LastCacheSnapshot {
     pub(crate) ttl: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct DistinctCacheSnapshot {
     pub(crate) table_id: TableId,
     pub(crate) table: Arc<str>,
@@ -190,7 +221,7 @@ pub(crate) struct DistinctCacheSnapshot {
     pub(crate) max_age_seconds: MaxAge,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct RepositorySnapshot<I, R>
 where
     I: CatalogId,
@@ -255,7 +286,7 @@ impl From<arrow::datatypes::TimeUnit> for TimeUnit {
 }
 
 /// Used to annotate columns in a Schema by their respective type in the Influx Data Model
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum InfluxType {
     Tag,
@@ -370,7 +401,7 @@ impl From<&DataType> for InfluxFieldType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum NodeStateSnapshot {
     Running { registered_time_ns: i64 },
     Stopped { stopped_time_ns: i64 },
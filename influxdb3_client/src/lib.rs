@@ -8,7 +8,7 @@ use reqwest::{
     tls::Version,
 };
 use secrecy::{ExposeSecret, Secret};
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{fmt::Display, num::NonZeroUsize, path::PathBuf, string::FromUtf8Error, time::Duration};
 use url::Url;
 
@@ -75,6 +75,23 @@ impl Error {
             source,
         }
     }
+
+    /// Build an [`Error::ApiError`] from the body of an error response
+    ///
+    /// Catalog errors are sent as JSON with a `code` that identifies the error and an `error`
+    /// message, in which case the message is taken from `error`; any other body is used as is.
+    fn api_error(code: StatusCode, body: String) -> Self {
+        #[derive(Deserialize)]
+        struct CatalogErrorBody {
+            #[allow(dead_code)]
+            code: String,
+            error: String,
+        }
+        let message = serde_json::from_str::<CatalogErrorBody>(&body)
+            .map(|body| body.error)
+            .unwrap_or(body);
+        Self::ApiError { code, message }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -168,6 +185,7 @@ impl Client {
                 precision: None,
                 accept_partial: None,
                 no_sync: None,
+                create_db_retention: None,
             },
             body: NoBody,
         }
@@ -732,10 +750,10 @@ impl Client {
         if resp.status().is_success() {
             resp.json().await.map_err(Error::Json)
         } else {
-            Err(Error::ApiError {
-                code: resp.status(),
-                message: resp.text().await.map_err(Error::Text)?,
-            })
+            Err(Error::api_error(
+                resp.status(),
+                resp.text().await.map_err(Error::Text)?,
+            ))
         }
     }
 
@@ -893,10 +911,10 @@ impl Client {
 
         match status {
             s if s.is_success() => Ok(content),
-            code => Err(Error::ApiError {
+            code => Err(Error::api_error(
                 code,
-                message: String::from_utf8(content.to_vec()).map_err(Error::InvalidUtf8)?,
-            }),
+                String::from_utf8(content.to_vec()).map_err(Error::InvalidUtf8)?,
+            )),
         }
     }
 
@@ -935,10 +953,10 @@ impl Client {
                 Ok(Some(content))
             }
             StatusCode::NO_CONTENT => Ok(None),
-            code => Err(Error::ApiError {
+            code => Err(Error::api_error(
                 code,
-                message: resp.text().await.map_err(Error::Text)?,
-            }),
+                resp.text().await.map_err(Error::Text)?,
+            )),
         }
     }
 
@@ -974,10 +992,10 @@ impl Client {
         if status.is_success() {
             resp.json().await.map_err(Error::Json)
         } else {
-            Err(Error::ApiError {
-                code: resp.status(),
-                message: resp.text().await.map_err(Error::Text)?,
-            })
+            Err(Error::api_error(
+                resp.status(),
+                resp.text().await.map_err(Error::Text)?,
+            ))
         }
     }
 }
@@ -1362,9 +1380,10 @@ impl<'c> CreateDistinctCacheRequestBuilder<'c> {
 mod tests {
     use influxdb3_types::http::{LastCacheSize, LastCacheTtl};
     use mockito::{Matcher, Server};
+    use reqwest::StatusCode;
     use serde_json::json;
 
-    use crate::{Client, Precision, QueryFormat};
+    use crate::{Client, Error, Precision, QueryFormat};
 
     #[tokio::test]
     async fn api_v3_write_lp() {
@@ -1705,4 +1724,49 @@ mod tests {
             .unwrap();
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn api_error_message_from_catalog_error() {
+        let mut mock_server = Server::new_async().await;
+        mock_server
+            .mock("POST", "/api/v3/configure/database")
+            .with_status(409)
+            .with_body(
+                json!({
+                    "code": "catalog/already_exists",
+                    "error": "attempted to create a resource that already exists",
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        mock_server
+            .mock("DELETE", "/api/v3/configure/database")
+            .match_query(Matcher::Any)
+            .with_status(404)
+            .with_body("not found")
+            .create_async()
+            .await;
+        let client = Client::new(mock_server.url(), None).unwrap();
+
+        // the message of a catalog error is taken from its JSON body:
+        let Err(Error::ApiError { code, message }) =
+            client.api_v3_configure_db_create("foo", None).await
+        else {
+            panic!("expected an API error");
+        };
+        assert_eq!(code, StatusCode::CONFLICT);
+        assert_eq!(
+            message,
+            "attempted to create a resource that already exists"
+        );
+
+        // any other body is used as is:
+        let Err(Error::ApiError { code, message }) = client.api_v3_configure_db_delete("foo").await
+        else {
+            panic!("expected an API error");
+        };
+        assert_eq!(code, StatusCode::NOT_FOUND);
+        assert_eq!(message, "not found");
+    }
 }
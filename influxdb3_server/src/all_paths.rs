@@ -23,6 +23,8 @@ pub(crate) const API_V3_CONFIGURE_DATABASE: &str = "/api/v3/configure/database";
 pub(crate) const API_V3_CONFIGURE_TABLE: &str = "/api/v3/configure/table";
 pub(crate) const API_V3_CONFIGURE_DATABASE_RETENTION_PERIOD: &str =
     "/api/v3/configure/database/retention_period";
+pub(crate) const API_V3_CONFIGURE_DELETION_STATUS: &str = "/api/v3/configure/deletion_status";
+pub(crate) const API_V3_CONFIGURE_GENERATION_CONFIG: &str = "/api/v3/configure/generation_config";
 pub const API_METRICS: &str = "/metrics";
 pub const API_PING: &str = "/ping";
 pub(crate) const API_V3_CONFIGURE_TOKEN: &str = "/api/v3/configure/token";
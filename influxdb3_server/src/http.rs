@@ -28,7 +28,9 @@ use influxdb3_authz::{AuthProvider, NoAuthAuthenticator};
 use influxdb3_cache::distinct_cache;
 use influxdb3_cache::last_cache;
 use influxdb3_catalog::CatalogError;
-use influxdb3_catalog::catalog::HardDeletionTime;
+use influxdb3_catalog::catalog::{
+    DeletionStatus, HardDeletionTime, ValidateDbNameError, validate_db_name,
+};
 use influxdb3_catalog::log::FieldDataType;
 use influxdb3_internal_api::query_executor::{QueryExecutor, QueryExecutorError};
 use influxdb3_process::{
@@ -43,7 +45,6 @@ use influxdb3_write::WriteBuffer;
 use influxdb3_write::persister::TrackedMemoryArrowWriter;
 use influxdb3_write::write_buffer::Error as WriteBufferError;
 use iox_http::write::single_tenant::SingleTenantRequestUnifier;
-use iox_http::write::v1::V1_NAMESPACE_RP_SEPARATOR;
 use iox_http::write::{WriteParseError, WriteRequestUnifier};
 use iox_http_util::{
     Request, Response, ResponseBody, ResponseBuilder, bytes_to_response_body, empty_response_body,
@@ -66,7 +67,6 @@ use std::task::Poll;
 use std::{convert::Infallible, time::Duration};
 use thiserror::Error;
 use trace::ctx::SpanContext;
-use unicode_segmentation::UnicodeSegmentation;
 use uuid::Uuid;
 
 mod v1;
@@ -285,47 +285,55 @@ trait IntoResponse {
     fn into_response(self) -> Response;
 }
 
+/// The JSON body of a response for a [`CatalogError`]
+#[derive(Debug, Serialize)]
+struct CatalogErrorMessage {
+    code: &'static str,
+    error: String,
+}
+
 impl IntoResponse for CatalogError {
     fn into_response(self) -> Response {
-        match self {
-            Self::NotFound => ResponseBuilder::new()
-                .status(StatusCode::NOT_FOUND)
-                .body(bytes_to_response_body(self.to_string()))
-                .unwrap(),
-            Self::AlreadyExists | Self::AlreadyDeleted => ResponseBuilder::new()
-                .status(StatusCode::CONFLICT)
-                .body(bytes_to_response_body(self.to_string()))
-                .unwrap(),
+        let status = match self {
+            Self::NotFound | Self::TableNotFound { .. } | Self::ColumnNotFound { .. } => {
+                StatusCode::NOT_FOUND
+            }
+            Self::AlreadyExists
+            | Self::AlreadyDeleted
+            | Self::NotDeleted
+            | Self::CannotRenameDatabaseWithTriggers { .. }
+            | Self::CannotRenameTableWithTriggers { .. }
+            | Self::TokenNameAlreadyExists(_) => StatusCode::CONFLICT,
             Self::InvalidConfiguration { .. }
+            | Self::RetentionPeriodTooShort { .. }
+            | Self::InvalidDatabaseName { .. }
+            | Self::InvalidColumnName { .. }
+            | Self::InvalidTriggerSpecification { .. }
+            | Self::IncompatibleColumnTypeChange { .. }
             | Self::InvalidDistinctCacheColumnType
             | Self::InvalidLastCacheKeyColumnType
-            | Self::InvalidColumnType { .. } => ResponseBuilder::new()
-                .status(StatusCode::BAD_REQUEST)
-                .body(bytes_to_response_body(self.to_string()))
-                .unwrap(),
+            | Self::InvalidColumnType { .. } => StatusCode::BAD_REQUEST,
             Self::TooManyColumns(_)
             | Self::TooManyTables(_)
             | Self::TooManyDbs(_)
-            | Self::TooManyTagColumns => {
-                let err: ErrorMessage<()> = ErrorMessage {
-                    error: self.to_string(),
-                    data: None,
-                };
-                let serialized = serde_json::to_string(&err).unwrap();
-                let body = bytes_to_response_body(serialized);
-                ResponseBuilder::new()
-                    .status(StatusCode::UNPROCESSABLE_ENTITY)
-                    .body(body)
-                    .unwrap()
-            }
-            _ => {
-                let body = bytes_to_response_body(self.to_string());
-                ResponseBuilder::new()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .body(body)
-                    .unwrap()
+            | Self::TooManyTokens(_)
+            | Self::TooManyTagColumns => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::ReadOnlyCatalog => StatusCode::FORBIDDEN,
+            Self::CannotDeleteOperatorToken | Self::CannotModifyOperatorToken => {
+                StatusCode::METHOD_NOT_ALLOWED
             }
-        }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let err = CatalogErrorMessage {
+            code: self.code(),
+            error: self.to_string(),
+        };
+        let serialized = serde_json::to_string(&err).unwrap();
+        ResponseBuilder::new()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(bytes_to_response_body(serialized))
+            .unwrap()
     }
 }
 
@@ -334,16 +342,6 @@ impl IntoResponse for Error {
     fn into_response(self) -> Response {
         debug!(error = ?self, "API error");
         match self {
-            Self::Catalog(err @ CatalogError::CannotDeleteOperatorToken) => ResponseBuilder::new()
-                .status(StatusCode::METHOD_NOT_ALLOWED)
-                .body(bytes_to_response_body(err.to_string()))
-                .unwrap(),
-            Self::Catalog(err @ CatalogError::TokenNameAlreadyExists { .. }) => {
-                ResponseBuilder::new()
-                    .status(StatusCode::CONFLICT)
-                    .body(bytes_to_response_body(err.to_string()))
-                    .unwrap()
-            }
             Self::Catalog(err) | Self::WriteBuffer(WriteBufferError::CatalogUpdateError(err)) => {
                 err.into_response()
             }
@@ -597,6 +595,28 @@ impl HttpApi {
         accept_rp: bool,
     ) -> Result<Response> {
         validate_db_name(&params.db, accept_rp)?;
+        if let Some(retention) = params.create_db_retention.as_deref() {
+            let retention_period: Duration = retention
+                .parse::<humantime::Duration>()
+                .map_err(Error::ParsingHumanTime)?
+                .into();
+            let catalog = self.write_buffer.catalog();
+            if catalog.db_schema(&params.db).is_none() {
+                match catalog
+                    .create_database_opts(
+                        &params.db,
+                        influxdb3_catalog::catalog::CreateDatabaseOptions {
+                            retention_period: Some(retention_period),
+                        },
+                    )
+                    .await
+                {
+                    // the database may have been created concurrently:
+                    Ok(_) | Err(CatalogError::AlreadyExists) => {}
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        }
         let body = self.read_body(req).await?;
         let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
 
@@ -1493,6 +1513,61 @@ impl HttpApi {
             .unwrap())
     }
 
+    async fn deletion_status(&self, req: Request) -> Result<Response> {
+        let query = req.uri().query().unwrap_or("");
+        let DeletionStatusRequest { db, table } = serde_urlencoded::from_str(query)?;
+        let catalog = self.write_buffer.catalog();
+
+        let (status, hard_delete_time) = match catalog.db_schema(&db) {
+            None => (Some(DeletionStatus::NotFound), None),
+            Some(db_schema) => match table {
+                None => (
+                    catalog.database_deletion_status(db_schema.id),
+                    db_schema.hard_delete_time,
+                ),
+                Some(table) => match db_schema.table_definition(&table) {
+                    None => (Some(DeletionStatus::NotFound), None),
+                    Some(table_def) => (
+                        catalog.table_deletion_status(db_schema.id, table_def.table_id),
+                        // tables in a deleted database are removed along with the database:
+                        if db_schema.deleted {
+                            db_schema.hard_delete_time
+                        } else {
+                            table_def.hard_delete_time
+                        },
+                    ),
+                },
+            },
+        };
+        let (status, hard_deleted_for) = match status {
+            None => (DeletionState::Active, None),
+            Some(DeletionStatus::Soft) => (DeletionState::Soft, None),
+            Some(DeletionStatus::Hard(duration)) => (DeletionState::Hard, Some(duration)),
+            Some(DeletionStatus::NotFound) => (DeletionState::NotFound, None),
+        };
+        let response = DeletionStatusResponse {
+            status,
+            hard_deleted_for,
+            hard_delete_time: hard_delete_time.map(|time| time.date_time()),
+        };
+        let body = serde_json::to_vec(&response)?;
+
+        Ok(ResponseBuilder::new()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "json")
+            .body(bytes_to_response_body(body))?)
+    }
+
+    async fn generation_config(&self) -> Result<Response> {
+        let summary = self.write_buffer.catalog().generation_config_summary();
+        let body = serde_json::to_vec(&summary)?;
+
+        Ok(ResponseBuilder::new()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "json")
+            .body(bytes_to_response_body(body))?)
+    }
+
     async fn delete_token(&self, req: Request) -> Result<Response> {
         let query = req.uri().query().unwrap_or("");
         let delete_req = serde_urlencoded::from_str::<TokenDeleteRequest>(query)?;
@@ -1681,76 +1756,6 @@ impl From<authz::Error> for AuthenticationError {
     }
 }
 
-/// Validate a database name
-///
-/// A valid name:
-/// - Starts with a letter or a number
-/// - Is ASCII not UTF-8
-/// - Contains only letters, numbers, underscores or hyphens
-/// - if `accept_rp` is true, then a single slash ('/') is allowed, separating the
-///   the database name from the retention policy name, e.g., '<db_name>/<rp_name>'
-fn validate_db_name(name: &str, accept_rp: bool) -> Result<(), ValidateDbNameError> {
-    if name.is_empty() {
-        return Err(ValidateDbNameError::Empty);
-    }
-    let mut is_first_char = true;
-    let mut rp_seperator_found = false;
-    let mut last_char = None;
-    for grapheme in name.graphemes(true) {
-        if grapheme.len() > 1 {
-            // In the case of a unicode we need to handle multibyte chars
-            return Err(ValidateDbNameError::InvalidChar);
-        }
-        let char = grapheme.as_bytes()[0] as char;
-        if !is_first_char {
-            match (accept_rp, rp_seperator_found, char) {
-                (true, true, V1_NAMESPACE_RP_SEPARATOR) => {
-                    return Err(ValidateDbNameError::InvalidRetentionPolicy);
-                }
-                (true, false, V1_NAMESPACE_RP_SEPARATOR) => {
-                    rp_seperator_found = true;
-                }
-                (false, _, char)
-                    if !(char.is_ascii_alphanumeric() || char == '_' || char == '-') =>
-                {
-                    return Err(ValidateDbNameError::InvalidChar);
-                }
-                _ => (),
-            }
-        } else {
-            if !char.is_ascii_alphanumeric() {
-                return Err(ValidateDbNameError::InvalidStartChar);
-            }
-            is_first_char = false;
-        }
-        last_char.replace(char);
-    }
-
-    if last_char.is_some_and(|c| c == '/') {
-        return Err(ValidateDbNameError::InvalidRetentionPolicy);
-    }
-
-    Ok(())
-}
-
-#[derive(Clone, Copy, Debug, thiserror::Error)]
-pub enum ValidateDbNameError {
-    #[error(
-        "invalid character in database name: must be ASCII, \
-        containing only letters, numbers, underscores, or hyphens"
-    )]
-    InvalidChar,
-    #[error("db name did not start with a number or letter")]
-    InvalidStartChar,
-    #[error(
-        "db name with invalid retention policy, if providing a \
-        retention policy name, must be of form '<db_name>/<rp_name>'"
-    )]
-    InvalidRetentionPolicy,
-    #[error("db name cannot be empty")]
-    Empty,
-}
-
 async fn record_batch_stream_to_body(
     mut stream: Pin<Box<dyn RecordBatchStream + Send>>,
     format: QueryFormat,
@@ -2093,6 +2098,12 @@ pub(crate) async fn route_request(
         }
         (Method::POST, all_paths::API_V3_CONFIGURE_TABLE) => http_server.create_table(req).await,
         (Method::DELETE, all_paths::API_V3_CONFIGURE_TABLE) => http_server.delete_table(req).await,
+        (Method::GET, all_paths::API_V3_CONFIGURE_DELETION_STATUS) => {
+            http_server.deletion_status(req).await
+        }
+        (Method::GET, all_paths::API_V3_CONFIGURE_GENERATION_CONFIG) => {
+            http_server.generation_config().await
+        }
         (Method::POST, all_paths::API_V3_TEST_WAL_ROUTE) => {
             http_server.test_processing_engine_wal_plugin(req).await
         }
@@ -2192,10 +2203,8 @@ mod tests {
     use crate::http::AuthenticationError;
 
     use super::QueryFormat;
-    use super::ValidateDbNameError;
     use super::record_batch_stream_to_body;
     use super::token_part_as_bytes;
-    use super::validate_db_name;
     use arrow_array::{Int32Array, RecordBatch, record_batch};
     use datafusion::execution::SendableRecordBatchStream;
     use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
@@ -2204,13 +2213,6 @@ mod tests {
     use std::str;
     use std::sync::Arc;
 
-    macro_rules! assert_validate_db_name {
-        ($name:literal, $accept_rp:literal, $expected:pat) => {
-            let actual = validate_db_name($name, $accept_rp);
-            assert!(matches!(&actual, $expected), "got: {actual:?}",);
-        };
-    }
-
     #[test]
     fn test_try_from_headers_default_browser_accept_headers_to_json() {
         let mut map = HeaderMap::new();
@@ -2224,26 +2226,6 @@ mod tests {
         assert!(matches!(format, QueryFormat::Json));
     }
 
-    #[test]
-    fn test_validate_db_name() {
-        assert_validate_db_name!("foo/bar", false, Err(ValidateDbNameError::InvalidChar));
-        assert!(validate_db_name("foo/bar", true).is_ok());
-        assert_validate_db_name!(
-            "foo/bar/baz",
-            true,
-            Err(ValidateDbNameError::InvalidRetentionPolicy)
-        );
-        assert_validate_db_name!(
-            "foo/",
-            true,
-            Err(ValidateDbNameError::InvalidRetentionPolicy)
-        );
-        assert_validate_db_name!("foo/bar", false, Err(ValidateDbNameError::InvalidChar));
-        assert_validate_db_name!("foo/bar/baz", false, Err(ValidateDbNameError::InvalidChar));
-        assert_validate_db_name!("_foo", false, Err(ValidateDbNameError::InvalidStartChar));
-        assert_validate_db_name!("", false, Err(ValidateDbNameError::Empty));
-    }
-
     #[tokio::test]
     async fn test_json_output_empty() {
         // Turn RecordBatches into a Body and then collect into Bytes to assert
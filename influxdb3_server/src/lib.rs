@@ -321,13 +321,14 @@ mod tests {
     use influxdb3_cache::distinct_cache::DistinctCacheProvider;
     use influxdb3_cache::last_cache::LastCacheProvider;
     use influxdb3_cache::parquet_cache::test_cached_obj_store_and_oracle;
-    use influxdb3_catalog::catalog::Catalog;
+    use influxdb3_catalog::catalog::{Catalog, RetentionPeriod};
     use influxdb3_processing_engine::ProcessingEngineManagerImpl;
     use influxdb3_processing_engine::environment::DisabledManager;
     use influxdb3_processing_engine::plugins::ProcessingEngineEnvironmentManager;
     use influxdb3_shutdown::ShutdownManager;
     use influxdb3_sys_events::SysEventStore;
     use influxdb3_telemetry::store::TelemetryStore;
+    use influxdb3_types::http::{DeletionState, DeletionStatusResponse};
     use influxdb3_wal::WalConfig;
     use influxdb3_write::persister::Persister;
     use influxdb3_write::write_buffer::N_SNAPSHOTS_TO_LOAD_ON_START;
@@ -345,6 +346,7 @@ mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
     use std::num::NonZeroUsize;
     use std::sync::{Arc, OnceLock};
+    use std::time::Duration;
     use tokio::net::TcpListener;
     use tokio_util::sync::CancellationToken;
 
@@ -1254,6 +1256,217 @@ mod tests {
         shutdown.cancel();
     }
 
+    #[tokio::test]
+    async fn deletion_status_for_soft_deleted_database_and_table() {
+        let start_time = 0;
+        let (server, shutdown, write_buffer) = setup_server(start_time).await;
+
+        write_lp(
+            &server,
+            "foo",
+            "cpu,host=a val=1i 123\nmem,host=a val=1i 123",
+            None,
+            false,
+            "nanosecond",
+        )
+        .await;
+        write_lp(
+            &server,
+            "bar",
+            "cpu,host=a val=1i 123",
+            None,
+            false,
+            "nanosecond",
+        )
+        .await;
+
+        let client = Client::new();
+        for url in [
+            format!("{server}/api/v3/configure/database?db=bar"),
+            format!("{server}/api/v3/configure/table?db=foo&table=cpu&hard_delete_at=never"),
+        ] {
+            let request = RequestBuilder::new()
+                .uri(url)
+                .method("DELETE")
+                .body(empty_request_body())
+                .expect("failed to construct HTTP request");
+            let response = client.request(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let catalog = write_buffer.catalog();
+        let deleted_db = catalog
+            .list_db_schema()
+            .into_iter()
+            .find(|db| db.deleted && db.name.starts_with("bar"))
+            .expect("deleted database should exist");
+        let deleted_table = catalog
+            .db_schema("foo")
+            .unwrap()
+            .tables()
+            .find(|table| table.deleted)
+            .expect("deleted table should exist");
+
+        // the deleted database is scheduled for hard deletion after the default duration:
+        let status = deletion_status(&server, &deleted_db.name, None).await;
+        assert_eq!(status.status, DeletionState::Soft);
+        assert_eq!(
+            status
+                .hard_delete_time
+                .unwrap()
+                .timestamp_nanos_opt()
+                .unwrap(),
+            start_time + Catalog::DEFAULT_HARD_DELETE_DURATION.as_nanos() as i64
+        );
+
+        // the deleted table is never hard deleted:
+        let status = deletion_status(&server, "foo", Some(&deleted_table.table_name)).await;
+        assert_eq!(status.status, DeletionState::Soft);
+        assert!(status.hard_delete_time.is_none());
+
+        // tables that were not deleted are active:
+        let status = deletion_status(&server, "foo", Some("mem")).await;
+        assert_eq!(status.status, DeletionState::Active);
+        assert!(status.hard_delete_time.is_none());
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn deletion_status_for_resources_not_found() {
+        let (server, shutdown, _) = setup_server(0).await;
+
+        write_lp(
+            &server,
+            "foo",
+            "cpu,host=a val=1i 123",
+            None,
+            false,
+            "nanosecond",
+        )
+        .await;
+
+        for (db, table) in [("bar", None), ("bar", Some("cpu")), ("foo", Some("mem"))] {
+            let status = deletion_status(&server, db, table).await;
+            assert_eq!(status.status, DeletionState::NotFound);
+            assert!(status.hard_deleted_for.is_none());
+            assert!(status.hard_delete_time.is_none());
+        }
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn query_soft_deleted_database_is_not_found() {
+        let (server, shutdown, write_buffer) = setup_server(0).await;
+
+        write_lp(
+            &server,
+            "bar",
+            "cpu,host=a val=1i 123",
+            None,
+            false,
+            "nanosecond",
+        )
+        .await;
+        let response = query(&server, "bar", "SELECT * FROM cpu", "json", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let client = Client::new();
+        let request = RequestBuilder::new()
+            .uri(format!("{server}/api/v3/configure/database?db=bar"))
+            .method("DELETE")
+            .body(empty_request_body())
+            .expect("failed to construct HTTP request");
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let deleted_name = write_buffer
+            .catalog()
+            .list_db_schema()
+            .into_iter()
+            .find(|db| db.deleted && db.name.starts_with("bar"))
+            .expect("deleted database should exist")
+            .name
+            .to_string();
+
+        // neither the original name nor the name given to the database on deletion can be
+        // queried:
+        for db in ["bar", deleted_name.as_str()] {
+            let db = urlencoding::encode(db).into_owned();
+            let response = query(&server, db.as_str(), "SELECT * FROM cpu", "json", None).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+            let request = RequestBuilder::new()
+                .uri(format!(
+                    "{server}/api/v3/query_influxql?db={db}&q={}",
+                    urlencoding::encode("SELECT * FROM cpu")
+                ))
+                .method("GET")
+                .body(empty_request_body())
+                .expect("failed to construct HTTP request");
+            let response = client.request(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn write_lp_creates_database_with_retention() {
+        let (server, shutdown, write_buffer) = setup_server(0).await;
+        let client = Client::new();
+
+        let url = format!("{server}/api/v3/write_lp?db=new_db&create_db_retention=7d");
+        let request = RequestBuilder::new()
+            .uri(url)
+            .method("POST")
+            .body(bytes_to_request_body("cpu,host=a val=1i 123"))
+            .expect("failed to construct HTTP request");
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let db_schema = write_buffer.catalog().db_schema("new_db").unwrap();
+        assert_eq!(
+            db_schema.retention_period,
+            RetentionPeriod::Duration(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert!(db_schema.table_definition("cpu").is_some());
+
+        // an unparseable retention period is rejected without creating the database:
+        let url = format!("{server}/api/v3/write_lp?db=other_db&create_db_retention=soon");
+        let request = RequestBuilder::new()
+            .uri(url)
+            .method("POST")
+            .body(bytes_to_request_body("cpu,host=a val=1i 123"))
+            .expect("failed to construct HTTP request");
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(write_buffer.catalog().db_schema("other_db").is_none());
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn generation_config_reports_catalog_summary() {
+        let (server, shutdown, write_buffer) = setup_server(0).await;
+
+        let request = RequestBuilder::new()
+            .uri(format!("{server}/api/v3/configure/generation_config"))
+            .method("GET")
+            .body(empty_request_body())
+            .expect("failed to construct HTTP request");
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = read_body_bytes_for_tests(response.into_body()).await;
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            summary,
+            serde_json::to_value(write_buffer.catalog().generation_config_summary()).unwrap()
+        );
+
+        shutdown.cancel();
+    }
+
     #[tokio::test]
     async fn query_from_last_cache() {
         let start_time = 0;
@@ -1563,6 +1776,29 @@ mod tests {
         (format!("http://{addr}"), shutdown, write_buffer)
     }
 
+    async fn deletion_status(
+        server: &str,
+        db: &str,
+        table: Option<&str>,
+    ) -> DeletionStatusResponse {
+        let mut url = format!(
+            "{server}/api/v3/configure/deletion_status?db={}",
+            urlencoding::encode(db)
+        );
+        if let Some(table) = table {
+            url.push_str(&format!("&table={}", urlencoding::encode(table)));
+        }
+        let request = RequestBuilder::new()
+            .uri(url)
+            .method("GET")
+            .body(empty_request_body())
+            .expect("failed to construct HTTP request");
+        let response = Client::new().request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = read_body_bytes_for_tests(response.into_body()).await;
+        serde_json::from_slice(&body).unwrap()
+    }
+
     pub(crate) async fn write_lp(
         server: impl Into<String> + Send,
         database: impl Into<String> + Send,
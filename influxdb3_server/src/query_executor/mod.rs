@@ -21,7 +21,9 @@ use datafusion_util::config::DEFAULT_SCHEMA;
 use influxdb_influxql_parser::statement::Statement;
 use influxdb3_cache::distinct_cache::{DISTINCT_CACHE_UDTF_NAME, DistinctCacheFunction};
 use influxdb3_cache::last_cache::{LAST_CACHE_UDTF_NAME, LastCacheFunction};
-use influxdb3_catalog::catalog::{Catalog, DatabaseSchema, TableDefinition};
+use influxdb3_catalog::catalog::{
+    Catalog, DatabaseSchema, TableDefinition, split_influxql_database_name,
+};
 use influxdb3_internal_api::query_executor::{QueryExecutor, QueryExecutorError};
 use influxdb3_sys_events::SysEventStore;
 use influxdb3_telemetry::store::TelemetryStore;
@@ -231,10 +233,10 @@ impl QueryExecutor for QueryExecutorImpl {
                     db_name: database.to_string(),
                 })?;
             let duration = db.retention_time_ns();
-            let (db_name, rp_name) = split_database_name(&database);
+            let (db_name, rp_name) = split_influxql_database_name(&database);
             rows.push(RetentionPolicyRow {
-                database: db_name,
-                name: rp_name,
+                database: db_name.to_owned(),
+                name: rp_name.to_owned(),
                 duration,
             });
         }
@@ -416,16 +418,6 @@ fn retention_policy_rows_to_batch(rows: &[RetentionPolicyRow]) -> RecordBatch {
     RecordBatch::from(&builder.finish())
 }
 
-const AUTOGEN_RETENTION_POLICY: &str = "autogen";
-
-fn split_database_name(db_name: &str) -> (String, String) {
-    let mut split = db_name.split('/');
-    (
-        split.next().unwrap().to_owned(),
-        split.next().unwrap_or(AUTOGEN_RETENTION_POLICY).to_owned(),
-    )
-}
-
 // This implementation is for the Flight service
 #[async_trait]
 impl QueryDatabase for QueryExecutorImpl {
@@ -437,7 +429,7 @@ impl QueryDatabase for QueryExecutorImpl {
         _include_debug_info_tables: bool,
     ) -> Result<Option<Arc<dyn QueryNamespace>>, DataFusionError> {
         let _span_recorder = SpanRecorder::new(span);
-        let db_schema = self.catalog.db_schema(name).ok_or_else(|| {
+        let db_schema = self.catalog.active_db_schema(name).ok_or_else(|| {
             DataFusionError::External(Box::new(QueryExecutorError::DatabaseNotFound {
                 db_name: name.into(),
             }))
@@ -686,11 +678,14 @@ impl QueryTable {
             .map_err(|error| DataFusionError::External(Box::new(error)))?;
 
         let catalog = self.write_buffer.catalog();
+        // a table's own retention period applies in addition to that of its database:
         let retention_period_cutoff = match self
             .db_schema
-            .get_retention_period_cutoff_ts_nanos(catalog.time_provider())
+            .effective_retention_for_table(&self.table_def.table_id)
         {
-            Some(time) => time,
+            Some(retention_period) => {
+                catalog.time_provider().now().timestamp_nanos() - retention_period.as_nanos() as i64
+            }
             None => {
                 return self.write_buffer.get_table_chunks(
                     Arc::clone(&self.db_schema),
@@ -1349,6 +1344,42 @@ mod tests {
         );
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_databases_sys_table_description() {
+        let (write_buffer, query_exec, _, _) = setup(None, true).await;
+        let catalog = write_buffer.catalog();
+        catalog.create_database("foo").await.unwrap();
+        catalog.create_database("bar").await.unwrap();
+        catalog
+            .set_database_description("foo", Some("sensor readings"))
+            .await
+            .unwrap();
+
+        let query = "select database_name, description FROM system.databases";
+        let stream = query_exec
+            .query_sql("_internal", query, None, None, None)
+            .await
+            .unwrap();
+        let batches: Vec<RecordBatch> = stream.try_collect().await.unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+---------------+-----------------+",
+                "| database_name | description     |",
+                "+---------------+-----------------+",
+                "| _internal     |                 |",
+                "| bar           |                 |",
+                "| foo           | sensor readings |",
+                "+---------------+-----------------+",
+            ],
+            &batches
+        );
+
+        // the description does not change the output of SHOW DATABASES:
+        let stream = query_exec.show_databases(false).unwrap();
+        let batches: Vec<RecordBatch> = stream.try_collect().await.unwrap();
+        assert_eq!(batches[0].schema().fields().len(), 1);
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_token_permissions_sys_table_query_with_auth() {
         let (write_buffer, query_exec, _, _) = setup(None, true).await;
@@ -1408,4 +1439,76 @@ mod tests {
             );
         }
     }
+
+    #[test_log::test(tokio::test)]
+    async fn query_applies_table_retention_period() {
+        let (write_buffer, query_executor, time_provider, _) = setup(None, true).await;
+        let db_name = "test_db";
+        let now = Time::from_timestamp(7_200, 0).unwrap();
+        time_provider.set(now);
+        // write one line from 90 minutes ago, and one from 10 minutes ago, to each table:
+        write_buffer
+            .write_lp(
+                NamespaceName::new(db_name).unwrap(),
+                "\
+                cpu,host=a usage=1 1800000000000\n\
+                cpu,host=b usage=2 6600000000000\n\
+                mem,host=a usage=3 1800000000000\n\
+                mem,host=b usage=4 6600000000000\n\
+                ",
+                now,
+                false,
+                influxdb3_write::Precision::Nanosecond,
+                false,
+            )
+            .await
+            .unwrap();
+        let catalog = write_buffer.catalog();
+        catalog
+            .set_retention_period_for_database(db_name, Duration::from_secs(86_400))
+            .await
+            .unwrap();
+        catalog
+            .set_retention_period_for_table(db_name, "cpu", Duration::from_secs(3_600))
+            .await
+            .unwrap();
+
+        // the table's retention period is shorter than the database's, so it applies to cpu:
+        let batches: Vec<RecordBatch> = query_executor
+            .query_sql(db_name, "SELECT host, usage FROM cpu", None, None, None)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+-------+",
+                "| host | usage |",
+                "+------+-------+",
+                "| b    | 2.0   |",
+                "+------+-------+",
+            ],
+            &batches
+        );
+        // while only the database's retention period applies to mem:
+        let batches: Vec<RecordBatch> = query_executor
+            .query_sql(db_name, "SELECT host, usage FROM mem", None, None, None)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+-------+",
+                "| host | usage |",
+                "+------+-------+",
+                "| a    | 3.0   |",
+                "| b    | 4.0   |",
+                "+------+-------+",
+            ],
+            &batches
+        );
+    }
 }
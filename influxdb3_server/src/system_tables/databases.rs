@@ -33,6 +33,7 @@ fn databases_schema() -> SchemaRef {
             DataType::Timestamp(TimeUnit::Second, Some(DEFAULT_TIMEZONE.into())),
             true,
         ),
+        Field::new("description", DataType::Utf8View, true),
     ];
     Arc::new(Schema::new(columns))
 }
@@ -57,6 +58,7 @@ impl IoxSystemTable for DatabasesTable {
             arrow::array::TimestampSecondBuilder::with_capacity(databases.len()).with_data_type(
                 DataType::Timestamp(TimeUnit::Second, Some(DEFAULT_TIMEZONE.into())),
             );
+        let mut description_arr = StringViewBuilder::with_capacity(databases.len());
 
         for db in databases {
             database_name_arr.append_value(&db.name);
@@ -75,6 +77,8 @@ impl IoxSystemTable for DatabasesTable {
             } else {
                 hard_deletion_time_arr.append_null()
             }
+
+            description_arr.append_option(db.description.as_deref());
         }
 
         let columns: Vec<ArrayRef> = vec![
@@ -82,6 +86,7 @@ impl IoxSystemTable for DatabasesTable {
             Arc::new(retention_period_arr.finish()),
             Arc::new(deleted_arr.finish()),
             Arc::new(hard_deletion_time_arr.finish()),
+            Arc::new(description_arr.finish()),
         ];
 
         RecordBatch::try_new(self.schema(), columns).map_err(DataFusionError::from)
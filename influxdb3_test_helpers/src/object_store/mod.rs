@@ -22,6 +22,8 @@ pub struct RequestCountedObjectStore {
     get_range: RequestCounter,
     get_ranges: RequestCounter,
     head: RequestCounter,
+    put: RequestCounter,
+    put_opts: RequestCounter,
 }
 
 impl RequestCountedObjectStore {
@@ -33,6 +35,8 @@ impl RequestCountedObjectStore {
             get_range: Default::default(),
             get_ranges: Default::default(),
             head: Default::default(),
+            put: Default::default(),
+            put_opts: Default::default(),
         }
     }
 
@@ -65,6 +69,20 @@ impl RequestCountedObjectStore {
     pub fn head_request_count(&self, path: &Path) -> usize {
         self.head.read().get(path).copied().unwrap_or(0)
     }
+
+    /// Get the total request count accross WRITE-style requests for all paths in the inner object
+    /// store.
+    pub fn total_write_request_count(&self) -> usize {
+        self.put.read().values().sum::<usize>() + self.put_opts.read().values().sum::<usize>()
+    }
+
+    pub fn put_request_count(&self, path: &Path) -> usize {
+        self.put.read().get(path).copied().unwrap_or(0)
+    }
+
+    pub fn put_opts_request_count(&self, path: &Path) -> usize {
+        self.put_opts.read().get(path).copied().unwrap_or(0)
+    }
 }
 
 impl std::fmt::Display for RequestCountedObjectStore {
@@ -76,6 +94,7 @@ impl std::fmt::Display for RequestCountedObjectStore {
 #[async_trait]
 impl ObjectStore for RequestCountedObjectStore {
     async fn put(&self, location: &Path, bytes: PutPayload) -> object_store::Result<PutResult> {
+        *self.put.write().entry(location.clone()).or_insert(0) += 1;
         self.inner.put(location, bytes).await
     }
 
@@ -85,6 +104,7 @@ impl ObjectStore for RequestCountedObjectStore {
         bytes: PutPayload,
         opts: PutOptions,
     ) -> object_store::Result<PutResult> {
+        *self.put_opts.write().entry(location.clone()).or_insert(0) += 1;
         self.inner.put_opts(location, bytes, opts).await
     }
 
@@ -334,6 +334,47 @@ pub struct DeleteTableRequest {
     pub hard_delete_at: Option<HardDeletionTime>,
 }
 
+/// Request definition for the `GET /api/v3/configure/deletion_status` API
+///
+/// If `table` is provided, the status of that table in `db` is returned, otherwise the status of
+/// `db` itself is returned.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeletionStatusRequest {
+    pub db: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+}
+
+/// The deletion state of a database or table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionState {
+    /// The resource exists and has not been deleted
+    Active,
+    /// The resource has been soft deleted but not yet hard deleted
+    Soft,
+    /// The resource is past its hard deletion time
+    Hard,
+    /// The resource was not found in the catalog
+    NotFound,
+}
+
+/// Response definition for the `GET /api/v3/configure/deletion_status` API
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeletionStatusResponse {
+    pub status: DeletionState,
+    /// How long ago the resource's hard deletion time passed, only set for the `hard` state
+    #[serde(
+        with = "humantime_serde",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hard_deleted_for: Option<Duration>,
+    /// When the resource is scheduled to be hard deleted, if at all
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hard_delete_time: Option<DateTime<Utc>>,
+}
+
 pub type ClientQueryRequest = QueryRequest<String, Option<QueryFormat>, StatementParams>;
 
 /// Request definition for the `POST /api/v3/query_sql` and `POST /api/v3/query_influxql` APIs
@@ -400,6 +441,9 @@ pub struct WriteParams {
     pub precision: Option<Precision>,
     pub accept_partial: Option<bool>,
     pub no_sync: Option<bool>,
+    /// If the database does not exist, create it with this retention period, e.g., `7d`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub create_db_retention: Option<String>,
 }
 
 impl From<iox_http::write::WriteParams> for WriteParams {
@@ -410,6 +454,7 @@ impl From<iox_http::write::WriteParams> for WriteParams {
             accept_partial: Some(false),
             precision: Some(legacy.precision.into()),
             no_sync: Some(false),
+            create_db_retention: None,
         }
     }
 }
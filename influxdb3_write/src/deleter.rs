@@ -54,6 +54,7 @@ pub fn run(
                     Task::NotifyDeleteDatabase {
                         db_id: db_schema.id(),
                         object_deleter: Arc::clone(object_deleter),
+                        catalog: Arc::clone(&catalog),
                     },
                 );
             }
@@ -80,6 +81,7 @@ pub fn run(
                         db_id: db_schema.id(),
                         table_id: table_def.id(),
                         object_deleter: Arc::clone(object_deleter),
+                        catalog: Arc::clone(&catalog),
                     },
                 );
             }
@@ -131,12 +133,14 @@ enum Task {
     NotifyDeleteDatabase {
         db_id: DbId,
         object_deleter: Arc<dyn ObjectDeleter>,
+        catalog: Arc<Catalog>,
     },
     /// Notify the object_deleter that the specified table should be deleted.
     NotifyDeleteTable {
         db_id: DbId,
         table_id: TableId,
         object_deleter: Arc<dyn ObjectDeleter>,
+        catalog: Arc<Catalog>,
     },
     /// Remove the database from the catalog.
     DeleteDatabase { db_id: DbId, catalog: Arc<Catalog> },
@@ -154,7 +158,12 @@ impl Task {
             Task::NotifyDeleteDatabase {
                 db_id,
                 object_deleter,
+                catalog,
             } => {
+                if is_database_undeleted(&catalog, &db_id) {
+                    info!(?db_id, "Database was undeleted, not deleting its objects.");
+                    return;
+                }
                 info!(?db_id, "Notify object_deleter to delete database.");
                 object_deleter.delete_database(db_id);
             }
@@ -162,11 +171,27 @@ impl Task {
                 db_id,
                 table_id,
                 object_deleter,
+                catalog,
             } => {
+                if is_table_undeleted(&catalog, &db_id, &table_id) {
+                    info!(
+                        ?db_id,
+                        ?table_id,
+                        "Table was undeleted, not deleting its objects."
+                    );
+                    return;
+                }
                 info!(?db_id, ?table_id, "Notify object_deleter to delete table.");
                 object_deleter.delete_table(db_id, table_id);
             }
             Task::DeleteDatabase { db_id, catalog } => {
+                if is_database_undeleted(&catalog, &db_id) {
+                    info!(
+                        ?db_id,
+                        "Database was undeleted, skipping delete database task."
+                    );
+                    return;
+                }
                 info!(?db_id, "Processing delete database task.");
                 match catalog.hard_delete_database(&db_id).await {
                     Err(CatalogError::NotFound) | Ok(_) => {}
@@ -184,6 +209,14 @@ impl Task {
                 table_id,
                 catalog,
             } => {
+                if is_table_undeleted(&catalog, &db_id, &table_id) {
+                    info!(
+                        ?db_id,
+                        ?table_id,
+                        "Table was undeleted, skipping delete table task."
+                    );
+                    return;
+                }
                 info!(?db_id, ?table_id, "Processing delete table task.");
                 match catalog.hard_delete_table(&db_id, &table_id).await {
                     Err(CatalogError::NotFound) | Ok(_) => {}
@@ -196,6 +229,21 @@ impl Task {
     }
 }
 
+/// Whether the database is no longer deleted, i.e., it was undeleted after its hard deletion was
+/// scheduled.
+fn is_database_undeleted(catalog: &Catalog, db_id: &DbId) -> bool {
+    catalog.db_schema_by_id(db_id).is_some_and(|db| !db.deleted)
+}
+
+/// Whether the table is no longer deleted, i.e., it was undeleted after its hard deletion was
+/// scheduled.
+fn is_table_undeleted(catalog: &Catalog, db_id: &DbId, table_id: &TableId) -> bool {
+    catalog
+        .db_schema_by_id(db_id)
+        .and_then(|db| db.table_definition_by_id(table_id))
+        .is_some_and(|table| !table.deleted)
+}
+
 struct DeleteManager {
     tasks: async_collections::PriorityQueue<Task>,
     catalog: Arc<Catalog>,
@@ -227,6 +275,7 @@ fn background_catalog_update(
                                     Task::NotifyDeleteDatabase {
                                         db_id: *database_id,
                                         object_deleter: Arc::clone(object_deleter),
+                                        catalog: Arc::clone(&manager.catalog),
                                     },
                                 );
                             }
@@ -252,6 +301,7 @@ fn background_catalog_update(
                                         db_id: *database_id,
                                         table_id: *table_id,
                                         object_deleter: Arc::clone(object_deleter),
+                                        catalog: Arc::clone(&manager.catalog),
                                     },
                                 );
                             }
@@ -241,6 +241,10 @@ impl WriteBufferImpl {
             persisted_files: Arc::clone(&persisted_files),
             parquet_cache: parquet_cache.clone(),
         }));
+        queryable_buffer::background_catalog_update(
+            Arc::clone(&queryable_buffer),
+            catalog.subscribe_to_updates("write_buffer").await,
+        );
 
         // create the wal instance, which will replay into the queryable buffer and start
         // the background flush task.
@@ -18,7 +18,9 @@ use hashbrown::HashMap;
 use influxdb3_cache::parquet_cache::{CacheRequest, ParquetCacheOracle};
 use influxdb3_cache::{distinct_cache::DistinctCacheProvider, last_cache::LastCacheProvider};
 use influxdb3_catalog::catalog::{Catalog, DatabaseSchema, TableDefinition};
-use influxdb3_id::{DbId, TableId};
+use influxdb3_catalog::channel::CatalogUpdateReceiver;
+use influxdb3_catalog::log::{CatalogBatch, DatabaseCatalogOp, DropColumnLog};
+use influxdb3_id::{ColumnId, DbId, TableId};
 use influxdb3_wal::{SnapshotDetails, WalContents, WalFileNotifier, WalOp, WriteBatch};
 use iox_query::QueryChunk;
 use iox_query::chunk_statistics::{NoColumnRanges, create_chunk_statistics};
@@ -454,6 +456,12 @@ impl QueryableBuffer {
         buffer.db_to_table.remove(db_id);
     }
 
+    /// Remove the buffered values of a column that was dropped from the catalog
+    pub fn drop_column(&self, db_id: &DbId, table_id: &TableId, column_id: ColumnId) {
+        let mut buffer = self.buffer.write();
+        buffer.drop_column(db_id, table_id, column_id);
+    }
+
     pub fn get_total_size_bytes(&self) -> usize {
         let buffer = self.buffer.read();
         buffer.find_overall_buffer_size_bytes()
@@ -515,6 +523,16 @@ impl BufferState {
         }
     }
 
+    fn drop_column(&mut self, db_id: &DbId, table_id: &TableId, column_id: ColumnId) {
+        if let Some(table_buffer) = self
+            .db_to_table
+            .get_mut(db_id)
+            .and_then(|tables| tables.get_mut(table_id))
+        {
+            table_buffer.drop_column(column_id);
+        }
+    }
+
     pub fn find_overall_buffer_size_bytes(&self) -> usize {
         let mut total = 0;
         for (_, all_tables) in &self.db_to_table {
@@ -526,6 +544,33 @@ impl BufferState {
     }
 }
 
+/// Apply the catalog updates that affect data already in the buffer
+pub(crate) fn background_catalog_update(
+    buffer: Arc<QueryableBuffer>,
+    mut subscription: CatalogUpdateReceiver,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(catalog_update) = subscription.recv().await {
+            for batch in catalog_update
+                .batches()
+                .filter_map(CatalogBatch::as_database)
+            {
+                for op in batch.ops.iter() {
+                    if let DatabaseCatalogOp::DropColumn(DropColumnLog {
+                        database_id,
+                        table_id,
+                        column_id,
+                        ..
+                    }) = op
+                    {
+                        buffer.drop_column(database_id, table_id, *column_id);
+                    }
+                }
+            }
+        }
+    })
+}
+
 #[derive(Debug)]
 struct PersistJob {
     database_id: DbId,
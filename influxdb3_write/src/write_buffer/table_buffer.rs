@@ -1,10 +1,12 @@
 //! The in memory buffer of a table that can be quickly added to and queried
 
 use arrow::array::{
-    Array, ArrayRef, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder,
+    Array, ArrayRef, AsArray, BooleanBuilder, Float64Builder, Int64Builder, StringBuilder,
     StringDictionaryBuilder, TimestampNanosecondBuilder, UInt64Builder,
 };
-use arrow::datatypes::Int32Type;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int32Type};
+use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use data_types::TimestampMinMax;
 use hashbrown::{HashMap, HashSet};
@@ -75,15 +77,15 @@ impl TableBuffer {
         for sc in self.snapshotting_chunks.iter().filter(|sc| {
             filter.test_time_stamp_min_max(sc.timestamp_min_max.min, sc.timestamp_min_max.max)
         }) {
-            let cols: std::result::Result<Vec<_>, _> = schema
+            let cols: Result<Vec<_>> = schema
                 .fields()
                 .iter()
                 .map(|f| {
                     let col = sc
                         .record_batch
                         .column_by_name(f.name())
-                        .ok_or(Error::FieldNotFound(f.name().to_string()));
-                    col.cloned()
+                        .ok_or(Error::FieldNotFound(f.name().to_string()))?;
+                    Ok(cast_to_type(Arc::clone(col), f.data_type())?)
                 })
                 .collect();
             let cols = cols?;
@@ -176,6 +178,16 @@ impl TableBuffer {
     pub fn clear_snapshots(&mut self) {
         self.snapshotting_chunks.clear();
     }
+
+    /// Remove the buffered values of a column that was dropped from the table
+    ///
+    /// Snapshotting chunks are left as they are, since their columns are only read by name from
+    /// the table schema, which no longer has the dropped column.
+    pub fn drop_column(&mut self, column_id: ColumnId) {
+        for chunk in self.chunk_time_to_chunks.values_mut() {
+            chunk.data.remove(&column_id);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -225,6 +237,9 @@ impl MutableTableChunk {
             for f in &r.fields {
                 value_added.insert(f.id);
 
+                if let Some(b) = self.data.get_mut(&f.id) {
+                    b.widen_for(&f.value);
+                }
                 match &f.value {
                     FieldData::Timestamp(v) => {
                         self.timestamp_min = self.timestamp_min.min(*v);
@@ -281,10 +296,11 @@ impl MutableTableChunk {
                             int_builder.append_nulls(row_index + self.row_count);
                             Builder::I64(int_builder)
                         });
-                        if let Builder::I64(b) = b {
-                            b.append_value(*v);
-                        } else {
-                            panic!("unexpected field type");
+                        match b {
+                            Builder::I64(b) => b.append_value(*v),
+                            // the column was promoted to a float:
+                            Builder::F64(b) => b.append_value(*v as f64),
+                            _ => panic!("unexpected field type"),
                         }
                     }
                     FieldData::UInteger(v) => {
@@ -294,10 +310,11 @@ impl MutableTableChunk {
                             uint_builder.append_nulls(row_index + self.row_count);
                             Builder::U64(uint_builder)
                         });
-                        if let Builder::U64(b) = b {
-                            b.append_value(*v);
-                        } else {
-                            panic!("unexpected field type");
+                        match b {
+                            Builder::U64(b) => b.append_value(*v),
+                            // the column was promoted to a float:
+                            Builder::F64(b) => b.append_value(*v as f64),
+                            _ => panic!("unexpected field type"),
                         }
                     }
                     FieldData::Float(v) => {
@@ -355,7 +372,7 @@ impl MutableTableChunk {
                 .column_definition(f.name())
                 .expect("a valid column name");
             let b = match self.data.get(&column_def.id) {
-                Some(b) => b.as_arrow(),
+                Some(b) => cast_to_type(b.as_arrow(), f.data_type())?,
                 None => array_ref_nulls_for_type(column_def.data_type, self.row_count),
             };
 
@@ -370,15 +387,17 @@ impl MutableTableChunk {
         let mut schema_builder = SchemaBuilder::new();
         let mut cols_in_batch = HashSet::new();
         for (col_id, builder) in self.data.into_iter() {
+            // the column may have been dropped from the table since its values were buffered:
+            let Some(col_def) = table_def.column_definition_by_id(&col_id) else {
+                continue;
+            };
             cols_in_batch.insert(col_id);
-            let (col_type, col) = builder.into_influxcol_and_arrow();
-            schema_builder.influx_column(
-                table_def
-                    .column_id_to_name(&col_id)
-                    .expect("valid column id")
-                    .as_ref(),
-                col_type,
-            );
+            let (_, col) = builder.into_influxcol_and_arrow();
+            // persist the column as the type it has in the catalog, which can differ from the type
+            // its values were buffered as if the column's type was changed
+            let col = cast_to_type(col, &DataType::from(&col_def.data_type))
+                .expect("buffered values can be cast to the column's type");
+            schema_builder.influx_column(col_def.name.as_ref(), col_def.data_type);
             cols.push(col);
             schema_builder.with_series_key(&table_def.series_key_names);
         }
@@ -405,6 +424,19 @@ impl MutableTableChunk {
     }
 }
 
+/// Cast a buffered column to the type it has in the table schema
+///
+/// A column's type can change while it has data in the buffer, i.e., when an integer column is
+/// promoted to a float, or a field column is altered to a string, so the values buffered before
+/// the change have the old type.
+fn cast_to_type(col: ArrayRef, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    if col.data_type() == data_type {
+        Ok(col)
+    } else {
+        cast(&col, data_type)
+    }
+}
+
 fn array_ref_nulls_for_type(data_type: InfluxColumnType, len: usize) -> ArrayRef {
     match data_type {
         InfluxColumnType::Field(InfluxFieldType::Boolean) => {
@@ -481,6 +513,31 @@ impl Builder {
         }
     }
 
+    /// Cast the values buffered so far if the given value has a wider type than the column
+    ///
+    /// This happens when an integer or unsigned integer column is promoted to a float, or a field
+    /// column is altered to a string, while it has data in the buffer. Afterwards, the column
+    /// holds values of the wider type.
+    fn widen_for(&mut self, value: &FieldData) {
+        match (&*self, value) {
+            (Self::I64(_) | Self::U64(_), FieldData::Float(_)) => {
+                let col = cast(&self.as_arrow(), &DataType::Float64)
+                    .expect("integers can be cast to floats");
+                let mut b = Float64Builder::with_capacity(col.len());
+                b.extend(col.as_primitive::<Float64Type>());
+                *self = Self::F64(b);
+            }
+            (Self::Bool(_) | Self::I64(_) | Self::U64(_) | Self::F64(_), FieldData::String(_)) => {
+                let col =
+                    cast(&self.as_arrow(), &DataType::Utf8).expect("fields can be cast to strings");
+                let mut b = StringBuilder::new();
+                b.extend(col.as_string::<i32>());
+                *self = Self::String(b);
+            }
+            _ => (),
+        }
+    }
+
     fn append_null(&mut self) {
         match self {
             Builder::Bool(b) => b.append_null(),
@@ -555,7 +612,7 @@ mod tests {
     use arrow_util::assert_batches_sorted_eq;
     use data_types::NamespaceName;
     use datafusion::prelude::{Expr, col, lit_timestamp_nano};
-    use influxdb3_catalog::catalog::{Catalog, DatabaseSchema};
+    use influxdb3_catalog::catalog::{Catalog, CatalogArgs, DatabaseSchema};
     use iox_time::{MockProvider, Time};
     use object_store::memory::InMemory;
 
@@ -567,12 +624,22 @@ mod tests {
         const DB_NAME: &str = "test-db";
 
         async fn new() -> Self {
+            Self::new_with_args(Default::default()).await
+        }
+
+        async fn new_with_args(args: CatalogArgs) -> Self {
             let obj_store = Arc::new(InMemory::new());
             let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
             let catalog = Arc::new(
-                Catalog::new("test-node", obj_store, time_provider, Default::default())
-                    .await
-                    .expect("should initialize catalog"),
+                Catalog::new_with_args(
+                    "test-node",
+                    obj_store,
+                    time_provider,
+                    Default::default(),
+                    args,
+                )
+                .await
+                .expect("should initialize catalog"),
             );
             Self { catalog }
         }
@@ -784,4 +851,149 @@ mod tests {
             assert_batches_sorted_eq!(t.expected_output, &batches);
         }
     }
+
+    #[tokio::test]
+    async fn test_promoted_column_in_buffer() {
+        let writer =
+            TestWriter::new_with_args(CatalogArgs::default().with_type_promotion(true)).await;
+        let mut table_buffer = TableBuffer::new();
+        table_buffer.buffer_chunk(0, &writer.write_to_rows("tbl,tag=a val=1i 1", 0).await);
+        table_buffer.buffer_chunk(10, &writer.write_to_rows("tbl,tag=b val=3i 10", 0).await);
+        // promote the column while it has integers in the buffer, then write another integer:
+        table_buffer.buffer_chunk(0, &writer.write_to_rows("tbl,tag=a val=1.5 2", 0).await);
+        table_buffer.buffer_chunk(0, &writer.write_to_rows("tbl,tag=a val=4i 3", 0).await);
+
+        let table_def = writer.db_schema().table_definition("tbl").unwrap();
+        let expected = [
+            "+-----+--------------------------------+-----+",
+            "| tag | time                           | val |",
+            "+-----+--------------------------------+-----+",
+            "| a   | 1970-01-01T00:00:00.000000001Z | 1.0 |",
+            "| a   | 1970-01-01T00:00:00.000000002Z | 1.5 |",
+            "| a   | 1970-01-01T00:00:00.000000003Z | 4.0 |",
+            "| b   | 1970-01-01T00:00:00.000000010Z | 3.0 |",
+            "+-----+--------------------------------+-----+",
+        ];
+        let batches = table_buffer
+            .partitioned_record_batches(Arc::clone(&table_def), &ChunkFilter::default())
+            .unwrap()
+            .into_values()
+            .flat_map(|(_, batches)| batches)
+            .collect::<Vec<RecordBatch>>();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // snapshotted chunks are persisted with the column's promoted type:
+        let snapshot_chunks = table_buffer.snapshot(Arc::clone(&table_def), 20);
+        assert_eq!(snapshot_chunks.len(), 2);
+        for chunk in &snapshot_chunks {
+            assert_eq!(
+                chunk
+                    .record_batch
+                    .schema()
+                    .field_with_name("val")
+                    .unwrap()
+                    .data_type(),
+                &DataType::Float64
+            );
+        }
+        let batches = table_buffer
+            .partitioned_record_batches(Arc::clone(&table_def), &ChunkFilter::default())
+            .unwrap()
+            .into_values()
+            .flat_map(|(_, batches)| batches)
+            .collect::<Vec<RecordBatch>>();
+        assert_batches_sorted_eq!(expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_altered_column_in_buffer() {
+        let writer = TestWriter::new().await;
+        let mut table_buffer = TableBuffer::new();
+        table_buffer.buffer_chunk(0, &writer.write_to_rows("tbl,tag=a ok=true 1", 0).await);
+        writer
+            .catalog
+            .alter_column_type(
+                TestWriter::DB_NAME,
+                "tbl",
+                "ok",
+                influxdb3_types::http::FieldDataType::String,
+                true,
+            )
+            .await
+            .unwrap();
+        table_buffer.buffer_chunk(
+            0,
+            &writer.write_to_rows("tbl,tag=a ok=\"maybe\" 2", 0).await,
+        );
+
+        let table_def = writer.db_schema().table_definition("tbl").unwrap();
+        let batches = table_buffer
+            .partitioned_record_batches(Arc::clone(&table_def), &ChunkFilter::default())
+            .unwrap()
+            .into_values()
+            .flat_map(|(_, batches)| batches)
+            .collect::<Vec<RecordBatch>>();
+        assert_batches_sorted_eq!(
+            [
+                "+-------+-----+--------------------------------+",
+                "| ok    | tag | time                           |",
+                "+-------+-----+--------------------------------+",
+                "| maybe | a   | 1970-01-01T00:00:00.000000002Z |",
+                "| true  | a   | 1970-01-01T00:00:00.000000001Z |",
+                "+-------+-----+--------------------------------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropped_column_in_buffer() {
+        let writer = TestWriter::new().await;
+        let rows = writer.write_to_rows("tbl,tag=a val=1i,extra=2i 1", 0).await;
+        // one buffer is told about the dropped column, the other is not:
+        let mut dropped = TableBuffer::new();
+        dropped.buffer_chunk(0, &rows);
+        let mut not_dropped = TableBuffer::new();
+        not_dropped.buffer_chunk(0, &rows);
+
+        let extra_id = writer
+            .db_schema()
+            .table_definition("tbl")
+            .and_then(|table_def| table_def.column_name_to_id("extra"))
+            .unwrap();
+        writer
+            .catalog
+            .drop_column(TestWriter::DB_NAME, "tbl", "extra")
+            .await
+            .unwrap();
+        let table_def = writer.db_schema().table_definition("tbl").unwrap();
+        assert!(table_def.column_definition_by_id(&extra_id).is_none());
+        dropped.drop_column(extra_id);
+
+        let expected = [
+            "+-----+--------------------------------+-----+",
+            "| tag | time                           | val |",
+            "+-----+--------------------------------+-----+",
+            "| a   | 1970-01-01T00:00:00.000000001Z | 1   |",
+            "+-----+--------------------------------+-----+",
+        ];
+        for mut table_buffer in [dropped, not_dropped] {
+            let snapshot_chunks = table_buffer.snapshot(Arc::clone(&table_def), 10);
+            assert_eq!(snapshot_chunks.len(), 1);
+            assert!(
+                snapshot_chunks[0]
+                    .record_batch
+                    .schema()
+                    .field_with_name("extra")
+                    .is_err()
+            );
+            let batches = table_buffer
+                .partitioned_record_batches(Arc::clone(&table_def), &ChunkFilter::default())
+                .unwrap()
+                .into_values()
+                .flat_map(|(_, batches)| batches)
+                .collect::<Vec<RecordBatch>>();
+            assert_batches_sorted_eq!(expected, &batches);
+        }
+    }
 }
@@ -8,12 +8,12 @@ use influxdb3_catalog::catalog::{
 };
 
 use influxdb_line_protocol::{ParsedLine, parse_lines};
-use influxdb3_id::{DbId, TableId};
+use influxdb3_id::{ColumnId, DbId, TableId};
 use influxdb3_types::http::FieldDataType;
 use influxdb3_wal::{Field, FieldData, Gen1Duration, Row, TableChunks, WriteBatch};
 use iox_time::Time;
 use observability_deps::tracing::trace;
-use schema::TIME_COLUMN_NAME;
+use schema::{InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME};
 
 use super::Error;
 
@@ -220,7 +220,8 @@ fn validate_and_qualify_v1_line(
                 line_number: line_number + 1,
                 error_message: error.to_string(),
             })?;
-        fields.push(Field::new(col_id, field_val));
+        let value = coerce_to_column_type(txn, table_name, col_id, field_val.into());
+        fields.push(Field::new(col_id, value));
         field_count += 1;
     }
 
@@ -248,6 +249,30 @@ fn validate_and_qualify_v1_line(
     })
 }
 
+/// Convert a field value to the type of the column it is written to
+///
+/// With type promotion enabled, an integer or unsigned integer column can become a float column,
+/// after which integer writes to it are accepted by the catalog. Those values are buffered, and
+/// written to the WAL, as floats so that the column holds a single type.
+fn coerce_to_column_type(
+    txn: &DatabaseCatalogTransaction,
+    table_name: &str,
+    column_id: ColumnId,
+    value: FieldData,
+) -> FieldData {
+    let is_float_column = || {
+        txn.db_schema()
+            .table_definition(table_name)
+            .and_then(|table_def| table_def.column_definition_by_id(&column_id))
+            .is_some_and(|col| col.data_type == InfluxColumnType::Field(InfluxFieldType::Float))
+    };
+    match value {
+        FieldData::Integer(v) if is_float_column() => FieldData::Float(v as f64),
+        FieldData::UInteger(v) if is_float_column() => FieldData::Float(v as f64),
+        value => value,
+    }
+}
+
 impl WriteValidator<LinesParsed> {
     pub async fn commit_catalog_changes(
         self,
@@ -397,9 +422,9 @@ mod tests {
     use crate::{Precision, write_buffer::Error};
 
     use data_types::NamespaceName;
-    use influxdb3_catalog::catalog::Catalog;
+    use influxdb3_catalog::catalog::{Catalog, CatalogArgs};
     use influxdb3_id::TableId;
-    use influxdb3_wal::Gen1Duration;
+    use influxdb3_wal::{FieldData, Gen1Duration};
     use iox_time::{MockProvider, Time};
     use object_store::memory::InMemory;
 
@@ -487,4 +512,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn write_validator_v1_integer_to_promoted_float() -> Result<(), Error> {
+        let obj_store = Arc::new(InMemory::new());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let namespace = NamespaceName::new("test").unwrap();
+        let catalog = Arc::new(
+            Catalog::new_with_args(
+                "sample-host-id",
+                obj_store,
+                time_provider,
+                Default::default(),
+                CatalogArgs::default().with_type_promotion(true),
+            )
+            .await
+            .unwrap(),
+        );
+        for lp in ["cpu val=1i 1", "cpu val=1.5 2"] {
+            WriteValidator::initialize(namespace.clone(), Arc::clone(&catalog))?
+                .v1_parse_lines_and_catalog_updates(
+                    lp,
+                    false,
+                    Time::from_timestamp_nanos(0),
+                    Precision::Nanosecond,
+                )?
+                .commit_catalog_changes()
+                .await?
+                .unwrap_success();
+        }
+
+        // now that the field is a float, an integer write is accepted and buffered as a float:
+        let result = WriteValidator::initialize(namespace.clone(), Arc::clone(&catalog))?
+            .v1_parse_lines_and_catalog_updates(
+                "cpu val=2i 3",
+                false,
+                Time::from_timestamp_nanos(0),
+                Precision::Nanosecond,
+            )?
+            .commit_catalog_changes()
+            .await?
+            .unwrap_success()
+            .convert_lines_to_buffer(Gen1Duration::new_5m());
+        assert!(result.errors.is_empty());
+        let rows = result
+            .valid_data
+            .table_chunks
+            .get(&TableId::from(0))
+            .unwrap()
+            .chunk_time_to_chunk
+            .values()
+            .flat_map(|chunk| chunk.rows.iter())
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), 1);
+        assert!(
+            rows[0]
+                .fields
+                .iter()
+                .any(|field| field.value == FieldData::Float(2.0))
+        );
+
+        Ok(())
+    }
 }